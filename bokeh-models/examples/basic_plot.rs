@@ -31,11 +31,11 @@ fn main() {
 
     plot.add_glyph(&source, circle);
 
-    plot.add_layout(Position::Below, Layout::LinearAxis);
-    plot.add_layout(Position::Left, Layout::LinearAxis);
+    plot.add_layout(Position::Below, LinearAxis::new());
+    plot.add_layout(Position::Left, LinearAxis::new());
 
-    plot.add_tool(Tool::PanTool);
-    plot.add_tool(Tool::WheelZoomTool);
+    plot.add_tool(PanTool::new());
+    plot.add_tool(WheelZoomTool::new());
 
     let mut doc = Document::new();
     doc.add_root(plot);
@@ -0,0 +1,45 @@
+/// This example serves the same plot as `basic_plot.rs` from the embedded server. Every browser
+/// visiting http://localhost:5006/ gets its own session and document.
+use bokeh_models::server::Server;
+use bokeh_models::*;
+use std::f64::consts;
+
+fn make_document() -> Document {
+    // Build the data set
+    let x: Vec<_> = (0..100)
+        .map(|i| (4.0 * consts::PI / 100.0) * (i as f64) - (2.0 * consts::PI))
+        .collect();
+    let y: Vec<_> = x.iter().map(|xval| xval.sin()).collect();
+
+    let mut source = ColumnDataSource::new();
+    source.add("x", &x);
+    source.add("y", &y);
+
+    let mut plot = Plot::new();
+    plot.min_border = Some(80);
+
+    let mut circle = Circle::new();
+    circle.x = Some("x".to_string());
+    circle.y = Some("y".to_string());
+    circle.fill_color = Some("red".to_string());
+    circle.size = Some(5);
+    circle.line_color = Some("black".to_string());
+
+    plot.add_glyph(&source, circle);
+
+    plot.add_layout(Position::Below, LinearAxis::new());
+    plot.add_layout(Position::Left, LinearAxis::new());
+
+    plot.add_tool(PanTool::new());
+    plot.add_tool(WheelZoomTool::new());
+
+    let mut doc = Document::new();
+    doc.add_root(plot);
+    doc
+}
+
+fn main() {
+    let server = Server::bind("127.0.0.1:5006", make_document).expect("binding server");
+    println!("Serving on http://{}/", server.local_addr().unwrap());
+    server.run().expect("running server");
+}
@@ -0,0 +1,28 @@
+//! Adapters for serving Bokeh documents and applications from existing web services
+//!
+//! Each adapter is behind a feature: `bokeh-axum` for axum and `bokeh-actix` for actix-web. Both
+//! offer the same routes: `document`, serving a standalone page of a document built for every
+//! request, and `app`, serving a page which shows a new session of an application running on a
+//! `server::Server`. `signed_app` does the same for servers which sign their session ids.
+//!
+//! The pages are served without consulting the server's `AuthProvider`, but it still checks the
+//! websocket connection each page makes to the server.
+
+use crate::server::{app_html, generate_session_id};
+use crate::{file_html, Document, Result};
+
+#[cfg(feature = "bokeh-actix")]
+pub mod actix;
+#[cfg(feature = "bokeh-axum")]
+pub mod axum;
+
+fn document_page<F>(title: &str, factory: &F) -> Result<String>
+where
+    F: Fn() -> Document,
+{
+    file_html(&factory().validate()?, title)
+}
+
+fn app_page(url: &str, secret_key: Option<&str>) -> Result<String> {
+    app_html(url, &generate_session_id(secret_key))
+}
@@ -0,0 +1,126 @@
+//! Routes for actix-web
+//!
+//! ```rust,ignore
+//! let app = App::new()
+//!     .route("/plot", bokeh_models::adapters::actix::document("Plot", make_document))
+//!     .route("/app", bokeh_models::adapters::actix::app("http://localhost:5006"));
+//! ```
+
+use crate::{Document, Result};
+use actix_web::{web, HttpResponse, Route};
+
+/// Route serving a standalone page of the document built by `factory` for every request
+pub fn document<T, F>(title: T, factory: F) -> Route
+where
+    T: Into<String>,
+    F: Fn() -> Document + Clone + 'static,
+{
+    let title = title.into();
+    web::get().to(move || {
+        let page = super::document_page(&title, &factory);
+        async move { respond(page) }
+    })
+}
+
+/// Route serving a page which shows a new session of the application served at `url`
+///
+/// Session ids are not signed, so the server must not require signed sessions. Use `signed_app`
+/// for servers which do.
+pub fn app<U>(url: U) -> Route
+where
+    U: Into<String>,
+{
+    let url = url.into();
+    web::get().to(move || {
+        let page = super::app_page(&url, None);
+        async move { respond(page) }
+    })
+}
+
+/// Route serving a page which shows a new session of the application served at `url`, with a
+/// session id signed with `secret_key`
+///
+/// `secret_key` must be the key given to `Server::sign_sessions`.
+pub fn signed_app<U, K>(url: U, secret_key: K) -> Route
+where
+    U: Into<String>,
+    K: Into<String>,
+{
+    let url = url.into();
+    let secret_key = secret_key.into();
+    web::get().to(move || {
+        let page = super::app_page(&url, Some(&secret_key));
+        async move { respond(page) }
+    })
+}
+
+fn respond(page: Result<String>) -> HttpResponse {
+    match page {
+        Ok(html) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{check_session_id, generate_secret_key};
+    use crate::{Circle, ColumnDataSource, Plot};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    fn make_document() -> Document {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut doc = Document::new();
+        doc.add_root(plot);
+        doc
+    }
+
+    #[actix_web::test]
+    async fn test_document() {
+        let service = test::init_service(
+            App::new()
+                .route("/plot", document("Plot", make_document))
+                .route("/invalid", document("Invalid", Document::new)),
+        )
+        .await;
+
+        let request = test::TestRequest::get().uri("/plot").to_request();
+        let body = test::call_and_read_body(&service, request).await;
+        assert!(String::from_utf8_lossy(&body).contains(r#""type":"Circle""#));
+
+        let request = test::TestRequest::get().uri("/invalid").to_request();
+        let response = test::call_service(&service, request).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_web::test]
+    async fn test_app() {
+        let service =
+            test::init_service(App::new().route("/app", app("http://localhost:5006/"))).await;
+
+        let request = test::TestRequest::get().uri("/app").to_request();
+        let body = test::call_and_read_body(&service, request).await;
+        assert!(String::from_utf8_lossy(&body).contains(r#""http://localhost:5006""#));
+    }
+
+    #[actix_web::test]
+    async fn test_signed_app() {
+        let secret_key = generate_secret_key();
+        let service = test::init_service(App::new().route(
+            "/app",
+            signed_app("http://localhost:5006/", secret_key.clone()),
+        ))
+        .await;
+
+        let request = test::TestRequest::get().uri("/app").to_request();
+        let body = test::call_and_read_body(&service, request).await;
+        let body = String::from_utf8_lossy(&body);
+        let start = body.find(r#""sessionid":""#).unwrap() + r#""sessionid":""#.len();
+        let session_id = &body[start..start + body[start..].find('"').unwrap()];
+        assert!(check_session_id(session_id, Some(&secret_key)));
+    }
+}
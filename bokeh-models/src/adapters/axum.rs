@@ -0,0 +1,132 @@
+//! Routes for axum
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .route("/plot", bokeh_models::adapters::axum::document("Plot", make_document))
+//!     .route("/app", bokeh_models::adapters::axum::app("http://localhost:5006"));
+//! ```
+
+use crate::{Document, Result};
+use ::axum::http::StatusCode;
+use ::axum::response::{Html, IntoResponse, Response};
+use ::axum::routing::{get, MethodRouter};
+
+/// Route serving a standalone page of the document built by `factory` for every request
+pub fn document<T, F, S>(title: T, factory: F) -> MethodRouter<S>
+where
+    T: Into<String>,
+    F: Fn() -> Document + Clone + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    let title = title.into();
+    get(move || {
+        let page = super::document_page(&title, &factory);
+        async move { respond(page) }
+    })
+}
+
+/// Route serving a page which shows a new session of the application served at `url`
+///
+/// Session ids are not signed, so the server must not require signed sessions. Use `signed_app`
+/// for servers which do.
+pub fn app<U, S>(url: U) -> MethodRouter<S>
+where
+    U: Into<String>,
+    S: Clone + Send + Sync + 'static,
+{
+    let url = url.into();
+    get(move || {
+        let page = super::app_page(&url, None);
+        async move { respond(page) }
+    })
+}
+
+/// Route serving a page which shows a new session of the application served at `url`, with a
+/// session id signed with `secret_key`
+///
+/// `secret_key` must be the key given to `Server::sign_sessions`.
+pub fn signed_app<U, K, S>(url: U, secret_key: K) -> MethodRouter<S>
+where
+    U: Into<String>,
+    K: Into<String>,
+    S: Clone + Send + Sync + 'static,
+{
+    let url = url.into();
+    let secret_key = secret_key.into();
+    get(move || {
+        let page = super::app_page(&url, Some(&secret_key));
+        async move { respond(page) }
+    })
+}
+
+fn respond(page: Result<String>) -> Response {
+    match page {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{check_session_id, generate_secret_key};
+    use crate::{Circle, ColumnDataSource, Plot};
+    use ::axum::body::{to_bytes, Body};
+    use ::axum::http::Request;
+    use ::axum::Router;
+    use tower::ServiceExt;
+
+    fn make_document() -> Document {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut doc = Document::new();
+        doc.add_root(plot);
+        doc
+    }
+
+    async fn get_page(router: Router, uri: &str) -> (StatusCode, String) {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_document() {
+        let router = Router::new()
+            .route("/plot", document("Plot", make_document))
+            .route("/invalid", document("Invalid", Document::new));
+
+        let (status, body) = get_page(router.clone(), "/plot").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(r#""type":"Circle""#));
+
+        let (status, _) = get_page(router, "/invalid").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_app() {
+        let router = Router::new().route("/app", app("http://localhost:5006/"));
+
+        let (status, body) = get_page(router, "/app").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(r#""http://localhost:5006""#));
+    }
+
+    #[tokio::test]
+    async fn test_signed_app() {
+        let secret_key = generate_secret_key();
+        let router = Router::new().route(
+            "/app",
+            signed_app("http://localhost:5006/", secret_key.clone()),
+        );
+
+        let (status, body) = get_page(router, "/app").await;
+        assert_eq!(status, StatusCode::OK);
+        let start = body.find(r#""sessionid":""#).unwrap() + r#""sessionid":""#.len();
+        let session_id = &body[start..start + body[start..].find('"').unwrap()];
+        assert!(check_session_id(session_id, Some(&secret_key)));
+    }
+}
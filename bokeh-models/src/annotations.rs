@@ -0,0 +1,1488 @@
+//! Annotations, adding titles, legends and guides to a plot
+
+use crate::tools::renderer_refs;
+use crate::{ArrowHead, ColumnDataSource, Dimension, FontStyle, Id, Layout, TextAlign, ToBokeh};
+use serde_json::{json, Value};
+
+/// Style of the lines of an annotation
+#[derive(Clone, Debug, Default)]
+pub struct LineProperties {
+    /// Colour of the line
+    pub color: Option<String>,
+    /// Width of the line in pixels
+    pub width: Option<f64>,
+    /// Opacity of the line
+    pub alpha: Option<f64>,
+    /// Dash pattern of the line, as lengths in pixels of alternating dashes and gaps
+    pub dash: Option<Vec<u32>>,
+}
+
+impl LineProperties {
+    pub(crate) fn add_attributes(&self, prefix: &str, attributes: &mut Value) {
+        if let Some(color) = &self.color {
+            attributes[format!("{}_color", prefix)] = json!({ "value": color });
+        }
+        if let Some(width) = self.width {
+            attributes[format!("{}_width", prefix)] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.alpha {
+            attributes[format!("{}_alpha", prefix)] = json!({ "value": alpha });
+        }
+        if let Some(dash) = &self.dash {
+            attributes[format!("{}_dash", prefix)] = json!(dash);
+        }
+    }
+}
+
+/// Style of the fill of an annotation
+#[derive(Clone, Debug, Default)]
+pub struct FillProperties {
+    /// Colour of the fill
+    pub color: Option<String>,
+    /// Opacity of the fill
+    pub alpha: Option<f64>,
+}
+
+impl FillProperties {
+    pub(crate) fn add_attributes(&self, prefix: &str, attributes: &mut Value) {
+        if let Some(color) = &self.color {
+            attributes[format!("{}_color", prefix)] = json!({ "value": color });
+        }
+        if let Some(alpha) = self.alpha {
+            attributes[format!("{}_alpha", prefix)] = json!({ "value": alpha });
+        }
+    }
+}
+
+/// Style of the text of an annotation
+#[derive(Clone, Debug, Default)]
+pub struct TextProperties {
+    /// Font of the text
+    pub font: Option<String>,
+    /// Font size of the text, such as `"12pt"`
+    pub font_size: Option<String>,
+    /// Font style of the text
+    pub font_style: Option<FontStyle>,
+    /// Colour of the text
+    pub color: Option<String>,
+    /// Opacity of the text
+    pub alpha: Option<f64>,
+    /// Horizontal alignment of the text relative to its position, left if not set
+    pub align: Option<TextAlign>,
+}
+
+impl TextProperties {
+    pub(crate) fn add_attributes(&self, prefix: &str, attributes: &mut Value) {
+        if let Some(font) = &self.font {
+            attributes[format!("{}_font", prefix)] = json!(font);
+        }
+        if let Some(font_size) = &self.font_size {
+            attributes[format!("{}_font_size", prefix)] = json!({ "value": font_size });
+        }
+        if let Some(font_style) = self.font_style {
+            attributes[format!("{}_font_style", prefix)] = json!(font_style.name());
+        }
+        if let Some(color) = &self.color {
+            attributes[format!("{}_color", prefix)] = json!({ "value": color });
+        }
+        if let Some(alpha) = self.alpha {
+            attributes[format!("{}_alpha", prefix)] = json!({ "value": alpha });
+        }
+        if let Some(align) = self.align {
+            attributes[format!("{}_align", prefix)] = json!(align.name());
+        }
+    }
+}
+
+/// Units of a coordinate of an annotation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpatialUnits {
+    /// Data units, on the axes of the plot
+    Data,
+    /// Pixels from the bottom left of the plot area
+    Screen,
+}
+
+impl SpatialUnits {
+    fn name(self) -> &'static str {
+        match self {
+            SpatialUnits::Data => "data",
+            SpatialUnits::Screen => "screen",
+        }
+    }
+}
+
+// Title
+
+/// Title of a plot, added to one of its sides with `Plot::add_layout`
+#[derive(Clone, Default)]
+pub struct Title {
+    id: Id,
+    /// Text of the title
+    pub text: String,
+    /// Font of the title
+    pub text_font: Option<String>,
+    /// Font size of the title, such as `"14pt"`
+    pub text_font_size: Option<String>,
+    /// Font style of the title, bold if not set
+    pub text_font_style: Option<FontStyle>,
+    /// Colour of the title
+    pub text_color: Option<String>,
+    /// Alignment of the title along its side of the plot, left if not set
+    pub align: Option<TextAlign>,
+    /// Distance in pixels of the title from where its alignment puts it
+    pub offset: Option<f64>,
+}
+
+impl Title {
+    /// Create a new title reading `text`
+    pub fn new<S: Into<String>>(text: S) -> Title {
+        Title::with_id(Id::new(), text)
+    }
+
+    /// Create a new title reading `text` with a known id
+    pub fn with_id<S: Into<String>>(id: Id, text: S) -> Title {
+        Title {
+            id,
+            text: text.into(),
+            ..Title::default()
+        }
+    }
+}
+
+impl From<Title> for Layout {
+    fn from(t: Title) -> Layout {
+        Layout::Title(t)
+    }
+}
+
+impl ToBokeh for Title {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "text": self.text,
+        });
+        if let Some(font) = &self.text_font {
+            attributes["text_font"] = json!(font);
+        }
+        if let Some(font_size) = &self.text_font_size {
+            attributes["text_font_size"] = json!({ "value": font_size });
+        }
+        if let Some(font_style) = self.text_font_style {
+            attributes["text_font_style"] = json!(font_style.name());
+        }
+        if let Some(color) = &self.text_color {
+            attributes["text_color"] = json!({ "value": color });
+        }
+        if let Some(align) = self.align {
+            attributes["align"] = json!(align.name());
+        }
+        if let Some(offset) = self.offset {
+            attributes["offset"] = json!(offset);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Title",
+        })
+    }
+}
+
+// Legend
+
+/// Where a legend is placed within the plot area
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LegendLocation {
+    /// Top left corner
+    TopLeft,
+    /// Middle of the top edge
+    TopCenter,
+    /// Top right corner
+    TopRight,
+    /// Middle of the left edge
+    CenterLeft,
+    /// Middle of the plot
+    Center,
+    /// Middle of the right edge
+    CenterRight,
+    /// Bottom left corner
+    BottomLeft,
+    /// Middle of the bottom edge
+    BottomCenter,
+    /// Bottom right corner
+    BottomRight,
+    /// Position in pixels of the bottom left corner of the legend, from the bottom left of the
+    /// plot area
+    Point(f64, f64),
+}
+
+impl LegendLocation {
+    fn value(self) -> Value {
+        match self {
+            LegendLocation::TopLeft => json!("top_left"),
+            LegendLocation::TopCenter => json!("top_center"),
+            LegendLocation::TopRight => json!("top_right"),
+            LegendLocation::CenterLeft => json!("center_left"),
+            LegendLocation::Center => json!("center"),
+            LegendLocation::CenterRight => json!("center_right"),
+            LegendLocation::BottomLeft => json!("bottom_left"),
+            LegendLocation::BottomCenter => json!("bottom_center"),
+            LegendLocation::BottomRight => json!("bottom_right"),
+            LegendLocation::Point(x, y) => json!([x, y]),
+        }
+    }
+}
+
+/// Direction a legend lists its items in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegendOrientation {
+    /// One item above another
+    Vertical,
+    /// One item beside another
+    Horizontal,
+}
+
+impl LegendOrientation {
+    fn name(self) -> &'static str {
+        match self {
+            LegendOrientation::Vertical => "vertical",
+            LegendOrientation::Horizontal => "horizontal",
+        }
+    }
+}
+
+/// What happens when a legend item is clicked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickPolicy {
+    /// Nothing
+    None,
+    /// Hide or show the renderers of the item
+    Hide,
+    /// Mute or unmute the renderers of the item, drawing their `muted_glyph` while muted
+    Mute,
+}
+
+impl ClickPolicy {
+    fn name(self) -> &'static str {
+        match self {
+            ClickPolicy::None => "none",
+            ClickPolicy::Hide => "hide",
+            ClickPolicy::Mute => "mute",
+        }
+    }
+}
+
+/// Label of a legend item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LegendLabel {
+    /// Fixed text
+    Value(String),
+    /// Values of a column of the data source, with an item for each distinct value
+    Field(String),
+}
+
+impl LegendLabel {
+    fn value(&self) -> Value {
+        match self {
+            LegendLabel::Value(value) => json!({ "value": value }),
+            LegendLabel::Field(field) => json!({ "field": field }),
+        }
+    }
+}
+
+/// Entry of a legend, showing the glyphs of its renderers next to its label
+#[derive(Clone)]
+pub struct LegendItem {
+    id: Id,
+    /// Label of the item
+    pub label: LegendLabel,
+    renderers: Vec<Id>,
+    /// Row of the data source whose glyph is shown, all of them if not set
+    pub index: Option<usize>,
+}
+
+impl LegendItem {
+    /// Create a new legend item labelled `label`
+    pub fn new(label: LegendLabel) -> LegendItem {
+        LegendItem::with_id(Id::new(), label)
+    }
+
+    /// Create a new legend item labelled `label` with a known id
+    pub fn with_id(id: Id, label: LegendLabel) -> LegendItem {
+        LegendItem {
+            id,
+            label,
+            renderers: Vec::new(),
+            index: None,
+        }
+    }
+
+    /// Show the glyph of `renderer` in the item
+    ///
+    /// `renderer` is the id returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl ToBokeh for LegendItem {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "label": self.label.value(),
+            "renderers": renderer_refs(&self.renderers),
+        });
+        if let Some(index) = self.index {
+            attributes["index"] = json!(index);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "LegendItem",
+        })
+    }
+}
+
+/// Legend of a plot
+///
+/// Each plot has a legend, which is drawn when it has items. Besides the items added with
+/// `add_item`, it gets an item for each glyph renderer with a `legend_label`, `legend_field` or
+/// `legend_group`. Further legends with their own items can be added with `Plot::add_layout`.
+#[derive(Clone, Default)]
+pub struct Legend {
+    id: Id,
+    items: Vec<LegendItem>,
+    /// Where the legend is placed, top right if not set
+    pub location: Option<LegendLocation>,
+    /// Font of the labels
+    pub label_text_font: Option<String>,
+    /// Font size of the labels, such as `"10pt"`
+    pub label_text_font_size: Option<String>,
+    /// Colour of the labels
+    pub label_text_color: Option<String>,
+    /// What happens when an item is clicked, nothing if not set
+    pub click_policy: Option<ClickPolicy>,
+    /// Direction the items are listed in, vertical if not set
+    pub orientation: Option<LegendOrientation>,
+    /// Space in pixels between items
+    pub spacing: Option<u32>,
+    /// Space in pixels between the items and the border of the legend
+    pub padding: Option<u32>,
+    /// Space in pixels between the legend and the edge of the plot
+    pub margin: Option<u32>,
+    /// Space in pixels between the glyph and the label of an item
+    pub label_standoff: Option<u32>,
+}
+
+impl Legend {
+    /// Create a new empty legend
+    pub fn new() -> Legend {
+        Legend::default()
+    }
+
+    /// Create a new empty legend with a known id
+    pub fn with_id(id: Id) -> Legend {
+        Legend {
+            id,
+            ..Legend::default()
+        }
+    }
+
+    /// Add an item to the legend
+    pub fn add_item(&mut self, item: LegendItem) {
+        self.items.push(item);
+    }
+
+    /// Items added to the legend with `add_item`
+    pub fn items(&self) -> &[LegendItem] {
+        &self.items
+    }
+
+    pub(crate) fn value_with_items(&self, items: &[LegendItem]) -> Value {
+        let mut attributes = json!({
+            "items": items.iter().map(|item| item.as_bokeh_ref()).collect::<Vec<_>>(),
+        });
+        if let Some(location) = self.location {
+            attributes["location"] = location.value();
+        }
+        if let Some(font) = &self.label_text_font {
+            attributes["label_text_font"] = json!(font);
+        }
+        if let Some(font_size) = &self.label_text_font_size {
+            attributes["label_text_font_size"] = json!({ "value": font_size });
+        }
+        if let Some(color) = &self.label_text_color {
+            attributes["label_text_color"] = json!({ "value": color });
+        }
+        if let Some(click_policy) = self.click_policy {
+            attributes["click_policy"] = json!(click_policy.name());
+        }
+        if let Some(orientation) = self.orientation {
+            attributes["orientation"] = json!(orientation.name());
+        }
+        if let Some(spacing) = self.spacing {
+            attributes["spacing"] = json!(spacing);
+        }
+        if let Some(padding) = self.padding {
+            attributes["padding"] = json!(padding);
+        }
+        if let Some(margin) = self.margin {
+            attributes["margin"] = json!(margin);
+        }
+        if let Some(label_standoff) = self.label_standoff {
+            attributes["label_standoff"] = json!(label_standoff);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Legend",
+        })
+    }
+}
+
+impl From<Legend> for Layout {
+    fn from(l: Legend) -> Layout {
+        Layout::Legend(l)
+    }
+}
+
+impl ToBokeh for Legend {
+    fn as_bokeh_value(&self) -> Value {
+        self.value_with_items(&self.items)
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.items.iter().map(|item| item.as_bokeh_value()));
+        out
+    }
+}
+
+// Span
+
+/// Line across the whole plot at a fixed location, such as a threshold or the time of an event
+///
+/// Spans are added within the plot area with `Plot::add_layout(Position::Center, span)`.
+#[derive(Clone)]
+pub struct Span {
+    id: Id,
+    /// Dimension the line runs along, so `Dimension::Width` gives a horizontal line
+    pub dimension: Dimension,
+    /// Location of the line in data units, on the y axis for a horizontal line
+    pub location: f64,
+    /// Style of the line
+    pub line: LineProperties,
+}
+
+impl Span {
+    /// Create a new span along `dimension` at `location`
+    pub fn new(dimension: Dimension, location: f64) -> Span {
+        Span::with_id(Id::new(), dimension, location)
+    }
+
+    /// Create a new span along `dimension` at `location` with a known id
+    pub fn with_id(id: Id, dimension: Dimension, location: f64) -> Span {
+        Span {
+            id,
+            dimension,
+            location,
+            line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<Span> for Layout {
+    fn from(s: Span) -> Layout {
+        Layout::Span(s)
+    }
+}
+
+impl ToBokeh for Span {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "dimension": self.dimension.name(),
+            "location": self.location,
+        });
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Span",
+        })
+    }
+}
+
+// BoxAnnotation
+
+/// Shaded box within the plot, such as an alert band or a window of time
+///
+/// Each edge is at a location in data units, or at the edge of the plot if not set, so setting
+/// only `bottom` and `top` gives a band across the whole plot.
+#[derive(Clone, Default)]
+pub struct BoxAnnotation {
+    id: Id,
+    /// Location of the left edge on the x axis
+    pub left: Option<f64>,
+    /// Location of the right edge on the x axis
+    pub right: Option<f64>,
+    /// Location of the top edge on the y axis
+    pub top: Option<f64>,
+    /// Location of the bottom edge on the y axis
+    pub bottom: Option<f64>,
+    /// Style of the fill
+    pub fill: FillProperties,
+    /// Style of the outline
+    pub line: LineProperties,
+}
+
+impl BoxAnnotation {
+    /// Create a new box covering the whole plot
+    pub fn new() -> BoxAnnotation {
+        BoxAnnotation::default()
+    }
+
+    /// Create a new box covering the whole plot with a known id
+    pub fn with_id(id: Id) -> BoxAnnotation {
+        BoxAnnotation {
+            id,
+            ..BoxAnnotation::default()
+        }
+    }
+}
+
+impl From<BoxAnnotation> for Layout {
+    fn from(b: BoxAnnotation) -> Layout {
+        Layout::BoxAnnotation(b)
+    }
+}
+
+impl ToBokeh for BoxAnnotation {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(left) = self.left {
+            attributes["left"] = json!(left);
+        }
+        if let Some(right) = self.right {
+            attributes["right"] = json!(right);
+        }
+        if let Some(top) = self.top {
+            attributes["top"] = json!(top);
+        }
+        if let Some(bottom) = self.bottom {
+            attributes["bottom"] = json!(bottom);
+        }
+        self.fill.add_attributes("fill", &mut attributes);
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "BoxAnnotation",
+        })
+    }
+}
+
+// Label
+
+/// Text at a point of the plot, for calling out a particular value
+#[derive(Clone)]
+pub struct Label {
+    id: Id,
+    /// Location of the label on the x axis
+    pub x: f64,
+    /// Location of the label on the y axis
+    pub y: f64,
+    /// Text of the label
+    pub text: String,
+    /// Units of `x`, data units if not set
+    pub x_units: Option<SpatialUnits>,
+    /// Units of `y`, data units if not set
+    pub y_units: Option<SpatialUnits>,
+    /// Distance in pixels to move the label right of `x`
+    pub x_offset: Option<f64>,
+    /// Distance in pixels to move the label above `y`
+    pub y_offset: Option<f64>,
+    /// Angle of the label in radians, anticlockwise from horizontal
+    pub angle: Option<f64>,
+    /// Style of the text
+    pub text_properties: TextProperties,
+    /// Fill of the box behind the text, which is not drawn if not set
+    pub background_fill: FillProperties,
+    /// Outline of the box behind the text, which is not drawn if not set
+    pub border_line: LineProperties,
+}
+
+impl Label {
+    /// Create a new label reading `text` at `(x, y)`
+    pub fn new<S: Into<String>>(x: f64, y: f64, text: S) -> Label {
+        Label::with_id(Id::new(), x, y, text)
+    }
+
+    /// Create a new label reading `text` at `(x, y)` with a known id
+    pub fn with_id<S: Into<String>>(id: Id, x: f64, y: f64, text: S) -> Label {
+        Label {
+            id,
+            x,
+            y,
+            text: text.into(),
+            x_units: None,
+            y_units: None,
+            x_offset: None,
+            y_offset: None,
+            angle: None,
+            text_properties: TextProperties::default(),
+            background_fill: FillProperties::default(),
+            border_line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<Label> for Layout {
+    fn from(l: Label) -> Layout {
+        Layout::Label(l)
+    }
+}
+
+impl ToBokeh for Label {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "text": self.text,
+            "x": self.x,
+            "y": self.y,
+        });
+        if let Some(x_units) = self.x_units {
+            attributes["x_units"] = json!(x_units.name());
+        }
+        if let Some(y_units) = self.y_units {
+            attributes["y_units"] = json!(y_units.name());
+        }
+        if let Some(x_offset) = self.x_offset {
+            attributes["x_offset"] = json!(x_offset);
+        }
+        if let Some(y_offset) = self.y_offset {
+            attributes["y_offset"] = json!(y_offset);
+        }
+        if let Some(angle) = self.angle {
+            attributes["angle"] = json!(angle);
+        }
+        self.text_properties.add_attributes("text", &mut attributes);
+        self.background_fill
+            .add_attributes("background_fill", &mut attributes);
+        self.border_line
+            .add_attributes("border_line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Label",
+        })
+    }
+}
+
+// LabelSet
+
+/// Text at each row of a data source, such as a value above every bar of a chart
+#[derive(Clone)]
+pub struct LabelSet {
+    id: Id,
+    pub(crate) source: ColumnDataSource,
+    /// Column of locations on the x axis
+    pub x: String,
+    /// Column of locations on the y axis
+    pub y: String,
+    /// Column of the text of the labels
+    pub text: String,
+    /// Units of the `x` column, data units if not set
+    pub x_units: Option<SpatialUnits>,
+    /// Units of the `y` column, data units if not set
+    pub y_units: Option<SpatialUnits>,
+    /// Distance in pixels to move each label right of its `x`
+    pub x_offset: Option<f64>,
+    /// Distance in pixels to move each label above its `y`
+    pub y_offset: Option<f64>,
+    /// Angle of the labels in radians, anticlockwise from horizontal
+    pub angle: Option<f64>,
+    /// Style of the text
+    pub text_properties: TextProperties,
+    /// Fill of the box behind each label, which is not drawn if not set
+    pub background_fill: FillProperties,
+    /// Outline of the box behind each label, which is not drawn if not set
+    pub border_line: LineProperties,
+}
+
+impl LabelSet {
+    /// Create a new set of labels with text from the `text` column of `source`, at the
+    /// locations in its `x` and `y` columns
+    pub fn new<S>(source: &ColumnDataSource, x: S, y: S, text: S) -> LabelSet
+    where
+        S: Into<String>,
+    {
+        LabelSet::with_id(Id::new(), source, x, y, text)
+    }
+
+    /// Create a new set of labels from the columns of `source` with a known id
+    pub fn with_id<S>(id: Id, source: &ColumnDataSource, x: S, y: S, text: S) -> LabelSet
+    where
+        S: Into<String>,
+    {
+        LabelSet {
+            id,
+            source: source.clone(),
+            x: x.into(),
+            y: y.into(),
+            text: text.into(),
+            x_units: None,
+            y_units: None,
+            x_offset: None,
+            y_offset: None,
+            angle: None,
+            text_properties: TextProperties::default(),
+            background_fill: FillProperties::default(),
+            border_line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<LabelSet> for Layout {
+    fn from(l: LabelSet) -> Layout {
+        Layout::LabelSet(l)
+    }
+}
+
+impl ToBokeh for LabelSet {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "source": self.source.as_bokeh_ref(),
+            "text": { "field": self.text },
+            "x": { "field": self.x },
+            "y": { "field": self.y },
+        });
+        if let Some(x_units) = self.x_units {
+            attributes["x_units"] = json!(x_units.name());
+        }
+        if let Some(y_units) = self.y_units {
+            attributes["y_units"] = json!(y_units.name());
+        }
+        if let Some(x_offset) = self.x_offset {
+            attributes["x_offset"] = json!({ "value": x_offset });
+        }
+        if let Some(y_offset) = self.y_offset {
+            attributes["y_offset"] = json!({ "value": y_offset });
+        }
+        if let Some(angle) = self.angle {
+            attributes["angle"] = json!({ "units": "rad", "value": angle });
+        }
+        self.text_properties.add_attributes("text", &mut attributes);
+        self.background_fill
+            .add_attributes("background_fill", &mut attributes);
+        self.border_line
+            .add_attributes("border_line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "LabelSet",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.source.references());
+        out
+    }
+}
+
+// Arrow
+
+/// Arrow between two points of the plot
+#[derive(Clone)]
+pub struct Arrow {
+    id: Id,
+    /// Start of the arrow on the x axis
+    pub x_start: f64,
+    /// Start of the arrow on the y axis
+    pub y_start: f64,
+    /// End of the arrow on the x axis
+    pub x_end: f64,
+    /// End of the arrow on the y axis
+    pub y_end: f64,
+    /// Head at the start of the arrow, none if not set
+    pub start: Option<ArrowHead>,
+    /// Head at the end of the arrow, an `OpenHead` if not set
+    pub end: Option<ArrowHead>,
+    /// Style of the line
+    pub line: LineProperties,
+}
+
+impl Arrow {
+    /// Create a new arrow from `(x_start, y_start)` to `(x_end, y_end)`
+    pub fn new(x_start: f64, y_start: f64, x_end: f64, y_end: f64) -> Arrow {
+        Arrow::with_id(Id::new(), x_start, y_start, x_end, y_end)
+    }
+
+    /// Create a new arrow from `(x_start, y_start)` to `(x_end, y_end)` with a known id
+    pub fn with_id(id: Id, x_start: f64, y_start: f64, x_end: f64, y_end: f64) -> Arrow {
+        Arrow {
+            id,
+            x_start,
+            y_start,
+            x_end,
+            y_end,
+            start: None,
+            end: None,
+            line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<Arrow> for Layout {
+    fn from(a: Arrow) -> Layout {
+        Layout::Arrow(a)
+    }
+}
+
+impl ToBokeh for Arrow {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "x_end": self.x_end,
+            "x_start": self.x_start,
+            "y_end": self.y_end,
+            "y_start": self.y_start,
+        });
+        if let Some(start) = &self.start {
+            attributes["start"] = start.as_bokeh_ref();
+        }
+        if let Some(end) = &self.end {
+            attributes["end"] = end.as_bokeh_ref();
+        }
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Arrow",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for head in self.start.iter().chain(&self.end) {
+            out.extend(head.references());
+        }
+        out
+    }
+}
+
+// Band
+
+/// Shaded region between the `lower` and `upper` columns of a data source, such as a confidence
+/// interval
+#[derive(Clone)]
+pub struct Band {
+    id: Id,
+    pub(crate) source: ColumnDataSource,
+    /// Column of locations along the band
+    pub base: String,
+    /// Column of the lower edge of the band
+    pub lower: String,
+    /// Column of the upper edge of the band
+    pub upper: String,
+    /// Dimension the band spans at each base location, so `Dimension::Height` takes `base` from
+    /// the x axis. Height if not set
+    pub dimension: Option<Dimension>,
+    /// Fill of the band
+    pub fill: FillProperties,
+    /// Style of the edges of the band
+    pub line: LineProperties,
+}
+
+impl Band {
+    /// Create a new band from the `base`, `lower` and `upper` columns of `source`
+    pub fn new<S>(source: &ColumnDataSource, base: S, lower: S, upper: S) -> Band
+    where
+        S: Into<String>,
+    {
+        Band::with_id(Id::new(), source, base, lower, upper)
+    }
+
+    /// Create a new band from the columns of `source` with a known id
+    pub fn with_id<S>(id: Id, source: &ColumnDataSource, base: S, lower: S, upper: S) -> Band
+    where
+        S: Into<String>,
+    {
+        Band {
+            id,
+            source: source.clone(),
+            base: base.into(),
+            lower: lower.into(),
+            upper: upper.into(),
+            dimension: None,
+            fill: FillProperties::default(),
+            line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<Band> for Layout {
+    fn from(b: Band) -> Layout {
+        Layout::Band(b)
+    }
+}
+
+impl ToBokeh for Band {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "base": { "field": self.base },
+            "lower": { "field": self.lower },
+            "source": self.source.as_bokeh_ref(),
+            "upper": { "field": self.upper },
+        });
+        if let Some(dimension) = self.dimension {
+            attributes["dimension"] = json!(dimension.name());
+        }
+        self.fill.add_attributes("fill", &mut attributes);
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Band",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.source.references());
+        out
+    }
+}
+
+// Whisker
+
+/// Error bars from the `lower` to the `upper` columns of a data source, with caps at each end
+#[derive(Clone)]
+pub struct Whisker {
+    id: Id,
+    pub(crate) source: ColumnDataSource,
+    /// Column of locations of the whiskers
+    pub base: String,
+    /// Column of the lower ends of the whiskers
+    pub lower: String,
+    /// Column of the upper ends of the whiskers
+    pub upper: String,
+    /// Dimension the whiskers span at each base location, so `Dimension::Height` takes `base`
+    /// from the x axis. Height if not set
+    pub dimension: Option<Dimension>,
+    /// Cap at the lower ends, a `TeeHead` if not set
+    pub lower_head: Option<ArrowHead>,
+    /// Cap at the upper ends, a `TeeHead` if not set
+    pub upper_head: Option<ArrowHead>,
+    /// Style of the whisker lines
+    pub line: LineProperties,
+}
+
+impl Whisker {
+    /// Create a new set of whiskers from the `base`, `lower` and `upper` columns of `source`
+    pub fn new<S>(source: &ColumnDataSource, base: S, lower: S, upper: S) -> Whisker
+    where
+        S: Into<String>,
+    {
+        Whisker::with_id(Id::new(), source, base, lower, upper)
+    }
+
+    /// Create a new set of whiskers from the columns of `source` with a known id
+    pub fn with_id<S>(id: Id, source: &ColumnDataSource, base: S, lower: S, upper: S) -> Whisker
+    where
+        S: Into<String>,
+    {
+        Whisker {
+            id,
+            source: source.clone(),
+            base: base.into(),
+            lower: lower.into(),
+            upper: upper.into(),
+            dimension: None,
+            lower_head: None,
+            upper_head: None,
+            line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<Whisker> for Layout {
+    fn from(w: Whisker) -> Layout {
+        Layout::Whisker(w)
+    }
+}
+
+impl ToBokeh for Whisker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "base": { "field": self.base },
+            "lower": { "field": self.lower },
+            "source": self.source.as_bokeh_ref(),
+            "upper": { "field": self.upper },
+        });
+        if let Some(dimension) = self.dimension {
+            attributes["dimension"] = json!(dimension.name());
+        }
+        if let Some(head) = &self.lower_head {
+            attributes["lower_head"] = head.as_bokeh_ref();
+        }
+        if let Some(head) = &self.upper_head {
+            attributes["upper_head"] = head.as_bokeh_ref();
+        }
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Whisker",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.source.references());
+        for head in self.lower_head.iter().chain(&self.upper_head) {
+            out.extend(head.references());
+        }
+        out
+    }
+}
+
+// Slope
+
+/// Line across the whole plot with a given gradient and intercept, such as a fitted trend or the
+/// diagonal `y = x`
+#[derive(Clone)]
+pub struct Slope {
+    id: Id,
+    /// Gradient of the line, in data units
+    pub gradient: f64,
+    /// Value on the y axis where the line crosses `x = 0`
+    pub y_intercept: f64,
+    /// Style of the line
+    pub line: LineProperties,
+}
+
+impl Slope {
+    /// Create a new line with `gradient` crossing the y axis at `y_intercept`
+    pub fn new(gradient: f64, y_intercept: f64) -> Slope {
+        Slope::with_id(Id::new(), gradient, y_intercept)
+    }
+
+    /// Create a new line with `gradient` crossing the y axis at `y_intercept` with a known id
+    pub fn with_id(id: Id, gradient: f64, y_intercept: f64) -> Slope {
+        Slope {
+            id,
+            gradient,
+            y_intercept,
+            line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<Slope> for Layout {
+    fn from(s: Slope) -> Layout {
+        Layout::Slope(s)
+    }
+}
+
+impl ToBokeh for Slope {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "gradient": self.gradient,
+            "y_intercept": self.y_intercept,
+        });
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Slope",
+        })
+    }
+}
+
+// PolyAnnotation
+
+/// Shaded polygon over a fixed region of the plot
+#[derive(Clone)]
+pub struct PolyAnnotation {
+    id: Id,
+    /// Locations of the corners on the x axis
+    pub xs: Vec<f64>,
+    /// Locations of the corners on the y axis
+    pub ys: Vec<f64>,
+    /// Units of `xs`, data units if not set
+    pub xs_units: Option<SpatialUnits>,
+    /// Units of `ys`, data units if not set
+    pub ys_units: Option<SpatialUnits>,
+    /// Fill of the polygon
+    pub fill: FillProperties,
+    /// Style of the outline
+    pub line: LineProperties,
+}
+
+impl PolyAnnotation {
+    /// Create a new polygon with corners at `xs` and `ys`
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>) -> PolyAnnotation {
+        PolyAnnotation::with_id(Id::new(), xs, ys)
+    }
+
+    /// Create a new polygon with corners at `xs` and `ys` with a known id
+    pub fn with_id(id: Id, xs: Vec<f64>, ys: Vec<f64>) -> PolyAnnotation {
+        PolyAnnotation {
+            id,
+            xs,
+            ys,
+            xs_units: None,
+            ys_units: None,
+            fill: FillProperties::default(),
+            line: LineProperties::default(),
+        }
+    }
+}
+
+impl From<PolyAnnotation> for Layout {
+    fn from(p: PolyAnnotation) -> Layout {
+        Layout::PolyAnnotation(p)
+    }
+}
+
+impl ToBokeh for PolyAnnotation {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "xs": self.xs,
+            "ys": self.ys,
+        });
+        if let Some(xs_units) = self.xs_units {
+            attributes["xs_units"] = json!(xs_units.name());
+        }
+        if let Some(ys_units) = self.ys_units {
+            attributes["ys_units"] = json!(ys_units.name());
+        }
+        self.fill.add_attributes("fill", &mut attributes);
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "PolyAnnotation",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LinearAxis, Plot, Position, TeeHead, VeeHead};
+
+    #[test]
+    fn test_legend() {
+        let mut item = LegendItem::with_id(Id::from("1002"), LegendLabel::Field("kind".into()));
+        item.add_renderer(&Id::from("1003"));
+        let mut legend = Legend::with_id(Id::from("1001"));
+        legend.add_item(item);
+        legend.location = Some(LegendLocation::Point(10.0, 20.0));
+        legend.click_policy = Some(ClickPolicy::Hide);
+        legend.orientation = Some(LegendOrientation::Horizontal);
+        legend.spacing = Some(8);
+
+        let references = legend.references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "click_policy": "hide",
+                    "items": [{"id": "1002", "type": "LegendItem"}],
+                    "location": [10.0, 20.0],
+                    "orientation": "horizontal",
+                    "spacing": 8,
+                },
+                "id": "1001",
+                "type": "Legend",
+            })
+        );
+        assert_eq!(
+            references[1]["attributes"],
+            json!({
+                "label": {"field": "kind"},
+                "renderers": [{"id": "1003", "type": "GlyphRenderer"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let mut span = Span::with_id(Id::from("1001"), Dimension::Height, 3.5);
+        span.line.color = Some("red".to_string());
+        span.line.dash = Some(vec![6, 2]);
+        assert_eq!(
+            Layout::from(span.clone()).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "dimension": "height",
+                    "line_color": {"value": "red"},
+                    "line_dash": [6, 2],
+                    "location": 3.5,
+                },
+                "id": "1001",
+                "type": "Span",
+            })
+        );
+
+        let mut plot = Plot::new();
+        plot.add_layout(Position::Center, span);
+        let attributes = plot.as_bokeh_value()["attributes"].clone();
+        assert_eq!(
+            attributes["renderers"][0],
+            json!({"id": "1001", "type": "Span"})
+        );
+        assert!(attributes.get("center").is_none());
+    }
+
+    #[test]
+    fn test_box_annotation() {
+        let mut band = BoxAnnotation::with_id(Id::from("1001"));
+        band.bottom = Some(80.0);
+        band.fill.color = Some("red".to_string());
+        band.fill.alpha = Some(0.1);
+        band.line.width = Some(0.0);
+        assert_eq!(
+            Layout::from(band).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "bottom": 80.0,
+                    "fill_alpha": {"value": 0.1},
+                    "fill_color": {"value": "red"},
+                    "line_width": {"value": 0.0},
+                },
+                "id": "1001",
+                "type": "BoxAnnotation",
+            })
+        );
+    }
+
+    #[test]
+    fn test_label() {
+        let mut label = Label::with_id(Id::from("1001"), 10.0, 20.0, "Peak");
+        label.x_units = Some(SpatialUnits::Screen);
+        label.y_offset = Some(5.0);
+        label.angle = Some(0.5);
+        label.text_properties.font_size = Some("9pt".to_string());
+        label.text_properties.align = Some(TextAlign::Center);
+        label.background_fill.color = Some("white".to_string());
+        label.border_line.color = Some("black".to_string());
+        assert_eq!(
+            Layout::from(label).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "angle": 0.5,
+                    "background_fill_color": {"value": "white"},
+                    "border_line_color": {"value": "black"},
+                    "text": "Peak",
+                    "text_align": "center",
+                    "text_font_size": {"value": "9pt"},
+                    "x": 10.0,
+                    "x_units": "screen",
+                    "y": 20.0,
+                    "y_offset": 5.0,
+                },
+                "id": "1001",
+                "type": "Label",
+            })
+        );
+    }
+
+    #[test]
+    fn test_label_set() {
+        let mut source = ColumnDataSource::with_id(Id::from("1002"));
+        source.add("x", &[1.0, 2.0]);
+        source.add("count", &[3.0, 5.0]);
+        source.add_factors("names", vec!["apples", "pears"]);
+        let mut labels = LabelSet::with_id(Id::from("1001"), &source, "x", "count", "names");
+        labels.y_offset = Some(4.0);
+        labels.text_properties.align = Some(TextAlign::Center);
+
+        let references = Layout::from(labels).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "source": {"id": "1002", "type": "ColumnDataSource"},
+                    "text": {"field": "names"},
+                    "text_align": "center",
+                    "x": {"field": "x"},
+                    "y": {"field": "count"},
+                    "y_offset": {"value": 4.0},
+                },
+                "id": "1001",
+                "type": "LabelSet",
+            })
+        );
+        assert!(references.iter().any(|r| r["id"] == "1002"));
+    }
+
+    #[test]
+    fn test_arrow() {
+        let mut arrow = Arrow::with_id(Id::from("1001"), 0.0, 0.0, 1.0, 2.0);
+        arrow.end = Some(VeeHead::with_id(Id::from("1002")).into());
+        arrow.line.width = Some(2.0);
+
+        let references = Layout::from(arrow).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "end": {"id": "1002", "type": "VeeHead"},
+                    "line_width": {"value": 2.0},
+                    "x_end": 1.0,
+                    "x_start": 0.0,
+                    "y_end": 2.0,
+                    "y_start": 0.0,
+                },
+                "id": "1001",
+                "type": "Arrow",
+            })
+        );
+        assert_eq!(references[1]["type"], "VeeHead");
+    }
+
+    #[test]
+    fn test_band() {
+        let mut source = ColumnDataSource::with_id(Id::from("1002"));
+        source.add("x", &[1.0, 2.0]);
+        source.add("lower", &[0.5, 1.5]);
+        source.add("upper", &[1.5, 2.5]);
+
+        let mut band = Band::with_id(Id::from("1001"), &source, "x", "lower", "upper");
+        band.fill.alpha = Some(0.2);
+
+        let references = Layout::from(band).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "base": {"field": "x"},
+                    "fill_alpha": {"value": 0.2},
+                    "lower": {"field": "lower"},
+                    "source": {"id": "1002", "type": "ColumnDataSource"},
+                    "upper": {"field": "upper"},
+                },
+                "id": "1001",
+                "type": "Band",
+            })
+        );
+        assert_eq!(references[1]["type"], "ColumnDataSource");
+    }
+
+    #[test]
+    fn test_whisker() {
+        let mut source = ColumnDataSource::with_id(Id::from("1002"));
+        source.add("x", &[1.0, 2.0]);
+        source.add("lower", &[0.5, 1.5]);
+        source.add("upper", &[1.5, 2.5]);
+
+        let mut whisker = Whisker::with_id(Id::from("1001"), &source, "x", "lower", "upper");
+        let mut head = TeeHead::with_id(Id::from("1003"));
+        head.size = Some(5.0);
+        whisker.lower_head = Some(head.clone().into());
+        whisker.upper_head = Some(head.into());
+
+        let references = Layout::from(whisker).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "base": {"field": "x"},
+                    "lower": {"field": "lower"},
+                    "lower_head": {"id": "1003", "type": "TeeHead"},
+                    "source": {"id": "1002", "type": "ColumnDataSource"},
+                    "upper": {"field": "upper"},
+                    "upper_head": {"id": "1003", "type": "TeeHead"},
+                },
+                "id": "1001",
+                "type": "Whisker",
+            })
+        );
+        assert_eq!(references[1]["type"], "ColumnDataSource");
+        let head = references.iter().find(|r| r["type"] == "TeeHead").unwrap();
+        assert_eq!(head["attributes"]["size"], 5.0);
+    }
+
+    #[test]
+    fn test_slope() {
+        let mut slope = Slope::with_id(Id::from("1001"), 1.0, 0.0);
+        slope.line.dash = Some(vec![4, 4]);
+
+        assert_eq!(
+            Layout::from(slope).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "gradient": 1.0,
+                    "line_dash": [4, 4],
+                    "y_intercept": 0.0,
+                },
+                "id": "1001",
+                "type": "Slope",
+            })
+        );
+    }
+
+    #[test]
+    fn test_poly_annotation() {
+        let mut poly = PolyAnnotation::with_id(
+            Id::from("1001"),
+            vec![0.0, 100.0, 100.0],
+            vec![0.0, 0.0, 50.0],
+        );
+        poly.xs_units = Some(SpatialUnits::Screen);
+        poly.fill.color = Some("green".to_string());
+
+        assert_eq!(
+            Layout::from(poly).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "fill_color": {"value": "green"},
+                    "xs": [0.0, 100.0, 100.0],
+                    "xs_units": "screen",
+                    "ys": [0.0, 0.0, 50.0],
+                },
+                "id": "1001",
+                "type": "PolyAnnotation",
+            })
+        );
+    }
+
+    #[test]
+    fn test_title() {
+        let mut title = Title::with_id(Id::from("1001"), "Rainfall");
+        title.text_font_size = Some("16pt".to_string());
+        title.text_font_style = Some(FontStyle::Italic);
+        title.align = Some(TextAlign::Center);
+        assert_eq!(
+            Layout::from(title.clone()).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "align": "center",
+                    "text": "Rainfall",
+                    "text_font_size": {"value": "16pt"},
+                    "text_font_style": "italic",
+                },
+                "id": "1001",
+                "type": "Title",
+            })
+        );
+
+        let mut plot = Plot::new();
+        plot.add_layout(Position::Above, LinearAxis::new());
+        plot.add_layout(Position::Above, title);
+        let above = &plot.as_bokeh_value()["attributes"]["above"];
+        assert_eq!(above[0]["type"], "LinearAxis");
+        assert_eq!(above[1], json!({"id": "1001", "type": "Title"}));
+    }
+}
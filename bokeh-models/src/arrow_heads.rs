@@ -0,0 +1,254 @@
+//! Arrowheads, drawn at the ends of an `Arrow` or `Whisker`
+
+use crate::{FillProperties, Id, LineProperties, ToBokeh};
+use serde_json::{json, Value};
+
+/// Heads at the ends of arrows and whiskers
+#[derive(Clone)]
+pub enum ArrowHead {
+    /// Open arrowhead, drawn as two lines
+    OpenHead(OpenHead),
+    /// Closed triangular arrowhead
+    NormalHead(NormalHead),
+    /// Closed arrowhead with a notched back
+    VeeHead(VeeHead),
+    /// Flat bar across the end of the line, as on an error bar
+    TeeHead(TeeHead),
+}
+
+impl ToBokeh for ArrowHead {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            ArrowHead::OpenHead(h) => h.as_bokeh_value(),
+            ArrowHead::NormalHead(h) => h.as_bokeh_value(),
+            ArrowHead::VeeHead(h) => h.as_bokeh_value(),
+            ArrowHead::TeeHead(h) => h.as_bokeh_value(),
+        }
+    }
+}
+
+// OpenHead
+
+/// Open arrowhead, drawn as two lines
+#[derive(Clone, Default)]
+pub struct OpenHead {
+    id: Id,
+    /// Size of the head in pixels
+    pub size: Option<f64>,
+    /// Style of the outline
+    pub line: LineProperties,
+}
+
+impl OpenHead {
+    /// Create a new OpenHead
+    pub fn new() -> OpenHead {
+        OpenHead::default()
+    }
+
+    /// Create a new OpenHead with a known id
+    pub fn with_id(id: Id) -> OpenHead {
+        OpenHead {
+            id,
+            ..OpenHead::default()
+        }
+    }
+}
+
+impl From<OpenHead> for ArrowHead {
+    fn from(h: OpenHead) -> ArrowHead {
+        ArrowHead::OpenHead(h)
+    }
+}
+
+impl ToBokeh for OpenHead {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(size) = self.size {
+            attributes["size"] = json!(size);
+        }
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "OpenHead",
+        })
+    }
+}
+
+// NormalHead
+
+/// Closed triangular arrowhead
+#[derive(Clone, Default)]
+pub struct NormalHead {
+    id: Id,
+    /// Size of the head in pixels
+    pub size: Option<f64>,
+    /// Style of the outline
+    pub line: LineProperties,
+    /// Style of the fill
+    pub fill: FillProperties,
+}
+
+impl NormalHead {
+    /// Create a new NormalHead
+    pub fn new() -> NormalHead {
+        NormalHead::default()
+    }
+
+    /// Create a new NormalHead with a known id
+    pub fn with_id(id: Id) -> NormalHead {
+        NormalHead {
+            id,
+            ..NormalHead::default()
+        }
+    }
+}
+
+impl From<NormalHead> for ArrowHead {
+    fn from(h: NormalHead) -> ArrowHead {
+        ArrowHead::NormalHead(h)
+    }
+}
+
+impl ToBokeh for NormalHead {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(size) = self.size {
+            attributes["size"] = json!(size);
+        }
+        self.line.add_attributes("line", &mut attributes);
+        self.fill.add_attributes("fill", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "NormalHead",
+        })
+    }
+}
+
+// VeeHead
+
+/// Closed arrowhead with a notched back
+#[derive(Clone, Default)]
+pub struct VeeHead {
+    id: Id,
+    /// Size of the head in pixels
+    pub size: Option<f64>,
+    /// Style of the outline
+    pub line: LineProperties,
+    /// Style of the fill
+    pub fill: FillProperties,
+}
+
+impl VeeHead {
+    /// Create a new VeeHead
+    pub fn new() -> VeeHead {
+        VeeHead::default()
+    }
+
+    /// Create a new VeeHead with a known id
+    pub fn with_id(id: Id) -> VeeHead {
+        VeeHead {
+            id,
+            ..VeeHead::default()
+        }
+    }
+}
+
+impl From<VeeHead> for ArrowHead {
+    fn from(h: VeeHead) -> ArrowHead {
+        ArrowHead::VeeHead(h)
+    }
+}
+
+impl ToBokeh for VeeHead {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(size) = self.size {
+            attributes["size"] = json!(size);
+        }
+        self.line.add_attributes("line", &mut attributes);
+        self.fill.add_attributes("fill", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "VeeHead",
+        })
+    }
+}
+
+// TeeHead
+
+/// Flat bar across the end of the line, as on an error bar
+#[derive(Clone, Default)]
+pub struct TeeHead {
+    id: Id,
+    /// Size of the head in pixels
+    pub size: Option<f64>,
+    /// Style of the outline
+    pub line: LineProperties,
+}
+
+impl TeeHead {
+    /// Create a new TeeHead
+    pub fn new() -> TeeHead {
+        TeeHead::default()
+    }
+
+    /// Create a new TeeHead with a known id
+    pub fn with_id(id: Id) -> TeeHead {
+        TeeHead {
+            id,
+            ..TeeHead::default()
+        }
+    }
+}
+
+impl From<TeeHead> for ArrowHead {
+    fn from(h: TeeHead) -> ArrowHead {
+        ArrowHead::TeeHead(h)
+    }
+}
+
+impl ToBokeh for TeeHead {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(size) = self.size {
+            attributes["size"] = json!(size);
+        }
+        self.line.add_attributes("line", &mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "TeeHead",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrow_heads() {
+        let mut head = NormalHead::with_id(Id::from("1001"));
+        head.size = Some(10.0);
+        head.fill.color = Some("red".to_string());
+        assert_eq!(
+            ArrowHead::from(head).as_bokeh_value(),
+            json!({
+                "attributes": {"fill_color": {"value": "red"}, "size": 10.0},
+                "id": "1001",
+                "type": "NormalHead",
+            })
+        );
+        assert_eq!(
+            ArrowHead::from(TeeHead::with_id(Id::from("1002"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1002", "type": "TeeHead"})
+        );
+    }
+}
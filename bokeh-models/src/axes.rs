@@ -0,0 +1,689 @@
+//! Axes, drawn on the sides of a plot
+
+use crate::{
+    BasicTickFormatter, BasicTicker, CategoricalScale, CategoricalTickFormatter, CategoricalTicker,
+    DatetimeTickFormatter, DatetimeTicker, Dimension, FontStyle, Id, LatLon, Layout, LinearScale,
+    LogScale, LogTickFormatter, LogTicker, MercatorTickFormatter, MercatorTicker, Scale,
+    TickFormatter, Ticker, ToBokeh,
+};
+use serde_json::{json, Map, Value};
+
+/// Kind of values shown along an axis, as used by `Plot::set_x_axis_type`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AxisType {
+    /// Linear scale with a `LinearAxis`
+    Linear,
+    /// Log scale with a `LogAxis`
+    Log,
+    /// Linear scale with a `DatetimeAxis`, for values which are milliseconds since the epoch
+    Datetime,
+    /// Categorical scale with a `CategoricalAxis`, for plots with a `FactorRange`
+    Categorical,
+    /// Linear scale with a `MercatorAxis`, for Web Mercator coordinates such as those of map
+    /// tiles
+    Mercator,
+}
+
+impl AxisType {
+    pub(crate) fn scale(self) -> Scale {
+        match self {
+            AxisType::Linear | AxisType::Datetime | AxisType::Mercator => LinearScale::new().into(),
+            AxisType::Log => LogScale::new().into(),
+            AxisType::Categorical => CategoricalScale::new().into(),
+        }
+    }
+
+    /// Axis for the `dimension` of a plot
+    pub(crate) fn axis(self, dimension: Dimension) -> Layout {
+        match self {
+            AxisType::Linear => LinearAxis::new().into(),
+            AxisType::Log => LogAxis::new().into(),
+            AxisType::Datetime => DatetimeAxis::new().into(),
+            AxisType::Categorical => CategoricalAxis::new().into(),
+            AxisType::Mercator => match dimension {
+                Dimension::Width => MercatorAxis::new(LatLon::Lon).into(),
+                Dimension::Height => MercatorAxis::new(LatLon::Lat).into(),
+            },
+        }
+    }
+}
+
+/// Axis with linearly spaced ticks
+#[derive(Clone)]
+pub struct LinearAxis {
+    id: Id,
+    /// Chooses where the ticks go
+    pub ticker: Ticker,
+    /// Turns the ticks into labels
+    pub formatter: TickFormatter,
+    /// Label and styling of the axis
+    pub properties: AxisProperties,
+}
+
+impl LinearAxis {
+    /// Create a new linear axis
+    pub fn new() -> Self {
+        LinearAxis::with_id(Id::new())
+    }
+
+    /// Create a new linear axis with a known id
+    pub fn with_id(id: Id) -> Self {
+        LinearAxis {
+            id,
+            ticker: BasicTicker::new().into(),
+            formatter: BasicTickFormatter::new().into(),
+            properties: AxisProperties::default(),
+        }
+    }
+}
+
+impl Default for LinearAxis {
+    fn default() -> Self {
+        LinearAxis::new()
+    }
+}
+
+impl From<LinearAxis> for Layout {
+    fn from(a: LinearAxis) -> Layout {
+        Layout::LinearAxis(a)
+    }
+}
+
+impl ToBokeh for LinearAxis {
+    fn as_bokeh_value(&self) -> Value {
+        axis_value(
+            &self.id,
+            "LinearAxis",
+            &self.ticker,
+            &self.formatter,
+            &self.properties,
+        )
+    }
+
+    fn references(&self) -> Vec<Value> {
+        vec![
+            self.as_bokeh_value(),
+            self.ticker.as_bokeh_value(),
+            self.formatter.as_bokeh_value(),
+        ]
+    }
+}
+
+/// Axis with ticks at powers of ten, for plots with a `LogScale`
+#[derive(Clone)]
+pub struct LogAxis {
+    id: Id,
+    /// Chooses where the ticks go
+    pub ticker: Ticker,
+    /// Turns the ticks into labels
+    pub formatter: TickFormatter,
+    /// Label and styling of the axis
+    pub properties: AxisProperties,
+}
+
+impl LogAxis {
+    /// Create a new log axis
+    pub fn new() -> Self {
+        LogAxis::with_id(Id::new())
+    }
+
+    /// Create a new log axis with a known id
+    pub fn with_id(id: Id) -> Self {
+        LogAxis {
+            id,
+            ticker: LogTicker::new().into(),
+            formatter: LogTickFormatter::new().into(),
+            properties: AxisProperties::default(),
+        }
+    }
+}
+
+impl Default for LogAxis {
+    fn default() -> Self {
+        LogAxis::new()
+    }
+}
+
+impl From<LogAxis> for Layout {
+    fn from(a: LogAxis) -> Layout {
+        Layout::LogAxis(a)
+    }
+}
+
+impl ToBokeh for LogAxis {
+    fn as_bokeh_value(&self) -> Value {
+        axis_value(
+            &self.id,
+            "LogAxis",
+            &self.ticker,
+            &self.formatter,
+            &self.properties,
+        )
+    }
+
+    fn references(&self) -> Vec<Value> {
+        vec![
+            self.as_bokeh_value(),
+            self.ticker.as_bokeh_value(),
+            self.formatter.as_bokeh_value(),
+        ]
+    }
+}
+
+/// Axis for values which are milliseconds since the epoch, labelled as dates and times
+#[derive(Clone)]
+pub struct DatetimeAxis {
+    id: Id,
+    /// Chooses where the ticks go
+    pub ticker: Ticker,
+    /// Turns the ticks into labels
+    pub formatter: TickFormatter,
+    /// Label and styling of the axis
+    pub properties: AxisProperties,
+}
+
+impl DatetimeAxis {
+    /// Create a new datetime axis
+    pub fn new() -> Self {
+        DatetimeAxis::with_id(Id::new())
+    }
+
+    /// Create a new datetime axis with a known id
+    pub fn with_id(id: Id) -> Self {
+        DatetimeAxis {
+            id,
+            ticker: DatetimeTicker::new().into(),
+            formatter: DatetimeTickFormatter::new().into(),
+            properties: AxisProperties::default(),
+        }
+    }
+}
+
+impl Default for DatetimeAxis {
+    fn default() -> Self {
+        DatetimeAxis::new()
+    }
+}
+
+impl From<DatetimeAxis> for Layout {
+    fn from(a: DatetimeAxis) -> Layout {
+        Layout::DatetimeAxis(a)
+    }
+}
+
+impl ToBokeh for DatetimeAxis {
+    fn as_bokeh_value(&self) -> Value {
+        axis_value(
+            &self.id,
+            "DatetimeAxis",
+            &self.ticker,
+            &self.formatter,
+            &self.properties,
+        )
+    }
+
+    fn references(&self) -> Vec<Value> {
+        vec![
+            self.as_bokeh_value(),
+            self.ticker.as_bokeh_value(),
+            self.formatter.as_bokeh_value(),
+        ]
+    }
+}
+
+/// Axis labelled with the categories of a `FactorRange`
+///
+/// Nested categories are labelled in groups, with the name of each group below its categories.
+#[derive(Clone)]
+pub struct CategoricalAxis {
+    id: Id,
+    /// Chooses where the ticks go
+    pub ticker: Ticker,
+    /// Turns the ticks into labels
+    pub formatter: TickFormatter,
+    /// Label and styling of the axis
+    pub properties: AxisProperties,
+}
+
+impl CategoricalAxis {
+    /// Create a new categorical axis
+    pub fn new() -> Self {
+        CategoricalAxis::with_id(Id::new())
+    }
+
+    /// Create a new categorical axis with a known id
+    pub fn with_id(id: Id) -> Self {
+        CategoricalAxis {
+            id,
+            ticker: CategoricalTicker::new().into(),
+            formatter: CategoricalTickFormatter::new().into(),
+            properties: AxisProperties::default(),
+        }
+    }
+}
+
+impl Default for CategoricalAxis {
+    fn default() -> Self {
+        CategoricalAxis::new()
+    }
+}
+
+impl From<CategoricalAxis> for Layout {
+    fn from(a: CategoricalAxis) -> Layout {
+        Layout::CategoricalAxis(a)
+    }
+}
+
+impl ToBokeh for CategoricalAxis {
+    fn as_bokeh_value(&self) -> Value {
+        axis_value(
+            &self.id,
+            "CategoricalAxis",
+            &self.ticker,
+            &self.formatter,
+            &self.properties,
+        )
+    }
+
+    fn references(&self) -> Vec<Value> {
+        vec![
+            self.as_bokeh_value(),
+            self.ticker.as_bokeh_value(),
+            self.formatter.as_bokeh_value(),
+        ]
+    }
+}
+
+/// Axis for Web Mercator coordinates, labelled with latitudes or longitudes
+#[derive(Clone)]
+pub struct MercatorAxis {
+    id: Id,
+    /// Chooses where the ticks go
+    pub ticker: Ticker,
+    /// Turns the ticks into labels
+    pub formatter: TickFormatter,
+    /// Label and styling of the axis
+    pub properties: AxisProperties,
+}
+
+impl MercatorAxis {
+    /// Create a new mercator axis labelled with latitudes or longitudes
+    pub fn new(dimension: LatLon) -> Self {
+        MercatorAxis::with_id(Id::new(), dimension)
+    }
+
+    /// Create a new mercator axis labelled with latitudes or longitudes, with a known id
+    pub fn with_id(id: Id, dimension: LatLon) -> Self {
+        let mut ticker = MercatorTicker::new();
+        ticker.dimension = Some(dimension);
+        let mut formatter = MercatorTickFormatter::new();
+        formatter.dimension = Some(dimension);
+        MercatorAxis {
+            id,
+            ticker: ticker.into(),
+            formatter: formatter.into(),
+            properties: AxisProperties::default(),
+        }
+    }
+}
+
+impl From<MercatorAxis> for Layout {
+    fn from(a: MercatorAxis) -> Layout {
+        Layout::MercatorAxis(a)
+    }
+}
+
+impl ToBokeh for MercatorAxis {
+    fn as_bokeh_value(&self) -> Value {
+        axis_value(
+            &self.id,
+            "MercatorAxis",
+            &self.ticker,
+            &self.formatter,
+            &self.properties,
+        )
+    }
+
+    fn references(&self) -> Vec<Value> {
+        vec![
+            self.as_bokeh_value(),
+            self.ticker.as_bokeh_value(),
+            self.formatter.as_bokeh_value(),
+        ]
+    }
+}
+
+/// Properties shared by every kind of axis
+#[derive(Clone, Debug, Default)]
+pub struct AxisProperties {
+    /// Label of the axis
+    pub axis_label: Option<String>,
+    /// Distance in pixels between the label and the tick labels
+    pub axis_label_standoff: Option<u32>,
+    /// Font of the label
+    pub axis_label_text_font: Option<String>,
+    /// Font size of the label, e.g. `12pt`
+    pub axis_label_text_font_size: Option<String>,
+    /// Font style of the label
+    pub axis_label_text_font_style: Option<FontStyle>,
+    /// Colour of the label
+    pub axis_label_text_color: Option<String>,
+    /// Font of the tick labels
+    pub major_label_text_font: Option<String>,
+    /// Font size of the tick labels, e.g. `8pt`
+    pub major_label_text_font_size: Option<String>,
+    /// Font style of the tick labels
+    pub major_label_text_font_style: Option<FontStyle>,
+    /// Colour of the tick labels
+    pub major_label_text_color: Option<String>,
+    /// Orientation of the tick labels
+    pub major_label_orientation: Option<LabelOrientation>,
+    /// Labels shown in place of the formatted tick, keyed by tick value
+    ///
+    /// This lets ticks at numeric positions, such as the indices of a list of names, be labelled
+    /// with arbitrary strings.
+    pub major_label_overrides: Option<Vec<(f64, String)>>,
+    /// Colour of the axis line
+    pub axis_line_color: Option<String>,
+    /// Width of the axis line in pixels
+    pub axis_line_width: Option<f64>,
+    /// Opacity of the axis line
+    pub axis_line_alpha: Option<f64>,
+    /// Dash pattern of the axis line, as lengths in pixels of alternating dashes and gaps
+    pub axis_line_dash: Option<Vec<u32>>,
+    /// Length in pixels of the major ticks into the plot
+    pub major_tick_in: Option<i32>,
+    /// Length in pixels of the major ticks away from the plot
+    pub major_tick_out: Option<i32>,
+    /// Colour of the major ticks
+    pub major_tick_line_color: Option<String>,
+    /// Width of the major ticks in pixels
+    pub major_tick_line_width: Option<f64>,
+    /// Opacity of the major ticks
+    pub major_tick_line_alpha: Option<f64>,
+    /// Length in pixels of the minor ticks into the plot
+    ///
+    /// The number of minor ticks is set on the ticker, such as `AdaptiveTicker`.
+    pub minor_tick_in: Option<i32>,
+    /// Length in pixels of the minor ticks away from the plot
+    pub minor_tick_out: Option<i32>,
+    /// Colour of the minor ticks
+    pub minor_tick_line_color: Option<String>,
+    /// Width of the minor ticks in pixels
+    pub minor_tick_line_width: Option<f64>,
+    /// Opacity of the minor ticks
+    pub minor_tick_line_alpha: Option<f64>,
+    /// Extra x range of the plot the axis shows, rather than its `x_range`
+    pub x_range_name: Option<String>,
+    /// Extra y range of the plot the axis shows, rather than its `y_range`
+    pub y_range_name: Option<String>,
+}
+
+impl AxisProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(axis_label) = &self.axis_label {
+            attributes["axis_label"] = json!(axis_label);
+        }
+        if let Some(standoff) = self.axis_label_standoff {
+            attributes["axis_label_standoff"] = json!(standoff);
+        }
+        if let Some(font) = &self.axis_label_text_font {
+            attributes["axis_label_text_font"] = json!(font);
+        }
+        if let Some(font_size) = &self.axis_label_text_font_size {
+            attributes["axis_label_text_font_size"] = json!({ "value": font_size });
+        }
+        if let Some(font_style) = self.axis_label_text_font_style {
+            attributes["axis_label_text_font_style"] = json!(font_style.name());
+        }
+        if let Some(color) = &self.axis_label_text_color {
+            attributes["axis_label_text_color"] = json!({ "value": color });
+        }
+        if let Some(font) = &self.major_label_text_font {
+            attributes["major_label_text_font"] = json!(font);
+        }
+        if let Some(font_size) = &self.major_label_text_font_size {
+            attributes["major_label_text_font_size"] = json!({ "value": font_size });
+        }
+        if let Some(font_style) = self.major_label_text_font_style {
+            attributes["major_label_text_font_style"] = json!(font_style.name());
+        }
+        if let Some(color) = &self.major_label_text_color {
+            attributes["major_label_text_color"] = json!({ "value": color });
+        }
+        if let Some(orientation) = self.major_label_orientation {
+            attributes["major_label_orientation"] = orientation.value();
+        }
+        if let Some(overrides) = &self.major_label_overrides {
+            // BokehJS looks labels up by the tick formatted as a JavaScript number, which `f64`'s
+            // `Display` matches for all but very large or small values
+            let overrides: Map<String, Value> = overrides
+                .iter()
+                .map(|(tick, label)| (tick.to_string(), json!(label)))
+                .collect();
+            attributes["major_label_overrides"] = json!(overrides);
+        }
+        if let Some(color) = &self.axis_line_color {
+            attributes["axis_line_color"] = json!({ "value": color });
+        }
+        if let Some(width) = self.axis_line_width {
+            attributes["axis_line_width"] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.axis_line_alpha {
+            attributes["axis_line_alpha"] = json!({ "value": alpha });
+        }
+        if let Some(dash) = &self.axis_line_dash {
+            attributes["axis_line_dash"] = json!(dash);
+        }
+        if let Some(tick_in) = self.major_tick_in {
+            attributes["major_tick_in"] = json!(tick_in);
+        }
+        if let Some(tick_out) = self.major_tick_out {
+            attributes["major_tick_out"] = json!(tick_out);
+        }
+        if let Some(color) = &self.major_tick_line_color {
+            attributes["major_tick_line_color"] = json!({ "value": color });
+        }
+        if let Some(width) = self.major_tick_line_width {
+            attributes["major_tick_line_width"] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.major_tick_line_alpha {
+            attributes["major_tick_line_alpha"] = json!({ "value": alpha });
+        }
+        if let Some(tick_in) = self.minor_tick_in {
+            attributes["minor_tick_in"] = json!(tick_in);
+        }
+        if let Some(tick_out) = self.minor_tick_out {
+            attributes["minor_tick_out"] = json!(tick_out);
+        }
+        if let Some(color) = &self.minor_tick_line_color {
+            attributes["minor_tick_line_color"] = json!({ "value": color });
+        }
+        if let Some(width) = self.minor_tick_line_width {
+            attributes["minor_tick_line_width"] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.minor_tick_line_alpha {
+            attributes["minor_tick_line_alpha"] = json!({ "value": alpha });
+        }
+        if let Some(x_range_name) = &self.x_range_name {
+            attributes["x_range_name"] = json!(x_range_name);
+        }
+        if let Some(y_range_name) = &self.y_range_name {
+            attributes["y_range_name"] = json!(y_range_name);
+        }
+    }
+}
+
+/// Orientation of the tick labels of an axis
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LabelOrientation {
+    /// Horizontal text
+    Horizontal,
+    /// Vertical text
+    Vertical,
+    /// Text along the axis
+    Parallel,
+    /// Text at right angles to the axis
+    Normal,
+    /// Text at the given angle in radians
+    Angle(f64),
+}
+
+impl LabelOrientation {
+    fn value(self) -> Value {
+        match self {
+            LabelOrientation::Horizontal => json!("horizontal"),
+            LabelOrientation::Vertical => json!("vertical"),
+            LabelOrientation::Parallel => json!("parallel"),
+            LabelOrientation::Normal => json!("normal"),
+            LabelOrientation::Angle(angle) => json!(angle),
+        }
+    }
+}
+
+// Model of an axis of type `kind`
+fn axis_value(
+    id: &Id,
+    kind: &str,
+    ticker: &Ticker,
+    formatter: &TickFormatter,
+    properties: &AxisProperties,
+) -> Value {
+    let mut attributes = json!({
+        "formatter": formatter.as_bokeh_ref(),
+        "ticker": ticker.as_bokeh_ref(),
+    });
+    properties.add_attributes(&mut attributes);
+
+    json!({
+        "attributes": attributes,
+        "id": id,
+        "type": kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_axis() {
+        let mut axis = LogAxis::with_id(Id::from("1001"));
+        axis.ticker = LogTicker::with_id(Id::from("1002")).into();
+        axis.formatter = LogTickFormatter::with_id(Id::from("1003")).into();
+        assert_eq!(
+            Layout::from(axis).references(),
+            vec![
+                json!({
+                    "attributes": {
+                        "formatter": {"id": "1003", "type": "LogTickFormatter"},
+                        "ticker": {"id": "1002", "type": "LogTicker"},
+                    },
+                    "id": "1001",
+                    "type": "LogAxis",
+                }),
+                json!({"attributes": {}, "id": "1002", "type": "LogTicker"}),
+                json!({"attributes": {}, "id": "1003", "type": "LogTickFormatter"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_datetime_axis() {
+        let axis = DatetimeAxis::with_id(Id::from("1001"));
+        let references = Layout::from(axis).references();
+        assert_eq!(references[0]["type"], "DatetimeAxis");
+        assert_eq!(references[1]["type"], "DatetimeTicker");
+        assert_eq!(references[2]["type"], "DatetimeTickFormatter");
+        assert_eq!(
+            references[0]["attributes"]["ticker"],
+            json!({"id": references[1]["id"], "type": "DatetimeTicker"})
+        );
+
+        assert_eq!(
+            AxisType::Datetime.axis(Dimension::Width).as_bokeh_value()["type"],
+            "DatetimeAxis"
+        );
+    }
+
+    #[test]
+    fn test_categorical_axis() {
+        let references = AxisType::Categorical.axis(Dimension::Width).references();
+        assert_eq!(references[0]["type"], "CategoricalAxis");
+        assert_eq!(references[1]["type"], "CategoricalTicker");
+        assert_eq!(references[2]["type"], "CategoricalTickFormatter");
+        assert_eq!(
+            references[0]["attributes"]["ticker"],
+            json!({"id": references[1]["id"], "type": "CategoricalTicker"})
+        );
+        assert_eq!(
+            Ticker::from(CategoricalTicker::with_id(Id::from("1001"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "CategoricalTicker"})
+        );
+    }
+
+    #[test]
+    fn test_mercator_axis() {
+        let references = AxisType::Mercator.axis(Dimension::Height).references();
+        assert_eq!(references[0]["type"], "MercatorAxis");
+        assert_eq!(references[1]["attributes"], json!({"dimension": "lat"}));
+        assert_eq!(references[1]["type"], "MercatorTicker");
+        assert_eq!(references[2]["attributes"], json!({"dimension": "lat"}));
+        assert_eq!(references[2]["type"], "MercatorTickFormatter");
+
+        let axis = MercatorAxis::new(LatLon::Lon);
+        assert_eq!(
+            axis.ticker.as_bokeh_value()["attributes"]["dimension"],
+            "lon"
+        );
+    }
+
+    #[test]
+    fn test_axis_properties() {
+        let mut axis = LinearAxis::with_id(Id::from("1001"));
+        axis.properties.axis_label = Some("Time (s)".to_string());
+        axis.properties.axis_label_text_font_size = Some("12pt".to_string());
+        axis.properties.axis_label_text_font_style = Some(FontStyle::Bold);
+        axis.properties.major_label_orientation = Some(LabelOrientation::Angle(0.5));
+        axis.properties.axis_line_color = Some("grey".to_string());
+        axis.properties.axis_line_dash = Some(vec![4, 4]);
+
+        let attributes = Layout::from(axis).as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["axis_label"], "Time (s)");
+        assert_eq!(
+            attributes["axis_label_text_font_size"],
+            json!({"value": "12pt"})
+        );
+        assert_eq!(attributes["axis_label_text_font_style"], "bold");
+        assert_eq!(attributes["major_label_orientation"], 0.5);
+        assert_eq!(attributes["axis_line_color"], json!({"value": "grey"}));
+        assert_eq!(attributes["axis_line_dash"], json!([4, 4]));
+    }
+
+    #[test]
+    fn test_axis_ticks() {
+        let mut axis = LinearAxis::new();
+        axis.properties.major_tick_in = Some(0);
+        axis.properties.major_tick_out = Some(10);
+        axis.properties.minor_tick_line_color = Some("red".to_string());
+        axis.properties.minor_tick_line_width = Some(2.0);
+
+        let attributes = axis.as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["major_tick_in"], 0);
+        assert_eq!(attributes["major_tick_out"], 10);
+        assert_eq!(attributes["minor_tick_line_color"], json!({"value": "red"}));
+        assert_eq!(attributes["minor_tick_line_width"], json!({"value": 2.0}));
+        assert!(attributes.get("minor_tick_in").is_none());
+    }
+
+    #[test]
+    fn test_major_label_overrides() {
+        let mut axis = LinearAxis::new();
+        axis.properties.major_label_overrides =
+            Some(vec![(0.0, "Mon".to_string()), (1.5, "Tue".to_string())]);
+        assert_eq!(
+            axis.as_bokeh_value()["attributes"]["major_label_overrides"],
+            json!({"0": "Mon", "1.5": "Tue"})
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Callbacks run in the browser in response to user interaction
+
+use crate::{Id, ToBokeh};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Callback run by BokehJS
+#[derive(Clone)]
+pub enum Callback {
+    /// Open a URL
+    OpenURL(OpenURL),
+    /// Run JavaScript code
+    CustomJS(CustomJS),
+}
+
+impl ToBokeh for Callback {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Callback::OpenURL(c) => c.as_bokeh_value(),
+            Callback::CustomJS(c) => c.as_bokeh_value(),
+        }
+    }
+}
+
+/// Open a URL in a new tab
+///
+/// Fields of the form `@column` in the URL are replaced by the values of the selected rows, e.g.
+/// `https://example.com/items/@id`.
+#[derive(Clone, Default)]
+pub struct OpenURL {
+    id: Id,
+    /// Template of the URL to open
+    pub url: String,
+}
+
+impl OpenURL {
+    /// Create a new callback opening `url`
+    pub fn new<S>(url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        OpenURL {
+            url: url.into(),
+            ..OpenURL::default()
+        }
+    }
+
+    /// Create a new callback opening `url`, with a known id
+    pub fn with_id<S>(id: Id, url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        OpenURL {
+            id,
+            url: url.into(),
+        }
+    }
+}
+
+impl From<OpenURL> for Callback {
+    fn from(c: OpenURL) -> Callback {
+        Callback::OpenURL(c)
+    }
+}
+
+impl ToBokeh for OpenURL {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "url": self.url,
+            },
+            "id": self.id,
+            "type": "OpenURL",
+        })
+    }
+}
+
+/// Run JavaScript code in the browser
+///
+/// The code is the body of a function which is given `cb_obj`, the model which triggered the
+/// callback, `cb_data`, details of the event, and every argument added with `add_arg` by name.
+///
+/// Besides being the `callback` of a widget or tool, it can be run whenever a property of a
+/// widget, range or data source changes with their `js_on_change` methods. Such callbacks run
+/// entirely in the browser, so they work in standalone HTML files with no server.
+#[derive(Clone, Debug, Default)]
+pub struct CustomJS {
+    id: Id,
+    /// Body of the function to run
+    pub code: String,
+    args: Vec<(String, Value)>,
+}
+
+impl CustomJS {
+    /// Create a new callback running `code`
+    pub fn new<S>(code: S) -> Self
+    where
+        S: Into<String>,
+    {
+        CustomJS {
+            code: code.into(),
+            ..CustomJS::default()
+        }
+    }
+
+    /// Create a new callback running `code`, with a known id
+    pub fn with_id<S>(id: Id, code: S) -> Self
+    where
+        S: Into<String>,
+    {
+        CustomJS {
+            id,
+            code: code.into(),
+            ..CustomJS::default()
+        }
+    }
+
+    /// Create a new callback setting the property `target_attr` of `target` to the value of the
+    /// property `attr` of the model which triggered it
+    pub(crate) fn link<M>(attr: &str, target: &M, target_attr: &str) -> Self
+    where
+        M: ToBokeh,
+    {
+        let mut callback = CustomJS::new(format!("target.{} = cb_obj.{}", target_attr, attr));
+        callback.add_arg("target", target);
+        callback
+    }
+
+    /// Make `model` available to the code as the variable `name`
+    ///
+    /// The model must also be part of the document, e.g. a data source of one of its plots.
+    pub fn add_arg<S, M>(&mut self, name: S, model: &M)
+    where
+        S: Into<String>,
+        M: ToBokeh,
+    {
+        self.args.push((name.into(), model.as_bokeh_ref()));
+    }
+}
+
+impl From<CustomJS> for Callback {
+    fn from(c: CustomJS) -> Callback {
+        Callback::CustomJS(c)
+    }
+}
+
+impl ToBokeh for CustomJS {
+    fn as_bokeh_value(&self) -> Value {
+        let args: Map<String, Value> = self.args.iter().cloned().collect();
+        json!({
+            "attributes": {
+                "args": args,
+                "code": self.code,
+            },
+            "id": self.id,
+            "type": "CustomJS",
+        })
+    }
+}
+
+/// JavaScript callbacks of a model, by the event they run on
+#[derive(Clone, Debug, Default)]
+pub(crate) struct JsCallbacks {
+    callbacks: BTreeMap<String, Vec<CustomJS>>,
+}
+
+impl JsCallbacks {
+    // Run `callback` on the event `event`, after any callbacks already added for it
+    pub(crate) fn add(&mut self, event: String, callback: CustomJS) {
+        self.callbacks.entry(event).or_default().push(callback);
+    }
+
+    // Run `callback` whenever the property `attr` changes
+    pub(crate) fn add_change(&mut self, attr: &str, callback: CustomJS) {
+        self.add(format!("change:{}", attr), callback);
+    }
+
+    // Keep the property `target_attr` of `target` equal to the property `attr`
+    pub(crate) fn add_link<M>(&mut self, attr: &str, target: &M, target_attr: &str)
+    where
+        M: ToBokeh,
+    {
+        self.add_change(attr, CustomJS::link(attr, target, target_attr));
+    }
+
+    pub(crate) fn add_attributes(&self, attributes: &mut Value) {
+        if self.callbacks.is_empty() {
+            return;
+        }
+        let callbacks: Map<String, Value> = self
+            .callbacks
+            .iter()
+            .map(|(event, callbacks)| {
+                let refs: Vec<Value> = callbacks.iter().map(|c| c.as_bokeh_ref()).collect();
+                (event.clone(), json!(refs))
+            })
+            .collect();
+        attributes["js_property_callbacks"] = json!(callbacks);
+    }
+
+    pub(crate) fn references(&self) -> Vec<Value> {
+        self.callbacks
+            .values()
+            .flatten()
+            .flat_map(|c| c.references())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, ColumnDataSource, Range, Range1d, Slider, Widget};
+
+    #[test]
+    fn test_open_url() {
+        let callback = OpenURL::with_id(Id::from("1001"), "https://example.com/@id");
+        assert_eq!(
+            Callback::from(callback).as_bokeh_value(),
+            json!({
+                "attributes": {"url": "https://example.com/@id"},
+                "id": "1001",
+                "type": "OpenURL",
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_js() {
+        let source = ColumnDataSource::with_id(Id::from("1002"));
+        let mut callback = CustomJS::with_id(Id::from("1001"), "source.change.emit()");
+        callback.add_arg("source", &source);
+        assert_eq!(
+            callback.as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "args": {"source": {"id": "1002", "type": "ColumnDataSource"}},
+                    "code": "source.change.emit()",
+                },
+                "id": "1001",
+                "type": "CustomJS",
+            })
+        );
+    }
+
+    #[test]
+    fn test_js_on_change() {
+        let mut callback = CustomJS::with_id(Id::from("1001"), "range.start = cb_obj.value");
+        let range = Range1d::with_id(Id::from("1002"), 0.0, 10.0);
+        callback.add_arg("range", &range);
+
+        let mut slider = Slider::with_id(Id::from("1003"), 0.0, 10.0, 0.0);
+        slider
+            .properties
+            .widget
+            .js_on_change("value", callback.clone());
+        slider
+            .properties
+            .widget
+            .js_on_change("value", CustomJS::with_id(Id::from("1004"), ""));
+        let references = Widget::from(slider).references();
+        assert_eq!(
+            references[0]["attributes"]["js_property_callbacks"],
+            json!({
+                "change:value": [
+                    {"id": "1001", "type": "CustomJS"},
+                    {"id": "1004", "type": "CustomJS"},
+                ],
+            })
+        );
+        assert!(references.contains(&callback.as_bokeh_value()));
+
+        let mut source = ColumnDataSource::with_id(Id::from("1005"));
+        source.js_on_change("streaming", callback.clone());
+        source.js_on_change("data", callback.clone());
+        assert_eq!(
+            source.as_bokeh_value()["attributes"]["js_property_callbacks"],
+            json!({
+                "change:data": [{"id": "1001", "type": "CustomJS"}],
+                "streaming": [{"id": "1001", "type": "CustomJS"}],
+            })
+        );
+        assert!(source.references().contains(&callback.as_bokeh_value()));
+
+        let mut range = Range1d::with_id(Id::from("1002"), 0.0, 10.0);
+        range.js_on_change("start", callback.clone());
+        let range = Range::from(range);
+        assert_eq!(
+            range.as_bokeh_value()["attributes"]["js_property_callbacks"],
+            json!({"change:start": [{"id": "1001", "type": "CustomJS"}]})
+        );
+        assert!(range.references().contains(&callback.as_bokeh_value()));
+    }
+
+    #[test]
+    fn test_js_link() {
+        let circle = Circle::with_id(Id::from("1001"));
+        let mut slider = Slider::with_id(Id::from("1002"), 1.0, 20.0, 5.0);
+        slider.properties.widget.js_link("value", &circle, "size");
+
+        let references = Widget::from(slider).references();
+        let callback = references.iter().find(|r| r["type"] == "CustomJS").unwrap();
+        assert_eq!(
+            callback["attributes"],
+            json!({
+                "args": {"target": {"id": "1001", "type": "Circle"}},
+                "code": "target.size = cb_obj.value",
+            })
+        );
+        assert_eq!(
+            references[0]["attributes"]["js_property_callbacks"]["change:value"][0]["id"],
+            callback["id"]
+        );
+
+        let target = Range1d::with_id(Id::from("1003"), 0.0, 1.0);
+        let mut range = Range1d::with_id(Id::from("1004"), 0.0, 1.0);
+        range.js_link("start", &target, "start");
+        let references = Range::from(range).references();
+        assert_eq!(
+            references[1]["attributes"]["code"],
+            "target.start = cb_obj.start"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Built-in defaults of the properties of each model the crate serializes
+//!
+//! The values are those of Bokeh 1.0, in their serialized form, exported from the Python library
+//! in `support/example_models_export.json`. Properties whose defaults refer to other models,
+//! such as the toolbar of a plot, are left out as they have no value without those models.
+
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+/// Defaults of the properties of models of type `model_type`, if it is a known type
+pub(crate) fn builtin_defaults(model_type: &str) -> Option<&'static Map<String, Value>> {
+    static DEFAULTS: OnceLock<Map<String, Value>> = OnceLock::new();
+    DEFAULTS
+        .get_or_init(|| {
+            serde_json::from_str(include_str!("defaults.json")).expect("valid defaults.json")
+        })
+        .get(model_type)
+        .and_then(Value::as_object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_builtin_defaults() {
+        let slider = builtin_defaults("Slider").unwrap();
+        assert_eq!(slider["show_value"], json!(true));
+        assert_eq!(slider["callback_policy"], json!("throttle"));
+
+        // Properties referring to other models have no default of their own
+        assert!(!builtin_defaults("Plot").unwrap().contains_key("toolbar"));
+        assert!(builtin_defaults("NotAModel").is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! Embedding Bokeh server applications into existing web pages
+//!
+//! These match `bokeh.embed.server_document` and `bokeh.embed.server_session` from Python: each
+//! returns a `<script>` tag which loads the application's `autoload.js`, which in turn connects
+//! to the server and renders the document in place of the tag.
+
+use crate::Id;
+
+/// Address of a Bokeh server running with its default settings
+pub const DEFAULT_SERVER_URL: &str = "http://localhost:5006/";
+
+/// Script tag embedding a new session of the application at `url`
+///
+/// Every page load creates a new session, with a fresh document.
+pub fn server_document<S>(url: S) -> String
+where
+    S: AsRef<str>,
+{
+    autoload_tag(url.as_ref(), None)
+}
+
+/// Script tag embedding the existing session `session_id` of the application at `url`
+///
+/// Use this to show a document which was prepared in advance, or to let several pages share
+/// one document.
+pub fn server_session<S, I>(url: S, session_id: I) -> String
+where
+    S: AsRef<str>,
+    I: AsRef<str>,
+{
+    autoload_tag(url.as_ref(), Some(session_id.as_ref()))
+}
+
+fn autoload_tag(url: &str, session_id: Option<&str>) -> String {
+    let url = url.trim_end_matches('/');
+    let element_id = Id::new();
+
+    // Arguments are encoded as by Python's `urlencode`
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    query.append_pair("bokeh-autoload-element", &element_id.to_string());
+    let app_path = app_path(url);
+    if app_path != "/" {
+        query.append_pair("bokeh-app-path", app_path);
+    }
+    query.append_pair("bokeh-absolute-url", url);
+    if let Some(session_id) = session_id {
+        query.append_pair("bokeh-session-id", session_id);
+    }
+
+    let src = format!("{}/autoload.js?{}", url, query.finish()).replace('&', "&amp;");
+    format!(r#"<script src="{}" id="{}"></script>"#, src, element_id)
+}
+
+/// Path of the application within the server, e.g. `/sliders` for `http://localhost:5006/sliders`
+fn app_path(url: &str) -> &str {
+    let without_scheme = match url.find("://") {
+        Some(index) => &url[index + 3..],
+        None => url,
+    };
+    match without_scheme.find('/') {
+        Some(index) => &without_scheme[index..],
+        None => "/",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element_id(tag: &str) -> &str {
+        let start = tag.find("bokeh-autoload-element=").unwrap() + "bokeh-autoload-element=".len();
+        let end = start + tag[start..].find('&').unwrap();
+        &tag[start..end]
+    }
+
+    #[test]
+    fn test_server_document() {
+        let tag = server_document(DEFAULT_SERVER_URL);
+        let id = element_id(&tag);
+        assert_eq!(
+            tag,
+            format!(
+                r#"<script src="http://localhost:5006/autoload.js?bokeh-autoload-element={0}&amp;bokeh-absolute-url=http%3A%2F%2Flocalhost%3A5006" id="{0}"></script>"#,
+                id
+            )
+        );
+    }
+
+    #[test]
+    fn test_server_session() {
+        let tag = server_session("http://example.com:8000/apps/sliders/", "abc123");
+        let id = element_id(&tag);
+        assert_eq!(
+            tag,
+            format!(
+                r#"<script src="http://example.com:8000/apps/sliders/autoload.js?bokeh-autoload-element={0}&amp;bokeh-app-path=%2Fapps%2Fsliders&amp;bokeh-absolute-url=http%3A%2F%2Fexample.com%3A8000%2Fapps%2Fsliders&amp;bokeh-session-id=abc123" id="{0}"></script>"#,
+                id
+            )
+        );
+    }
+
+    #[test]
+    fn test_arguments_are_encoded() {
+        let tag = server_session("http://localhost:5006/app", "a&b#c?d");
+        assert!(tag.contains("&amp;bokeh-session-id=a%26b%23c%3Fd\""));
+    }
+
+    #[test]
+    fn test_app_path() {
+        assert_eq!(app_path("http://localhost:5006"), "/");
+        assert_eq!(app_path("http://localhost:5006/app"), "/app");
+        assert_eq!(app_path("/app"), "/app");
+    }
+}
@@ -0,0 +1,558 @@
+//! Document change events, and tracking of the changes made to a document
+//!
+//! Models are plain Rust values which can be changed freely, so rather than every setter
+//! recording its change, a `ChangeTracker` remembers the serialized state of each model and
+//! compares it with the document when asked for the changes. Only the differences are then sent
+//! to BokehJS, as the events of a PATCH-DOC message.
+//!
+//! Streaming to and patching a `ColumnDataSource` are the exception: resending the whole of a
+//! large source for every new row would be wasteful, so `Document::stream` and `Document::patch`
+//! record `ColumnsStreamed` and `ColumnsPatched` events as they are made.
+
+use crate::defaults::builtin_defaults;
+use crate::ValidatedDocument;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Change to a document
+#[derive(Clone, Debug, PartialEq)]
+pub enum DocumentEvent {
+    /// An attribute of a model was given a new value
+    ModelChanged {
+        /// Reference to the changed model
+        model: Value,
+        /// Name of the attribute
+        attr: String,
+        /// New value of the attribute
+        new: Value,
+    },
+    /// A root model was added to the document
+    RootAdded {
+        /// Reference to the new root
+        model: Value,
+    },
+    /// A root model was removed from the document
+    RootRemoved {
+        /// Reference to the removed root
+        model: Value,
+    },
+    /// Rows were appended to the columns of a data source
+    ColumnsStreamed {
+        /// Reference to the data source
+        source: Value,
+        /// New values of each column
+        data: Value,
+        /// Maximum number of rows to keep, dropping the oldest
+        rollover: Option<usize>,
+    },
+    /// Individual values of a data source were replaced
+    ColumnsPatched {
+        /// Reference to the data source
+        source: Value,
+        /// `[index, value]` pairs for each column
+        patches: Value,
+    },
+}
+
+impl DocumentEvent {
+    /// JSON representation of the event, as understood by BokehJS
+    pub fn to_json(&self) -> Value {
+        match self {
+            DocumentEvent::ModelChanged { model, attr, new } => json!({
+                "attr": attr,
+                "kind": "ModelChanged",
+                "model": model,
+                "new": new,
+            }),
+            DocumentEvent::RootAdded { model } => json!({
+                "kind": "RootAdded",
+                "model": model,
+            }),
+            DocumentEvent::RootRemoved { model } => json!({
+                "kind": "RootRemoved",
+                "model": model,
+            }),
+            DocumentEvent::ColumnsStreamed {
+                source,
+                data,
+                rollover,
+            } => json!({
+                "column_source": source,
+                "data": data,
+                "kind": "ColumnsStreamed",
+                "rollover": rollover,
+            }),
+            DocumentEvent::ColumnsPatched { source, patches } => json!({
+                "column_source": source,
+                "kind": "ColumnsPatched",
+                "patches": patches,
+            }),
+        }
+    }
+
+    /// Data source changed by a stream or patch event
+    fn column_source(&self) -> Option<&Value> {
+        match self {
+            DocumentEvent::ColumnsStreamed { source, .. }
+            | DocumentEvent::ColumnsPatched { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Set of events, along with the new models they refer to
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Patch {
+    /// Changes to the document
+    pub events: Vec<DocumentEvent>,
+    /// Models which were not in the document before
+    pub references: Vec<Value>,
+}
+
+impl Patch {
+    /// Add an event to the patch
+    ///
+    /// Rows streamed to a source which has not otherwise changed since its last stream are
+    /// appended to that stream, so a burst of small updates is sent as a single event.
+    pub fn push(&mut self, event: DocumentEvent) {
+        if let DocumentEvent::ColumnsStreamed {
+            source,
+            data,
+            rollover,
+        } = &event
+        {
+            let previous = self
+                .events
+                .iter_mut()
+                .rev()
+                .find(|e| e.column_source() == Some(source));
+            if let Some(DocumentEvent::ColumnsStreamed {
+                data: previous_data,
+                rollover: previous_rollover,
+                ..
+            }) = previous
+            {
+                if previous_rollover == rollover {
+                    stream_columns(previous_data, data, *rollover);
+                    return;
+                }
+            }
+        }
+        self.events.push(event);
+    }
+
+    /// Whether the patch contains no changes
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.references.is_empty()
+    }
+
+    /// Contents of a PATCH-DOC message applying the patch
+    pub fn to_json(&self) -> Value {
+        let events: Vec<Value> = self.events.iter().map(|e| e.to_json()).collect();
+        json!({
+            "events": events,
+            "references": self.references,
+        })
+    }
+}
+
+/// Remembers the state of a document, so that later changes can be sent as a `Patch`
+#[derive(Clone, Debug, Default)]
+pub struct ChangeTracker {
+    models: HashMap<String, Value>,
+    root_ids: Vec<Value>,
+}
+
+impl ChangeTracker {
+    /// Create a tracker for which every model is new
+    pub fn new() -> Self {
+        ChangeTracker::default()
+    }
+
+    /// Create a tracker recording the current state of `doc`
+    pub fn from_document(doc: &ValidatedDocument) -> Self {
+        let mut tracker = ChangeTracker::new();
+        tracker.changes(doc);
+        tracker
+    }
+
+    /// Apply an event to the recorded state of the model it changes
+    ///
+    /// Use this for changes which have been sent by other means, such as streams to a data
+    /// source or changes made by a client. Returns whether the model is known to the tracker. If
+    /// it is not, the model is new and will be sent in full along with the other changes, so the
+    /// event should not be sent.
+    pub fn apply(&mut self, event: &DocumentEvent) -> bool {
+        if let DocumentEvent::ModelChanged { model, attr, new } = event {
+            return match self.models.get_mut(&model["id"].to_string()) {
+                Some(model) => {
+                    model["attributes"][attr.as_str()] = new.clone();
+                    true
+                }
+                None => false,
+            };
+        }
+
+        let source = match event.column_source() {
+            Some(source) => source,
+            None => return true,
+        };
+        let data = match self.models.get_mut(&source["id"].to_string()) {
+            Some(model) => &mut model["attributes"]["data"],
+            None => return false,
+        };
+
+        match event {
+            DocumentEvent::ColumnsStreamed {
+                data: new,
+                rollover,
+                ..
+            } => stream_columns(data, new, *rollover),
+            DocumentEvent::ColumnsPatched { patches, .. } => patch_columns(data, patches),
+            _ => {}
+        }
+        true
+    }
+
+    /// Changes made to `doc` since the tracker last saw it
+    pub fn changes(&mut self, doc: &ValidatedDocument) -> Patch {
+        self.changes_in(doc.references(), doc.root_ids())
+    }
+
+    /// Changes given the serialized models and root ids of a document
+    pub fn changes_in(&mut self, references: Vec<Value>, root_ids: Vec<Value>) -> Patch {
+        let mut patch = Patch::default();
+        let mut models = HashMap::new();
+
+        for model in references {
+            let id = model["id"].to_string();
+            match self.models.get(&id) {
+                Some(old) => {
+                    let model_ref = json!({ "id": model["id"], "type": model["type"] });
+                    for (attr, new) in changed_attributes(old, &model) {
+                        patch.events.push(DocumentEvent::ModelChanged {
+                            model: model_ref.clone(),
+                            attr,
+                            new,
+                        });
+                    }
+                }
+                None => patch.references.push(model.clone()),
+            }
+            models.insert(id, model);
+        }
+
+        for removed in self.root_ids.iter().filter(|id| !root_ids.contains(id)) {
+            let model = &self.models[&removed.to_string()];
+            patch.events.push(DocumentEvent::RootRemoved {
+                model: json!({ "id": model["id"], "type": model["type"] }),
+            });
+        }
+        for added in root_ids.iter().filter(|id| !self.root_ids.contains(id)) {
+            let model = &models[&added.to_string()];
+            patch.events.push(DocumentEvent::RootAdded {
+                model: json!({ "id": model["id"], "type": model["type"] }),
+            });
+        }
+
+        self.models = models;
+        self.root_ids = root_ids;
+        patch
+    }
+}
+
+/// Attributes whose values differ between two serialized versions of a model
+///
+/// An attribute which is no longer serialized was unset, meaning BokehJS should use its built-in
+/// default, so it is reset to that default rather than to `null`. Attributes without a known
+/// default, those whose defaults are other models, are left as they are.
+fn changed_attributes(old: &Value, new: &Value) -> Vec<(String, Value)> {
+    let empty = serde_json::Map::new();
+    let defaults = builtin_defaults(new["type"].as_str().unwrap_or_default()).unwrap_or(&empty);
+    let old = old["attributes"].as_object().unwrap_or(&empty);
+    let new = new["attributes"].as_object().unwrap_or(&empty);
+
+    let mut changes: Vec<(String, Value)> = new
+        .iter()
+        .filter(|(attr, value)| old.get(*attr) != Some(value))
+        .map(|(attr, value)| (attr.clone(), value.clone()))
+        .collect();
+    changes.extend(
+        old.keys()
+            .filter(|attr| !new.contains_key(*attr))
+            .filter_map(|attr| Some((attr.clone(), defaults.get(attr)?.clone()))),
+    );
+    changes
+}
+
+/// Append the rows of `new` to the columns of `data`, keeping at most `rollover` rows
+fn stream_columns(data: &mut Value, new: &Value, rollover: Option<usize>) {
+    let new = match new.as_object() {
+        Some(new) => new,
+        None => return,
+    };
+    for (name, values) in new {
+        let column = &mut data[name.as_str()];
+        if !column.is_array() {
+            *column = json!([]);
+        }
+        let column = column.as_array_mut().unwrap();
+        column.extend(values.as_array().into_iter().flatten().cloned());
+        if let Some(rollover) = rollover {
+            if column.len() > rollover {
+                column.drain(..column.len() - rollover);
+            }
+        }
+    }
+}
+
+/// Apply a change to the columns of a data source sent by BokehJS to `data`
+///
+/// Handles `ColumnsStreamed`, `ColumnsPatched` and `ColumnDataChanged` events, returning whether
+/// `event` was one of them.
+pub(crate) fn apply_column_event(data: &mut Value, event: &Value) -> bool {
+    match event["kind"].as_str() {
+        Some("ColumnsStreamed") => {
+            let rollover = event["rollover"].as_u64().map(|rollover| rollover as usize);
+            stream_columns(data, &event["data"], rollover);
+        }
+        Some("ColumnsPatched") => patch_columns(data, &event["patches"]),
+        Some("ColumnDataChanged") => {
+            let new = match event["new"].as_object() {
+                Some(new) => new,
+                None => return true,
+            };
+            for (name, values) in new {
+                let changed = match event["cols"].as_array() {
+                    Some(cols) => cols.iter().any(|col| col == name),
+                    None => true,
+                };
+                if changed {
+                    data[name.as_str()] = values.clone();
+                }
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Replace the values of `data` given as `[index, value]` pairs for each column
+fn patch_columns(data: &mut Value, patches: &Value) {
+    let patches = match patches.as_object() {
+        Some(patches) => patches,
+        None => return,
+    };
+    for (name, changes) in patches {
+        for change in changes.as_array().into_iter().flatten() {
+            let index = change[0].as_u64().unwrap_or_default() as usize;
+            if let Some(value) = data[name.as_str()].get_mut(index) {
+                *value = change[1].clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, ColumnDataSource, Document, Id, LinearAxis, Plot, Position};
+
+    fn document(plot: &Plot) -> ValidatedDocument {
+        let mut doc = Document::new();
+        doc.add_root(plot.clone());
+        doc.validate().unwrap()
+    }
+
+    #[test]
+    fn test_unchanged_document() {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+
+        let mut tracker = ChangeTracker::from_document(&document(&plot));
+        assert!(tracker.changes(&document(&plot)).is_empty());
+    }
+
+    #[test]
+    fn test_changed_attribute() {
+        let mut plot = Plot::with_id(Id::from("1001"));
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut tracker = ChangeTracker::from_document(&document(&plot));
+
+        plot.min_border = Some(40);
+        let patch = tracker.changes(&document(&plot));
+        assert_eq!(
+            patch.events,
+            vec![DocumentEvent::ModelChanged {
+                model: json!({"id": "1001", "type": "Plot"}),
+                attr: "min_border".to_string(),
+                new: json!(40),
+            }]
+        );
+        assert!(patch.references.is_empty());
+
+        plot.min_border = None;
+        let patch = tracker.changes(&document(&plot));
+        assert_eq!(
+            patch.to_json()["events"],
+            json!([{
+                "attr": "min_border",
+                "kind": "ModelChanged",
+                "model": {"id": "1001", "type": "Plot"},
+                "new": 5,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_cleared_attributes_reset_to_default() {
+        let mut plot = Plot::with_id(Id::from("1001"));
+        let mut circle = Circle::with_id(Id::from("1002"));
+        circle.fill_color = Some("red".to_string());
+        plot.add_glyph(&ColumnDataSource::new(), circle);
+        plot.plot_width = Some(300);
+        let mut tracker = ChangeTracker::from_document(&document(&plot));
+
+        let mut cleared = Plot::with_id(Id::from("1001"));
+        cleared.add_glyph(&ColumnDataSource::new(), Circle::with_id(Id::from("1002")));
+        let patch = tracker.changes(&document(&cleared));
+        let changed = |attr: &str| {
+            patch.events.iter().find_map(|e| match e {
+                DocumentEvent::ModelChanged { attr: a, new, .. } if a == attr => Some(new.clone()),
+                _ => None,
+            })
+        };
+        assert_eq!(changed("fill_color"), Some(json!({"value": "gray"})));
+        assert_eq!(changed("plot_width"), Some(json!(600)));
+        assert!(patch.events.iter().all(|e| match e {
+            DocumentEvent::ModelChanged { new, .. } => !new.is_null(),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn test_new_models_are_referenced() {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut tracker = ChangeTracker::from_document(&document(&plot));
+
+        plot.add_layout(Position::Below, LinearAxis::with_id(Id::from("2001")));
+        let patch = tracker.changes(&document(&plot));
+
+        let new_types: Vec<&Value> = patch.references.iter().map(|r| &r["type"]).collect();
+        assert!(new_types.contains(&&json!("LinearAxis")));
+        assert!(new_types.contains(&&json!("BasicTicker")));
+        assert!(patch.events.iter().any(|e| match e {
+            DocumentEvent::ModelChanged { attr, .. } => attr == "below",
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_root_changes() {
+        let mut first = Plot::with_id(Id::from("1001"));
+        first.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut second = Plot::with_id(Id::from("1002"));
+        second.add_glyph(&ColumnDataSource::new(), Circle::new());
+
+        let mut tracker = ChangeTracker::from_document(&document(&first));
+        let patch = tracker.changes(&document(&second));
+        assert_eq!(
+            patch.events,
+            vec![
+                DocumentEvent::RootRemoved {
+                    model: json!({"id": "1001", "type": "Plot"}),
+                },
+                DocumentEvent::RootAdded {
+                    model: json!({"id": "1002", "type": "Plot"}),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_streams_are_coalesced() {
+        let a = json!({"id": "1", "type": "ColumnDataSource"});
+        let b = json!({"id": "2", "type": "ColumnDataSource"});
+        let stream = |source: &Value, x: f64| DocumentEvent::ColumnsStreamed {
+            source: source.clone(),
+            data: json!({ "x": [x] }),
+            rollover: Some(2),
+        };
+
+        let mut patch = Patch::default();
+        patch.push(stream(&a, 1.0));
+        patch.push(stream(&b, 1.0));
+        patch.push(stream(&a, 2.0));
+        patch.push(stream(&a, 3.0));
+        assert_eq!(patch.events.len(), 2);
+        assert_eq!(
+            patch.events[0],
+            DocumentEvent::ColumnsStreamed {
+                source: a.clone(),
+                data: json!({"x": [2.0, 3.0]}),
+                rollover: Some(2),
+            }
+        );
+
+        patch.push(DocumentEvent::ColumnsPatched {
+            source: a.clone(),
+            patches: json!({"x": [[0, 5.0]]}),
+        });
+        patch.push(stream(&a, 4.0));
+        assert_eq!(patch.events.len(), 4);
+    }
+
+    #[test]
+    fn test_applied_streams_are_not_resent() {
+        let mut source = ColumnDataSource::with_id(Id::from("1001"));
+        source.add("x", &[1.0, 2.0]);
+        let mut plot = Plot::new();
+        plot.add_glyph(&source, Circle::new());
+        let mut doc = Document::new();
+        doc.add_root(plot);
+
+        let mut tracker = ChangeTracker::from_document(&doc.clone().validate().unwrap());
+
+        let mut data = HashMap::new();
+        data.insert("x".to_string(), vec![3.0]);
+        doc.stream(&Id::from("1001"), data, Some(2)).unwrap();
+        let mut patches = HashMap::new();
+        patches.insert("x".to_string(), vec![(0, 5.0)]);
+        doc.patch(&Id::from("1001"), patches).unwrap();
+
+        let events = doc.take_events();
+        assert_eq!(events.events.len(), 2);
+        assert!(events.events.iter().all(|e| tracker.apply(e)));
+        assert!(tracker.changes(&doc.validate().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_apply_column_event() {
+        let mut data = json!({ "x": [1, 2, 3], "y": [4, 5, 6] });
+        assert!(apply_column_event(
+            &mut data,
+            &json!({ "kind": "ColumnsPatched", "patches": { "x": [[0, 10]] } })
+        ));
+        assert!(apply_column_event(
+            &mut data,
+            &json!({ "kind": "ColumnsStreamed", "data": { "x": [7], "y": [8] }, "rollover": 3 })
+        ));
+        assert_eq!(data, json!({ "x": [2, 3, 7], "y": [5, 6, 8] }));
+
+        assert!(apply_column_event(
+            &mut data,
+            &json!({
+                "kind": "ColumnDataChanged",
+                "new": { "x": [0], "y": [0] },
+                "cols": ["y"],
+            })
+        ));
+        assert_eq!(data, json!({ "x": [2, 3, 7], "y": [0] }));
+
+        assert!(!apply_column_event(
+            &mut data,
+            &json!({ "kind": "ModelChanged" })
+        ));
+    }
+}
@@ -0,0 +1,609 @@
+//! Tick formatters, turning the ticks of an axis into labels
+
+use crate::{Id, LatLon, ToBokeh};
+use serde_json::{json, Map, Value};
+
+/// Formatters for the tick labels of axes
+#[derive(Clone)]
+pub enum TickFormatter {
+    /// Plain numbers, switching to scientific notation for very large or small values
+    BasicTickFormatter(BasicTickFormatter),
+    /// Powers of the base, e.g. `10^3`
+    LogTickFormatter(LogTickFormatter),
+    /// Dates and times
+    DatetimeTickFormatter(DatetimeTickFormatter),
+    /// Names of the categories
+    CategoricalTickFormatter(CategoricalTickFormatter),
+    /// Latitudes or longitudes
+    MercatorTickFormatter(MercatorTickFormatter),
+    /// Numbers formatted with a Numbro format string
+    NumeralTickFormatter(NumeralTickFormatter),
+    /// Numbers formatted with a printf style format string
+    PrintfTickFormatter(PrintfTickFormatter),
+    /// Labels computed by JavaScript code
+    FuncTickFormatter(FuncTickFormatter),
+}
+
+impl ToBokeh for TickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            TickFormatter::BasicTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::LogTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::DatetimeTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::CategoricalTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::MercatorTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::NumeralTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::PrintfTickFormatter(f) => f.as_bokeh_value(),
+            TickFormatter::FuncTickFormatter(f) => f.as_bokeh_value(),
+        }
+    }
+}
+
+// Basic tick formatter
+
+/// Struct dealing with basic tick formatting.
+#[derive(Clone, Default)]
+pub struct BasicTickFormatter {
+    id: Id,
+    /// Number of decimal places shown, chosen from the ticks if not set
+    pub precision: Option<u32>,
+    /// Whether to switch to scientific notation for very large or small values, true if not set
+    pub use_scientific: Option<bool>,
+    /// Power of ten above which scientific notation is used, 5 if not set
+    pub power_limit_high: Option<i32>,
+    /// Power of ten below which scientific notation is used, -3 if not set
+    pub power_limit_low: Option<i32>,
+}
+
+impl BasicTickFormatter {
+    /// Create a new BasicTickFormatter
+    pub fn new() -> BasicTickFormatter {
+        BasicTickFormatter::default()
+    }
+
+    /// Create a new BasicTickFormatter with a known id
+    pub fn with_id(id: Id) -> BasicTickFormatter {
+        BasicTickFormatter {
+            id,
+            ..BasicTickFormatter::default()
+        }
+    }
+}
+
+impl From<BasicTickFormatter> for TickFormatter {
+    fn from(f: BasicTickFormatter) -> TickFormatter {
+        TickFormatter::BasicTickFormatter(f)
+    }
+}
+
+impl ToBokeh for BasicTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(precision) = self.precision {
+            attributes["precision"] = json!(precision);
+        }
+        if let Some(use_scientific) = self.use_scientific {
+            attributes["use_scientific"] = json!(use_scientific);
+        }
+        if let Some(power_limit_high) = self.power_limit_high {
+            attributes["power_limit_high"] = json!(power_limit_high);
+        }
+        if let Some(power_limit_low) = self.power_limit_low {
+            attributes["power_limit_low"] = json!(power_limit_low);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "BasicTickFormatter",
+        })
+    }
+}
+
+// Log tick formatter
+
+/// Formats ticks as powers of ten, for log axes
+#[derive(Clone, Default)]
+pub struct LogTickFormatter {
+    id: Id,
+}
+
+impl LogTickFormatter {
+    /// Create a new LogTickFormatter
+    pub fn new() -> LogTickFormatter {
+        LogTickFormatter::default()
+    }
+
+    /// Create a new LogTickFormatter with a known id
+    pub fn with_id(id: Id) -> LogTickFormatter {
+        LogTickFormatter { id }
+    }
+}
+
+impl From<LogTickFormatter> for TickFormatter {
+    fn from(f: LogTickFormatter) -> TickFormatter {
+        TickFormatter::LogTickFormatter(f)
+    }
+}
+
+impl ToBokeh for LogTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "LogTickFormatter",
+        })
+    }
+}
+
+// DatetimeTickFormatter
+
+/// Formats ticks, given as milliseconds since the epoch, as dates and times
+///
+/// The format used depends on the spacing of the ticks, so it changes as the plot is zoomed. Each
+/// format is a list of `strftime` style format strings, of which BokehJS uses the first. For
+/// example, setting `days` to `["%a %d"]` labels daily ticks with the weekday and day of the
+/// month.
+#[derive(Clone, Default)]
+pub struct DatetimeTickFormatter {
+    id: Id,
+    /// Formats of ticks spaced in microseconds
+    pub microseconds: Option<Vec<String>>,
+    /// Formats of ticks spaced in milliseconds
+    pub milliseconds: Option<Vec<String>>,
+    /// Formats of ticks spaced in seconds
+    pub seconds: Option<Vec<String>>,
+    /// Formats of ticks spaced in minutes and seconds
+    pub minsec: Option<Vec<String>>,
+    /// Formats of ticks spaced in minutes
+    pub minutes: Option<Vec<String>>,
+    /// Formats of ticks spaced in hours and minutes
+    pub hourmin: Option<Vec<String>>,
+    /// Formats of ticks spaced in hours
+    pub hours: Option<Vec<String>>,
+    /// Formats of ticks spaced in days
+    pub days: Option<Vec<String>>,
+    /// Formats of ticks spaced in months
+    pub months: Option<Vec<String>>,
+    /// Formats of ticks spaced in years
+    pub years: Option<Vec<String>>,
+}
+
+impl DatetimeTickFormatter {
+    /// Create a new DatetimeTickFormatter
+    pub fn new() -> DatetimeTickFormatter {
+        DatetimeTickFormatter::default()
+    }
+
+    /// Create a new DatetimeTickFormatter with a known id
+    pub fn with_id(id: Id) -> DatetimeTickFormatter {
+        DatetimeTickFormatter {
+            id,
+            ..DatetimeTickFormatter::default()
+        }
+    }
+}
+
+impl From<DatetimeTickFormatter> for TickFormatter {
+    fn from(f: DatetimeTickFormatter) -> TickFormatter {
+        TickFormatter::DatetimeTickFormatter(f)
+    }
+}
+
+impl ToBokeh for DatetimeTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(microseconds) = &self.microseconds {
+            attributes["microseconds"] = json!(microseconds);
+        }
+        if let Some(milliseconds) = &self.milliseconds {
+            attributes["milliseconds"] = json!(milliseconds);
+        }
+        if let Some(seconds) = &self.seconds {
+            attributes["seconds"] = json!(seconds);
+        }
+        if let Some(minsec) = &self.minsec {
+            attributes["minsec"] = json!(minsec);
+        }
+        if let Some(minutes) = &self.minutes {
+            attributes["minutes"] = json!(minutes);
+        }
+        if let Some(hourmin) = &self.hourmin {
+            attributes["hourmin"] = json!(hourmin);
+        }
+        if let Some(hours) = &self.hours {
+            attributes["hours"] = json!(hours);
+        }
+        if let Some(days) = &self.days {
+            attributes["days"] = json!(days);
+        }
+        if let Some(months) = &self.months {
+            attributes["months"] = json!(months);
+        }
+        if let Some(years) = &self.years {
+            attributes["years"] = json!(years);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "DatetimeTickFormatter",
+        })
+    }
+}
+
+// CategoricalTickFormatter
+
+/// Labels ticks with the names of their categories
+#[derive(Clone, Default)]
+pub struct CategoricalTickFormatter {
+    id: Id,
+}
+
+impl CategoricalTickFormatter {
+    /// Create a new CategoricalTickFormatter
+    pub fn new() -> CategoricalTickFormatter {
+        CategoricalTickFormatter::default()
+    }
+
+    /// Create a new CategoricalTickFormatter with a known id
+    pub fn with_id(id: Id) -> CategoricalTickFormatter {
+        CategoricalTickFormatter { id }
+    }
+}
+
+impl From<CategoricalTickFormatter> for TickFormatter {
+    fn from(f: CategoricalTickFormatter) -> TickFormatter {
+        TickFormatter::CategoricalTickFormatter(f)
+    }
+}
+
+impl ToBokeh for CategoricalTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "CategoricalTickFormatter",
+        })
+    }
+}
+
+// MercatorTickFormatter
+
+/// Labels ticks of Web Mercator coordinates with their latitudes or longitudes
+#[derive(Clone, Default)]
+pub struct MercatorTickFormatter {
+    id: Id,
+    /// Whether the ticks are labelled with latitudes or longitudes
+    pub dimension: Option<LatLon>,
+}
+
+impl MercatorTickFormatter {
+    /// Create a new MercatorTickFormatter
+    pub fn new() -> MercatorTickFormatter {
+        MercatorTickFormatter::default()
+    }
+
+    /// Create a new MercatorTickFormatter with a known id
+    pub fn with_id(id: Id) -> MercatorTickFormatter {
+        MercatorTickFormatter {
+            id,
+            ..MercatorTickFormatter::default()
+        }
+    }
+}
+
+impl From<MercatorTickFormatter> for TickFormatter {
+    fn from(f: MercatorTickFormatter) -> TickFormatter {
+        TickFormatter::MercatorTickFormatter(f)
+    }
+}
+
+impl ToBokeh for MercatorTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimension) = self.dimension {
+            attributes["dimension"] = json!(dimension.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "MercatorTickFormatter",
+        })
+    }
+}
+
+// NumeralTickFormatter
+
+/// How a `NumeralTickFormatter` rounds numbers to fit its format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round to the nearest value
+    Round,
+    /// Round to the nearest value
+    Nearest,
+    /// Round towards negative infinity
+    Floor,
+    /// Round towards negative infinity
+    RoundDown,
+    /// Round towards positive infinity
+    Ceil,
+    /// Round towards positive infinity
+    RoundUp,
+}
+
+impl Rounding {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Rounding::Round => "round",
+            Rounding::Nearest => "nearest",
+            Rounding::Floor => "floor",
+            Rounding::RoundDown => "rounddown",
+            Rounding::Ceil => "ceil",
+            Rounding::RoundUp => "roundup",
+        }
+    }
+}
+
+/// Formats ticks with a [Numbro](http://numbrojs.com/format.html) format string
+///
+/// For example `"0.0a"` gives abbreviated magnitudes such as `1.2k`, `"$0,0.00"` currencies and
+/// `"0%"` percentages.
+#[derive(Clone)]
+pub struct NumeralTickFormatter {
+    id: Id,
+    /// Format of the labels
+    pub format: String,
+    /// Language to format the labels in, such as `"fr"`, English if not set
+    pub language: Option<String>,
+    /// How numbers are rounded, to the nearest value if not set
+    pub rounding: Option<Rounding>,
+}
+
+impl NumeralTickFormatter {
+    /// Create a new NumeralTickFormatter with format string `format`
+    pub fn new<S: Into<String>>(format: S) -> NumeralTickFormatter {
+        NumeralTickFormatter::with_id(Id::new(), format)
+    }
+
+    /// Create a new NumeralTickFormatter with format string `format` and a known id
+    pub fn with_id<S: Into<String>>(id: Id, format: S) -> NumeralTickFormatter {
+        NumeralTickFormatter {
+            id,
+            format: format.into(),
+            language: None,
+            rounding: None,
+        }
+    }
+}
+
+impl From<NumeralTickFormatter> for TickFormatter {
+    fn from(f: NumeralTickFormatter) -> TickFormatter {
+        TickFormatter::NumeralTickFormatter(f)
+    }
+}
+
+impl ToBokeh for NumeralTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "format": self.format,
+        });
+        if let Some(language) = &self.language {
+            attributes["language"] = json!(language);
+        }
+        if let Some(rounding) = self.rounding {
+            attributes["rounding"] = json!(rounding.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "NumeralTickFormatter",
+        })
+    }
+}
+
+// PrintfTickFormatter
+
+/// Formats ticks with a printf style format string, such as `"%5.2f mm"`
+#[derive(Clone)]
+pub struct PrintfTickFormatter {
+    id: Id,
+    /// Format of the labels
+    pub format: String,
+}
+
+impl PrintfTickFormatter {
+    /// Create a new PrintfTickFormatter with format string `format`
+    pub fn new<S: Into<String>>(format: S) -> PrintfTickFormatter {
+        PrintfTickFormatter::with_id(Id::new(), format)
+    }
+
+    /// Create a new PrintfTickFormatter with format string `format` and a known id
+    pub fn with_id<S: Into<String>>(id: Id, format: S) -> PrintfTickFormatter {
+        PrintfTickFormatter {
+            id,
+            format: format.into(),
+        }
+    }
+}
+
+impl From<PrintfTickFormatter> for TickFormatter {
+    fn from(f: PrintfTickFormatter) -> TickFormatter {
+        TickFormatter::PrintfTickFormatter(f)
+    }
+}
+
+impl ToBokeh for PrintfTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "format": self.format,
+            },
+            "id": self.id,
+            "type": "PrintfTickFormatter",
+        })
+    }
+}
+
+// FuncTickFormatter
+
+/// Formats ticks by running JavaScript code in the browser
+///
+/// The code is the body of a function which is given the tick as the variable `tick` and returns
+/// its label. Models added with `add_arg`, such as a data source holding the labels, are also
+/// available to it.
+#[derive(Clone, Default)]
+pub struct FuncTickFormatter {
+    id: Id,
+    /// Body of the function returning the label of `tick`
+    pub code: String,
+    args: Vec<(String, Value)>,
+}
+
+impl FuncTickFormatter {
+    /// Create a new FuncTickFormatter running `code`
+    pub fn new<S: Into<String>>(code: S) -> FuncTickFormatter {
+        FuncTickFormatter::with_id(Id::new(), code)
+    }
+
+    /// Create a new FuncTickFormatter running `code` with a known id
+    pub fn with_id<S: Into<String>>(id: Id, code: S) -> FuncTickFormatter {
+        FuncTickFormatter {
+            id,
+            code: code.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Make `model` available to the code as the variable `name`
+    ///
+    /// The model must also be part of the document, e.g. a data source of one of its plots.
+    pub fn add_arg<S, M>(&mut self, name: S, model: &M)
+    where
+        S: Into<String>,
+        M: ToBokeh,
+    {
+        self.args.push((name.into(), model.as_bokeh_ref()));
+    }
+}
+
+impl From<FuncTickFormatter> for TickFormatter {
+    fn from(f: FuncTickFormatter) -> TickFormatter {
+        TickFormatter::FuncTickFormatter(f)
+    }
+}
+
+impl ToBokeh for FuncTickFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let args: Map<String, Value> = self.args.iter().cloned().collect();
+        json!({
+            "attributes": {
+                "args": args,
+                "code": self.code,
+            },
+            "id": self.id,
+            "type": "FuncTickFormatter",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColumnDataSource;
+
+    #[test]
+    fn test_basic_tick_formatter() {
+        let mut formatter = BasicTickFormatter::with_id(Id::from("1001"));
+        assert_eq!(formatter.as_bokeh_value()["attributes"], json!({}));
+
+        formatter.precision = Some(2);
+        formatter.use_scientific = Some(true);
+        formatter.power_limit_high = Some(3);
+        formatter.power_limit_low = Some(-2);
+        assert_eq!(
+            TickFormatter::from(formatter).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "power_limit_high": 3,
+                    "power_limit_low": -2,
+                    "precision": 2,
+                    "use_scientific": true,
+                },
+                "id": "1001",
+                "type": "BasicTickFormatter",
+            })
+        );
+    }
+
+    #[test]
+    fn test_log_tick_formatter() {
+        assert_eq!(
+            TickFormatter::from(LogTickFormatter::with_id(Id::from("1001"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "LogTickFormatter"})
+        );
+    }
+
+    #[test]
+    fn test_numeral_tick_formatter() {
+        let mut formatter = NumeralTickFormatter::with_id(Id::from("1001"), "$0,0.00");
+        formatter.rounding = Some(Rounding::RoundDown);
+        assert_eq!(
+            TickFormatter::from(formatter).as_bokeh_value(),
+            json!({
+                "attributes": {"format": "$0,0.00", "rounding": "rounddown"},
+                "id": "1001",
+                "type": "NumeralTickFormatter",
+            })
+        );
+    }
+
+    #[test]
+    fn test_datetime_tick_formatter() {
+        let mut formatter = DatetimeTickFormatter::with_id(Id::from("1001"));
+        formatter.days = Some(vec!["%a %d".to_string()]);
+        formatter.months = Some(vec!["%b".to_string(), "%B %Y".to_string()]);
+        assert_eq!(
+            TickFormatter::from(formatter).as_bokeh_value(),
+            json!({
+                "attributes": {"days": ["%a %d"], "months": ["%b", "%B %Y"]},
+                "id": "1001",
+                "type": "DatetimeTickFormatter",
+            })
+        );
+    }
+
+    #[test]
+    fn test_printf_tick_formatter() {
+        assert_eq!(
+            TickFormatter::from(PrintfTickFormatter::with_id(Id::from("1001"), "%5.2f mm"))
+                .as_bokeh_value(),
+            json!({
+                "attributes": {"format": "%5.2f mm"},
+                "id": "1001",
+                "type": "PrintfTickFormatter",
+            })
+        );
+    }
+
+    #[test]
+    fn test_func_tick_formatter() {
+        let source = ColumnDataSource::with_id(Id::from("1002"));
+        let mut formatter =
+            FuncTickFormatter::with_id(Id::from("1001"), "return source.data.labels[tick]");
+        formatter.add_arg("source", &source);
+        assert_eq!(
+            TickFormatter::from(formatter).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "args": {"source": {"id": "1002", "type": "ColumnDataSource"}},
+                    "code": "return source.data.labels[tick]",
+                },
+                "id": "1001",
+                "type": "FuncTickFormatter",
+            })
+        );
+    }
+}
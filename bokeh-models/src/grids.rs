@@ -0,0 +1,166 @@
+//! Grids, drawn across a plot at the ticks of an axis
+
+use crate::{Dimension, Id, Ticker, ToBokeh};
+use serde_json::{json, Value};
+
+/// Lines across a plot at the ticks of one of its axes, with optional bands between them
+///
+/// Plots have no grid unless one is added with `Plot::add_grid`. To line up with the ticks of an
+/// axis, give the grid a copy of the axis' ticker.
+#[derive(Clone)]
+pub struct Grid {
+    id: Id,
+    /// Dimension of the axis the grid follows, so `Dimension::Width` draws vertical lines at the
+    /// ticks of the x axis
+    pub dimension: Dimension,
+    /// Ticker choosing where the lines go
+    pub ticker: Ticker,
+    /// Whether the grid is drawn, true if not set
+    pub visible: Option<bool>,
+    /// Colour of the grid lines
+    pub grid_line_color: Option<String>,
+    /// Width of the grid lines in pixels
+    pub grid_line_width: Option<f64>,
+    /// Opacity of the grid lines
+    pub grid_line_alpha: Option<f64>,
+    /// Dash pattern of the grid lines, as lengths in pixels of alternating dashes and gaps
+    pub grid_line_dash: Option<Vec<u32>>,
+    /// Colour of the lines at minor ticks, which are not drawn if not set
+    pub minor_grid_line_color: Option<String>,
+    /// Width of the lines at minor ticks in pixels
+    pub minor_grid_line_width: Option<f64>,
+    /// Opacity of the lines at minor ticks
+    pub minor_grid_line_alpha: Option<f64>,
+    /// Dash pattern of the lines at minor ticks
+    pub minor_grid_line_dash: Option<Vec<u32>>,
+    /// Colour of every other band between grid lines, which are not filled if not set
+    pub band_fill_color: Option<String>,
+    /// Opacity of the bands between grid lines
+    pub band_fill_alpha: Option<f64>,
+}
+
+impl Grid {
+    /// Create a new grid along `dimension` with lines at the ticks of `ticker`
+    pub fn new<T>(dimension: Dimension, ticker: T) -> Grid
+    where
+        T: Into<Ticker>,
+    {
+        Grid::with_id(Id::new(), dimension, ticker)
+    }
+
+    /// Create a new grid along `dimension` with lines at the ticks of `ticker` and a known id
+    pub fn with_id<T>(id: Id, dimension: Dimension, ticker: T) -> Grid
+    where
+        T: Into<Ticker>,
+    {
+        Grid {
+            id,
+            dimension,
+            ticker: ticker.into(),
+            visible: None,
+            grid_line_color: None,
+            grid_line_width: None,
+            grid_line_alpha: None,
+            grid_line_dash: None,
+            minor_grid_line_color: None,
+            minor_grid_line_width: None,
+            minor_grid_line_alpha: None,
+            minor_grid_line_dash: None,
+            band_fill_color: None,
+            band_fill_alpha: None,
+        }
+    }
+}
+
+impl ToBokeh for Grid {
+    fn as_bokeh_value(&self) -> Value {
+        let dimension = match self.dimension {
+            Dimension::Width => 0,
+            Dimension::Height => 1,
+        };
+        let mut attributes = json!({
+            "dimension": dimension,
+            "ticker": self.ticker.as_bokeh_ref(),
+        });
+        if let Some(visible) = self.visible {
+            attributes["visible"] = json!(visible);
+        }
+        if let Some(color) = &self.grid_line_color {
+            attributes["grid_line_color"] = json!({ "value": color });
+        }
+        if let Some(width) = self.grid_line_width {
+            attributes["grid_line_width"] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.grid_line_alpha {
+            attributes["grid_line_alpha"] = json!({ "value": alpha });
+        }
+        if let Some(dash) = &self.grid_line_dash {
+            attributes["grid_line_dash"] = json!(dash);
+        }
+        if let Some(color) = &self.minor_grid_line_color {
+            attributes["minor_grid_line_color"] = json!({ "value": color });
+        }
+        if let Some(width) = self.minor_grid_line_width {
+            attributes["minor_grid_line_width"] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.minor_grid_line_alpha {
+            attributes["minor_grid_line_alpha"] = json!({ "value": alpha });
+        }
+        if let Some(dash) = &self.minor_grid_line_dash {
+            attributes["minor_grid_line_dash"] = json!(dash);
+        }
+        if let Some(color) = &self.band_fill_color {
+            attributes["band_fill_color"] = json!({ "value": color });
+        }
+        if let Some(alpha) = self.band_fill_alpha {
+            attributes["band_fill_alpha"] = json!({ "value": alpha });
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Grid",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        vec![self.as_bokeh_value(), self.ticker.as_bokeh_value()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BasicTicker;
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::with_id(
+            Id::from("1001"),
+            Dimension::Height,
+            BasicTicker::with_id(Id::from("1002")),
+        );
+        grid.grid_line_dash = Some(vec![2, 2]);
+        grid.minor_grid_line_color = Some("#eeeeee".to_string());
+        grid.band_fill_color = Some("olive".to_string());
+        grid.band_fill_alpha = Some(0.1);
+
+        let references = grid.references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "band_fill_alpha": {"value": 0.1},
+                    "band_fill_color": {"value": "olive"},
+                    "dimension": 1,
+                    "grid_line_dash": [2, 2],
+                    "minor_grid_line_color": {"value": "#eeeeee"},
+                    "ticker": {"id": "1002", "type": "BasicTicker"},
+                },
+                "id": "1001",
+                "type": "Grid",
+            })
+        );
+        assert_eq!(references[1]["type"], "BasicTicker");
+    }
+}
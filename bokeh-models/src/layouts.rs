@@ -0,0 +1,985 @@
+//! Layouts, arranging several plots in one document
+//!
+//! These live in their own module as `Column` would otherwise clash with the columns of a
+//! `ColumnDataSource`.
+
+use crate::{
+    merge_tools, ColumnDataSource, DataRange1d, Id, Plot, Range, Result, ToBokeh, ToolbarBox,
+    ToolbarLocation, Widget,
+};
+use serde_json::{json, Value};
+
+/// How an item in a layout is sized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizingMode {
+    /// Keep the width and height the item is given
+    Fixed,
+    /// Fill all of the space available
+    StretchBoth,
+    /// Fill the available width, keeping the aspect ratio
+    ScaleWidth,
+    /// Fill the available height, keeping the aspect ratio
+    ScaleHeight,
+    /// Fill as much of the available space as possible, keeping the aspect ratio
+    ScaleBoth,
+}
+
+impl SizingMode {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SizingMode::Fixed => "fixed",
+            SizingMode::StretchBoth => "stretch_both",
+            SizingMode::ScaleWidth => "scale_width",
+            SizingMode::ScaleHeight => "scale_height",
+            SizingMode::ScaleBoth => "scale_both",
+        }
+    }
+}
+
+/// Anything which can be placed in a layout, or be the root of a document
+#[derive(Clone)]
+pub enum LayoutDom {
+    /// A single plot
+    Plot(Box<Plot>),
+    /// Items side by side
+    Row(Row),
+    /// Items one above the other
+    Column(Column),
+    /// Empty space
+    Spacer(Spacer),
+    /// Toolbar shared by several plots
+    ToolbarBox(ToolbarBox),
+    /// Items behind tabs, one shown at a time
+    Tabs(Tabs),
+    /// Interactive control
+    Widget(Box<Widget>),
+}
+
+impl LayoutDom {
+    /// Every plot in the layout, in the order they are laid out
+    pub fn plots_mut(&mut self) -> Vec<&mut Plot> {
+        match self {
+            LayoutDom::Plot(p) => vec![p],
+            other => other
+                .children_mut()
+                .into_iter()
+                .flat_map(|c| c.plots_mut())
+                .collect(),
+        }
+    }
+
+    /// Size this item with `sizing_mode`, along with every item inside it
+    ///
+    /// Items inside it which already have a sizing mode keep it, and pass it on to the items
+    /// inside them instead.
+    pub fn set_sizing_mode(&mut self, sizing_mode: SizingMode) {
+        *self.sizing_mode_mut() = Some(sizing_mode);
+        for child in self.children_mut() {
+            child.inherit_sizing_mode(sizing_mode);
+        }
+    }
+
+    /// Enable or disable this item along with every item inside it
+    ///
+    /// Disabled widgets are greyed out and ignore input until enabled again. Plots and toolbars
+    /// can't be disabled, and are left as they are.
+    pub fn set_disabled(&mut self, disabled: bool) {
+        if let Some(d) = self.disabled_mut() {
+            *d = Some(disabled);
+        }
+        for child in self.children_mut() {
+            child.set_disabled(disabled);
+        }
+    }
+
+    fn disabled_mut(&mut self) -> Option<&mut Option<bool>> {
+        match self {
+            LayoutDom::Plot(_) | LayoutDom::ToolbarBox(_) => None,
+            LayoutDom::Row(r) => Some(&mut r.disabled),
+            LayoutDom::Column(c) => Some(&mut c.disabled),
+            LayoutDom::Spacer(s) => Some(&mut s.disabled),
+            LayoutDom::Tabs(t) => Some(&mut t.disabled),
+            LayoutDom::Widget(w) => Some(&mut w.properties_mut().disabled),
+        }
+    }
+
+    fn inherit_sizing_mode(&mut self, sizing_mode: SizingMode) {
+        let sizing_mode = *self.sizing_mode_mut().get_or_insert(sizing_mode);
+        for child in self.children_mut() {
+            child.inherit_sizing_mode(sizing_mode);
+        }
+    }
+
+    fn sizing_mode_mut(&mut self) -> &mut Option<SizingMode> {
+        match self {
+            LayoutDom::Plot(p) => &mut p.sizing_mode,
+            LayoutDom::Row(r) => &mut r.sizing_mode,
+            LayoutDom::Column(c) => &mut c.sizing_mode,
+            LayoutDom::Spacer(s) => &mut s.sizing_mode,
+            LayoutDom::ToolbarBox(t) => &mut t.sizing_mode,
+            LayoutDom::Tabs(t) => &mut t.sizing_mode,
+            LayoutDom::Widget(w) => &mut w.properties_mut().sizing_mode,
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut LayoutDom> {
+        match self {
+            LayoutDom::Plot(_)
+            | LayoutDom::Spacer(_)
+            | LayoutDom::ToolbarBox(_)
+            | LayoutDom::Widget(_) => vec![],
+            LayoutDom::Row(r) => r.children.iter_mut().collect(),
+            LayoutDom::Column(c) => c.children.iter_mut().collect(),
+            LayoutDom::Tabs(t) => t.tabs.iter_mut().map(|p| &mut p.child).collect(),
+        }
+    }
+
+    fn children(&self) -> Vec<&LayoutDom> {
+        match self {
+            LayoutDom::Plot(_)
+            | LayoutDom::Spacer(_)
+            | LayoutDom::ToolbarBox(_)
+            | LayoutDom::Widget(_) => vec![],
+            LayoutDom::Row(r) => r.children.iter().collect(),
+            LayoutDom::Column(c) => c.children.iter().collect(),
+            LayoutDom::Tabs(t) => t.tabs.iter().map(|p| &p.child).collect(),
+        }
+    }
+
+    // Check every plot in the layout is sane
+    pub(crate) fn validate(&self) -> Result<()> {
+        if let LayoutDom::Plot(p) = self {
+            p.check()?;
+        }
+        for child in self.children() {
+            child.validate()?;
+        }
+        Ok(())
+    }
+
+    // Every copy of the data source with id `id`
+    pub(crate) fn sources_mut<'a>(&'a mut self, id: &'a Id) -> Vec<&'a mut ColumnDataSource> {
+        match self {
+            LayoutDom::Plot(p) => p.sources_mut(id).collect(),
+            LayoutDom::Widget(w) => w
+                .source_mut()
+                .into_iter()
+                .filter(|source| source.id() == id)
+                .collect(),
+            other => other
+                .children_mut()
+                .into_iter()
+                .flat_map(|c| c.sources_mut(id))
+                .collect(),
+        }
+    }
+}
+
+impl From<Plot> for LayoutDom {
+    fn from(p: Plot) -> LayoutDom {
+        LayoutDom::Plot(Box::new(p))
+    }
+}
+
+impl From<ToolbarBox> for LayoutDom {
+    fn from(t: ToolbarBox) -> LayoutDom {
+        LayoutDom::ToolbarBox(t)
+    }
+}
+
+impl ToBokeh for LayoutDom {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            LayoutDom::Plot(p) => p.as_bokeh_value(),
+            LayoutDom::Row(r) => r.as_bokeh_value(),
+            LayoutDom::Column(c) => c.as_bokeh_value(),
+            LayoutDom::Spacer(s) => s.as_bokeh_value(),
+            LayoutDom::ToolbarBox(t) => t.as_bokeh_value(),
+            LayoutDom::Tabs(t) => t.as_bokeh_value(),
+            LayoutDom::Widget(w) => w.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            LayoutDom::Plot(p) => p.references(),
+            LayoutDom::Row(r) => r.references(),
+            LayoutDom::Column(c) => c.references(),
+            LayoutDom::Spacer(s) => s.references(),
+            LayoutDom::ToolbarBox(t) => t.references(),
+            LayoutDom::Tabs(t) => t.references(),
+            LayoutDom::Widget(w) => w.references(),
+        }
+    }
+}
+
+// Row
+
+/// Items laid out side by side, from left to right
+#[derive(Clone, Default)]
+pub struct Row {
+    id: Id,
+    /// Items in the row
+    pub children: Vec<LayoutDom>,
+    /// Width of the row in pixels, fitted to its children if not set
+    pub width: Option<u32>,
+    /// Height of the row in pixels, fitted to its children if not set
+    pub height: Option<u32>,
+    /// How the row is sized, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+    /// Whether the row is greyed out and ignores input, false if not set
+    pub disabled: Option<bool>,
+    /// CSS classes added to the row's element, for styling from the page
+    pub css_classes: Vec<String>,
+}
+
+impl Row {
+    /// Create a new empty row
+    pub fn new() -> Row {
+        Row::default()
+    }
+
+    /// Create a new empty row with a known id
+    pub fn with_id(id: Id) -> Row {
+        Row {
+            id,
+            ..Row::default()
+        }
+    }
+
+    /// Add an item to the end of the row
+    pub fn add_child<L>(&mut self, child: L)
+    where
+        L: Into<LayoutDom>,
+    {
+        self.children.push(child.into());
+    }
+}
+
+impl From<Row> for LayoutDom {
+    fn from(r: Row) -> LayoutDom {
+        LayoutDom::Row(r)
+    }
+}
+
+impl ToBokeh for Row {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = box_attributes(&self.children);
+        add_dom_attributes(
+            &mut attributes,
+            self.width,
+            self.height,
+            self.sizing_mode,
+            self.disabled,
+            &self.css_classes,
+        );
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Row",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for child in &self.children {
+            out.extend(child.references());
+        }
+        out
+    }
+}
+
+// Column
+
+/// Items laid out one above the other, from top to bottom
+#[derive(Clone, Default)]
+pub struct Column {
+    id: Id,
+    /// Items in the column
+    pub children: Vec<LayoutDom>,
+    /// Width of the column in pixels, fitted to its children if not set
+    pub width: Option<u32>,
+    /// Height of the column in pixels, fitted to its children if not set
+    pub height: Option<u32>,
+    /// How the column is sized, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+    /// Whether the column is greyed out and ignores input, false if not set
+    pub disabled: Option<bool>,
+    /// CSS classes added to the column's element, for styling from the page
+    pub css_classes: Vec<String>,
+}
+
+impl Column {
+    /// Create a new empty column
+    pub fn new() -> Column {
+        Column::default()
+    }
+
+    /// Create a new empty column with a known id
+    pub fn with_id(id: Id) -> Column {
+        Column {
+            id,
+            ..Column::default()
+        }
+    }
+
+    /// Add an item to the bottom of the column
+    pub fn add_child<L>(&mut self, child: L)
+    where
+        L: Into<LayoutDom>,
+    {
+        self.children.push(child.into());
+    }
+}
+
+impl From<Column> for LayoutDom {
+    fn from(c: Column) -> LayoutDom {
+        LayoutDom::Column(c)
+    }
+}
+
+impl ToBokeh for Column {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = box_attributes(&self.children);
+        add_dom_attributes(
+            &mut attributes,
+            self.width,
+            self.height,
+            self.sizing_mode,
+            self.disabled,
+            &self.css_classes,
+        );
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Column",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for child in &self.children {
+            out.extend(child.references());
+        }
+        out
+    }
+}
+
+// Spacer
+
+/// Empty space in a layout
+///
+/// Give the spacer a `width` or `height` for a fixed gap, or a stretching `sizing_mode` to take
+/// up the space left over, pushing the items after it to the far end of a row or column.
+#[derive(Clone, Default)]
+pub struct Spacer {
+    id: Id,
+    /// Width of the space in pixels
+    pub width: Option<u32>,
+    /// Height of the space in pixels
+    pub height: Option<u32>,
+    /// How the space is sized, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+    /// Whether the space is greyed out, false if not set
+    pub disabled: Option<bool>,
+    /// CSS classes added to the space's element, for styling from the page
+    pub css_classes: Vec<String>,
+}
+
+impl Spacer {
+    /// Create a new spacer
+    pub fn new() -> Spacer {
+        Spacer::default()
+    }
+
+    /// Create a new spacer with a known id
+    pub fn with_id(id: Id) -> Spacer {
+        Spacer {
+            id,
+            ..Spacer::default()
+        }
+    }
+}
+
+impl From<Spacer> for LayoutDom {
+    fn from(s: Spacer) -> LayoutDom {
+        LayoutDom::Spacer(s)
+    }
+}
+
+impl ToBokeh for Spacer {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        add_dom_attributes(
+            &mut attributes,
+            self.width,
+            self.height,
+            self.sizing_mode,
+            self.disabled,
+            &self.css_classes,
+        );
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Spacer",
+        })
+    }
+}
+
+// Tabs
+
+/// Tab holding one item of a `Tabs` layout
+#[derive(Clone)]
+pub struct Panel {
+    id: Id,
+    /// Title shown on the tab
+    pub title: String,
+    /// Item shown when the tab is active
+    pub child: LayoutDom,
+    /// Whether the tab can be closed, false if not set
+    pub closable: Option<bool>,
+}
+
+impl Panel {
+    /// Create a new tab showing `child` under `title`
+    pub fn new<S, L>(title: S, child: L) -> Panel
+    where
+        S: Into<String>,
+        L: Into<LayoutDom>,
+    {
+        Panel::with_id(Id::new(), title, child)
+    }
+
+    /// Create a new tab showing `child` under `title` with a known id
+    pub fn with_id<S, L>(id: Id, title: S, child: L) -> Panel
+    where
+        S: Into<String>,
+        L: Into<LayoutDom>,
+    {
+        Panel {
+            id,
+            title: title.into(),
+            child: child.into(),
+            closable: None,
+        }
+    }
+}
+
+impl ToBokeh for Panel {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "child": self.child.as_bokeh_ref(),
+            "title": self.title,
+        });
+        if let Some(closable) = self.closable {
+            attributes["closable"] = json!(closable);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Panel",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.child.references());
+        out
+    }
+}
+
+/// Items behind a row of tabs, showing one at a time
+#[derive(Clone, Default)]
+pub struct Tabs {
+    id: Id,
+    /// Tabs in order from left to right
+    pub tabs: Vec<Panel>,
+    /// Index of the tab shown, the first if not set
+    pub active: Option<usize>,
+    /// Width of the tabs in pixels
+    pub width: Option<u32>,
+    /// Height of the tabs in pixels
+    pub height: Option<u32>,
+    /// How the tabs are sized, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+    /// Whether the tabs are greyed out and ignore clicks, false if not set
+    pub disabled: Option<bool>,
+    /// CSS classes added to the tabs' element, for styling from the page
+    pub css_classes: Vec<String>,
+}
+
+impl Tabs {
+    /// Create a new layout with no tabs
+    pub fn new() -> Tabs {
+        Tabs::default()
+    }
+
+    /// Create a new layout with no tabs and a known id
+    pub fn with_id(id: Id) -> Tabs {
+        Tabs {
+            id,
+            ..Tabs::default()
+        }
+    }
+
+    /// Add a tab after the existing ones
+    pub fn add_tab(&mut self, tab: Panel) {
+        self.tabs.push(tab);
+    }
+}
+
+impl From<Tabs> for LayoutDom {
+    fn from(t: Tabs) -> LayoutDom {
+        LayoutDom::Tabs(t)
+    }
+}
+
+impl ToBokeh for Tabs {
+    fn as_bokeh_value(&self) -> Value {
+        let tabs: Vec<Value> = self.tabs.iter().map(|t| t.as_bokeh_ref()).collect();
+        let mut attributes = json!({ "tabs": tabs });
+        if let Some(active) = self.active {
+            attributes["active"] = json!(active);
+        }
+        add_dom_attributes(
+            &mut attributes,
+            self.width,
+            self.height,
+            self.sizing_mode,
+            self.disabled,
+            &self.css_classes,
+        );
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Tabs",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for tab in &self.tabs {
+            out.extend(tab.references());
+        }
+        out
+    }
+}
+
+fn box_attributes(children: &[LayoutDom]) -> Value {
+    let children: Vec<Value> = children.iter().map(|c| c.as_bokeh_ref()).collect();
+    json!({ "children": children })
+}
+
+// Attributes shared by the rows, columns, spacers and tabs of a layout
+fn add_dom_attributes(
+    attributes: &mut Value,
+    width: Option<u32>,
+    height: Option<u32>,
+    sizing_mode: Option<SizingMode>,
+    disabled: Option<bool>,
+    css_classes: &[String],
+) {
+    if let Some(width) = width {
+        attributes["width"] = json!(width);
+    }
+    if let Some(height) = height {
+        attributes["height"] = json!(height);
+    }
+    if let Some(sizing_mode) = sizing_mode {
+        attributes["sizing_mode"] = json!(sizing_mode.name());
+    }
+    if let Some(disabled) = disabled {
+        attributes["disabled"] = json!(disabled);
+    }
+    if !css_classes.is_empty() {
+        attributes["css_classes"] = json!(css_classes);
+    }
+}
+
+// Helpers
+
+/// Lay out `children` side by side, as `bokeh.layouts.row` does in Python
+///
+/// To mix plots with widgets or other items, convert each into a `LayoutDom` first.
+pub fn row<I, L>(children: I) -> Row
+where
+    I: IntoIterator<Item = L>,
+    L: Into<LayoutDom>,
+{
+    Row {
+        children: children.into_iter().map(Into::into).collect(),
+        ..Row::default()
+    }
+}
+
+/// Lay out `children` one above the other, as `bokeh.layouts.column` does in Python
+///
+/// To mix plots with widgets or other items, convert each into a `LayoutDom` first.
+pub fn column<I, L>(children: I) -> Column
+where
+    I: IntoIterator<Item = L>,
+    L: Into<LayoutDom>,
+{
+    Column {
+        children: children.into_iter().map(Into::into).collect(),
+        ..Column::default()
+    }
+}
+
+/// Lay out `rows` of items one above the other, all sized with `sizing_mode`, as
+/// `bokeh.layouts.layout` does in Python
+///
+/// Each row is laid out side by side. Items can themselves be rows and columns, for deeper
+/// nesting. Items which already have a sizing mode keep it.
+pub fn layout<I, R>(rows: I, sizing_mode: SizingMode) -> LayoutDom
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = LayoutDom>,
+{
+    let mut layout: LayoutDom = column(rows.into_iter().map(row)).into();
+    layout.set_sizing_mode(sizing_mode);
+    layout
+}
+
+/// Options of `gridplot`
+#[derive(Clone)]
+pub struct GridPlotOptions {
+    /// Whether to move the tools of every plot onto one toolbar, true by default
+    pub merge_tools: bool,
+    /// Side of the grid the merged toolbar is placed on, above by default
+    ///
+    /// `ToolbarLocation::Hidden` merges the tools without showing the toolbar.
+    pub toolbar_location: ToolbarLocation,
+    /// Whether every plot shares the x range of the first plot, so panning one pans them all
+    pub share_x_range: bool,
+    /// Whether every plot shares the y range of the first plot
+    pub share_y_range: bool,
+}
+
+impl Default for GridPlotOptions {
+    fn default() -> GridPlotOptions {
+        GridPlotOptions {
+            merge_tools: true,
+            toolbar_location: ToolbarLocation::Above,
+            share_x_range: false,
+            share_y_range: false,
+        }
+    }
+}
+
+/// Lay out rows of plots in a grid, as `bokeh.layouts.gridplot` does in Python
+///
+/// Gaps in the grid are given as `None`, and filled with spacers.
+pub fn gridplot<I, R>(children: I, options: GridPlotOptions) -> LayoutDom
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = Option<Plot>>,
+{
+    let mut grid = Column::new();
+    for children in children {
+        let mut row = Row::new();
+        for child in children {
+            match child {
+                Some(plot) => row.add_child(plot),
+                None => row.add_child(Spacer::new()),
+            }
+        }
+        grid.add_child(row);
+    }
+    let mut grid = LayoutDom::from(grid);
+
+    let mut plots = grid.plots_mut();
+    if options.share_x_range {
+        share_range(&mut plots, |plot| &mut plot.x_range);
+    }
+    if options.share_y_range {
+        share_range(&mut plots, |plot| &mut plot.y_range);
+    }
+    if !options.merge_tools {
+        return grid;
+    }
+
+    let mut toolbar_box = merge_tools(plots);
+    toolbar_box.toolbar_location = Some(options.toolbar_location);
+    match options.toolbar_location {
+        ToolbarLocation::Above => column(vec![toolbar_box.into(), grid]).into(),
+        ToolbarLocation::Below => column(vec![grid, toolbar_box.into()]).into(),
+        ToolbarLocation::Left => row(vec![toolbar_box.into(), grid]).into(),
+        ToolbarLocation::Right => row(vec![grid, toolbar_box.into()]).into(),
+        ToolbarLocation::Hidden => grid,
+    }
+}
+
+// Give every plot a copy of the first plot's range, which share an id
+fn share_range<F>(plots: &mut [&mut Plot], mut range: F)
+where
+    F: FnMut(&mut Plot) -> &mut Option<Range>,
+{
+    let shared = match plots.first_mut() {
+        Some(first) => range(first)
+            .get_or_insert_with(|| DataRange1d::new().into())
+            .clone(),
+        None => return,
+    };
+    for plot in plots.iter_mut() {
+        *range(plot) = Some(shared.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Button, Circle, Document, PanTool};
+
+    fn plot(id: &str) -> Plot {
+        let mut plot = Plot::with_id(Id::from(id));
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        plot
+    }
+
+    #[test]
+    fn test_row_and_column() {
+        let mut top = row(vec![plot("1001"), plot("1002")]);
+        top.height = Some(300);
+        let mut layout = Column::with_id(Id::from("1003"));
+        layout.add_child(top);
+        layout.add_child(plot("1004"));
+
+        let value = layout.as_bokeh_value();
+        assert_eq!(value["type"], "Column");
+        assert_eq!(value["attributes"]["children"][0]["type"], "Row");
+        assert_eq!(
+            value["attributes"]["children"][1],
+            json!({"id": "1004", "type": "Plot"})
+        );
+        let references = layout.references();
+        assert_eq!(references[1]["attributes"]["height"], 300);
+        assert_eq!(references[1]["attributes"]["children"][1]["id"], "1002");
+
+        let mut layout = LayoutDom::from(layout);
+        let ids: Vec<Value> = layout
+            .plots_mut()
+            .iter()
+            .map(|p| p.as_bokeh_ref()["id"].clone())
+            .collect();
+        assert_eq!(ids, vec!["1001", "1002", "1004"]);
+    }
+
+    #[test]
+    fn test_document_with_layout() {
+        let mut doc = Document::new();
+        doc.add_root(column(vec![plot("1001"), Plot::with_id(Id::from("1002"))]));
+        assert!(doc.clone().validate().is_err());
+
+        *doc.root_mut().unwrap().plots_mut()[1] = plot("1002");
+        let doc = doc.validate().unwrap();
+        let types: Vec<Value> = doc.references().iter().map(|r| r["type"].clone()).collect();
+        assert_eq!(types.iter().filter(|t| *t == "Plot").count(), 2);
+        assert_eq!(types.iter().filter(|t| *t == "Column").count(), 1);
+        assert_eq!(doc.root_ids()[0], doc.references()[0]["id"]);
+    }
+
+    #[test]
+    fn test_gridplot() {
+        let mut first = plot("1001");
+        first.add_tool(PanTool::with_id(Id::from("1002")));
+        let mut second = plot("1003");
+        second.add_tool(PanTool::with_id(Id::from("1004")));
+
+        let options = GridPlotOptions {
+            share_x_range: true,
+            ..GridPlotOptions::default()
+        };
+        let mut grid = gridplot(
+            vec![vec![Some(first), None], vec![None, Some(second)]],
+            options,
+        );
+
+        let value = grid.as_bokeh_value();
+        assert_eq!(value["type"], "Column");
+        let references = grid.references();
+        assert_eq!(references[1]["type"], "ToolbarBox");
+        assert_eq!(references[1]["attributes"]["toolbar_location"], "above");
+        assert_eq!(
+            references[2]["attributes"]["tools"],
+            json!([
+                {"id": "1002", "type": "PanTool"},
+                {"id": "1004", "type": "PanTool"},
+            ])
+        );
+        let types: Vec<Value> = references.iter().map(|r| r["type"].clone()).collect();
+        assert_eq!(types.iter().filter(|t| *t == "Spacer").count(), 2);
+        assert_eq!(types.iter().filter(|t| *t == "Row").count(), 2);
+
+        let plots = grid.plots_mut();
+        assert_eq!(plots[0].toolbar_location, Some(ToolbarLocation::Hidden));
+        let x_range = plots[0].as_bokeh_value()["attributes"]["x_range"].clone();
+        assert_eq!(x_range["type"], "DataRange1d");
+        assert_eq!(plots[1].as_bokeh_value()["attributes"]["x_range"], x_range);
+        assert!(plots[1].y_range.is_none());
+    }
+
+    #[test]
+    fn test_gridplot_without_merged_tools() {
+        let options = GridPlotOptions {
+            merge_tools: false,
+            ..GridPlotOptions::default()
+        };
+        let grid = gridplot(vec![vec![Some(plot("1001")), Some(plot("1002"))]], options);
+        let references = grid.references();
+        assert_eq!(references[0]["type"], "Column");
+        assert!(references.iter().all(|r| r["type"] != "ToolbarBox"));
+    }
+
+    #[test]
+    fn test_layout_sizing_mode() {
+        let mut fixed = plot("1003");
+        fixed.sizing_mode = Some(SizingMode::Fixed);
+        let mut nested = column(vec![LayoutDom::from(fixed), Spacer::new().into()]);
+        nested.sizing_mode = Some(SizingMode::ScaleWidth);
+
+        let layout = layout(
+            vec![
+                vec![
+                    plot("1001").into(),
+                    Spacer::with_id(Id::from("1002")).into(),
+                ],
+                vec![nested.into()],
+            ],
+            SizingMode::StretchBoth,
+        );
+
+        let references = layout.references();
+        let sizing_mode = |id: &str| {
+            references
+                .iter()
+                .find(|r| r["id"] == id)
+                .map(|r| r["attributes"]["sizing_mode"].clone())
+                .unwrap()
+        };
+        assert_eq!(references[0]["type"], "Column");
+        assert_eq!(references[0]["attributes"]["sizing_mode"], "stretch_both");
+        assert_eq!(references[1]["type"], "Row");
+        assert_eq!(references[1]["attributes"]["sizing_mode"], "stretch_both");
+        assert_eq!(sizing_mode("1001"), "stretch_both");
+        assert_eq!(sizing_mode("1002"), "stretch_both");
+        assert_eq!(sizing_mode("1003"), "fixed");
+        let spacers: Vec<&Value> = references
+            .iter()
+            .filter(|r| r["type"] == "Spacer" && r["id"] != "1002")
+            .collect();
+        assert_eq!(spacers[0]["attributes"]["sizing_mode"], "scale_width");
+    }
+
+    #[test]
+    fn test_tabs() {
+        let mut tabs = Tabs::with_id(Id::from("1001"));
+        tabs.add_tab(Panel::with_id(Id::from("1002"), "First", plot("1003")));
+        let mut second = Panel::with_id(Id::from("1004"), "Second", row(vec![plot("1005")]));
+        second.closable = Some(true);
+        tabs.add_tab(second);
+        tabs.active = Some(1);
+
+        let mut tabs = LayoutDom::from(tabs);
+        let references = tabs.references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "active": 1,
+                    "tabs": [
+                        {"id": "1002", "type": "Panel"},
+                        {"id": "1004", "type": "Panel"},
+                    ],
+                },
+                "id": "1001",
+                "type": "Tabs",
+            })
+        );
+        assert_eq!(
+            references[1]["attributes"],
+            json!({"child": {"id": "1003", "type": "Plot"}, "title": "First"})
+        );
+        assert!(tabs.validate().is_ok());
+        assert_eq!(tabs.plots_mut().len(), 2);
+    }
+
+    #[test]
+    fn test_spacer() {
+        let mut gap = Spacer::with_id(Id::from("1001"));
+        gap.width = Some(50);
+        let mut filler = Spacer::with_id(Id::from("1002"));
+        filler.sizing_mode = Some(SizingMode::StretchBoth);
+
+        let references = row(vec![
+            LayoutDom::from(gap),
+            plot("1003").into(),
+            filler.into(),
+        ])
+        .references();
+        assert_eq!(
+            references[1],
+            json!({"attributes": {"width": 50}, "id": "1001", "type": "Spacer"})
+        );
+        assert_eq!(
+            references.last().unwrap(),
+            &json!({
+                "attributes": {"sizing_mode": "stretch_both"},
+                "id": "1002",
+                "type": "Spacer",
+            })
+        );
+    }
+
+    #[test]
+    fn test_disabled_and_css_classes() {
+        let mut controls = Column::with_id(Id::from("1001"));
+        controls.css_classes = vec!["controls".to_string()];
+        controls.add_child(Button::with_id(Id::from("1002"), "Run"));
+        let mut dashboard = row(vec![LayoutDom::from(controls), plot("1003").into()]);
+        dashboard.css_classes = vec!["dashboard".to_string(), "dark".to_string()];
+
+        let mut dashboard = LayoutDom::from(dashboard);
+        dashboard.set_disabled(true);
+        let references = dashboard.references();
+        assert_eq!(
+            references[0]["attributes"]["css_classes"],
+            json!(["dashboard", "dark"])
+        );
+        assert_eq!(references[0]["attributes"]["disabled"], true);
+        assert_eq!(
+            references[1]["attributes"],
+            json!({
+                "children": [{"id": "1002", "type": "Button"}],
+                "css_classes": ["controls"],
+                "disabled": true,
+            })
+        );
+        assert_eq!(references[2]["attributes"]["disabled"], true);
+        assert!(references
+            .iter()
+            .filter(|r| r["type"] == "Plot")
+            .all(|r| r["attributes"].get("disabled").is_none()));
+
+        dashboard.set_disabled(false);
+        assert_eq!(dashboard.references()[2]["attributes"]["disabled"], false);
+    }
+
+    #[test]
+    fn test_plot_size() {
+        let mut plot = plot("1001");
+        plot.plot_width = Some(400);
+        plot.plot_height = Some(250);
+        plot.sizing_mode = Some(SizingMode::ScaleWidth);
+
+        let attributes = LayoutDom::from(plot).as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["plot_width"], 400);
+        assert_eq!(attributes["plot_height"], 250);
+        assert_eq!(attributes["sizing_mode"], "scale_width");
+    }
+}
@@ -2,12 +2,97 @@
 
 #![deny(missing_docs)]
 
+use askama::Template;
 use failure::format_err;
-use serde_json::{json, to_string, Value};
-use std::collections::HashMap;
+use serde_derive::Serialize;
+use serde_json::{json, to_string, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(any(feature = "bokeh-axum", feature = "bokeh-actix"))]
+pub mod adapters;
+mod annotations;
+mod arrow_heads;
+mod axes;
+mod callbacks;
+mod defaults;
+pub mod embed;
+pub mod events;
+mod formatters;
+mod grids;
+pub mod layouts;
+pub mod mercator;
+mod ranges;
+mod scales;
+pub mod server;
+mod tables;
+pub mod themes;
+mod tickers;
+mod tools;
+mod widgets;
+
+pub use crate::annotations::*;
+pub use crate::arrow_heads::*;
+pub use crate::axes::*;
+use crate::callbacks::JsCallbacks;
+pub use crate::callbacks::*;
+pub use crate::formatters::*;
+pub use crate::grids::*;
+use crate::layouts::{LayoutDom, SizingMode};
+pub use crate::ranges::*;
+pub use crate::scales::*;
+pub use crate::tables::*;
+pub use crate::tickers::*;
+pub use crate::tools::*;
+pub use crate::widgets::*;
 
 type Result<T> = std::result::Result<T, failure::Error>;
 
+/// Version of BokehJS that the generated documents target
+pub const BOKEH_VERSION: &str = "1.0.3";
+
+// Ids
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1000);
+
+/// Unique identifier of a model within a document
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Id(String);
+
+impl Id {
+    /// Generate a new unique id
+    pub fn new() -> Self {
+        Id(NEXT_ID.fetch_add(1, Ordering::SeqCst).to_string())
+    }
+}
+
+impl Default for Id {
+    fn default() -> Self {
+        Id::new()
+    }
+}
+
+impl From<&str> for Id {
+    fn from(s: &str) -> Self {
+        Id(s.to_string())
+    }
+}
+
+impl From<String> for Id {
+    fn from(s: String) -> Self {
+        Id(s)
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Trait encoding the ability to transform the type into their Bokeh representation
 pub trait ToBokeh {
     /// Compulsory method for converting Bokeh model into serializable JSON
@@ -16,6 +101,26 @@ pub trait ToBokeh {
     /// BokehJS in the browser
     fn as_bokeh_value(&self) -> Value;
 
+    /// Reference to the model, for embedding in the attributes of other models
+    ///
+    /// Automatically implemented from the `id` and `type` keys of
+    /// `ToBokeh::as_bokeh_value`.
+    fn as_bokeh_ref(&self) -> Value {
+        let value = self.as_bokeh_value();
+        json!({
+            "id": value["id"],
+            "type": value["type"],
+        })
+    }
+
+    /// Serialized representations of this model and every model it refers to
+    ///
+    /// Models which hold other models must override this so that the sub-models end up in the
+    /// document.
+    fn references(&self) -> Vec<Value> {
+        vec![self.as_bokeh_value()]
+    }
+
     /// Convert a bokeh struct to string
     ///
     /// Automatically implemented for objects based on their `ToBokeh::as_bokeh_value`
@@ -28,15 +133,54 @@ pub trait ToBokeh {
 // ColumnDataSource
 
 /// Column data source for handling columar data
+#[derive(Clone, Default)]
 pub struct ColumnDataSource {
-    columns: HashMap<String, Vec<f64>>,
+    id: Id,
+    columns: HashMap<String, Column>,
+    selected: Selection,
+    js_callbacks: JsCallbacks,
+}
+
+/// Values of a column of a data source
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum Column {
+    Numbers(Vec<f64>),
+    Factors(Vec<Factor>),
+    Lists(Vec<Vec<f64>>),
+}
+
+impl Column {
+    // Values of the column as text, for labels
+    fn labels(&self) -> Vec<String> {
+        match self {
+            Column::Numbers(numbers) => numbers.iter().map(ToString::to_string).collect(),
+            Column::Factors(factors) => factors.iter().map(Factor::label).collect(),
+            Column::Lists(lists) => lists.iter().map(|list| format!("{:?}", list)).collect(),
+        }
+    }
+
+    // What the values of the column are, for errors
+    fn kind(&self) -> &'static str {
+        match self {
+            Column::Numbers(_) => "numbers",
+            Column::Factors(_) => "categories",
+            Column::Lists(_) => "lists",
+        }
+    }
 }
 
 impl ColumnDataSource {
     /// Create a new default column data source
     pub fn new() -> Self {
+        ColumnDataSource::default()
+    }
+
+    /// Create a new column data source with a known id
+    pub fn with_id(id: Id) -> Self {
         ColumnDataSource {
-            columns: HashMap::new(),
+            id,
+            ..ColumnDataSource::default()
         }
     }
 
@@ -45,20 +189,255 @@ impl ColumnDataSource {
     where
         S: Into<String>,
     {
-        self.columns.insert(key.into(), values.to_vec());
+        self.columns
+            .insert(key.into(), Column::Numbers(values.to_vec()));
+    }
+
+    /// Add a column of categories to the data source, for glyphs on categorical plots
+    ///
+    /// Columns of categories can't be streamed or patched.
+    pub fn add_factors<S, I>(&mut self, key: S, factors: I)
+    where
+        S: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<Factor>,
+    {
+        let factors = factors.into_iter().map(Into::into).collect();
+        self.columns.insert(key.into(), Column::Factors(factors));
+    }
+
+    /// Add a column holding a list of numbers in each row, such as the vertices of `Patches` and
+    /// `MultiLine` glyphs
+    ///
+    /// Columns of lists can't be streamed or patched.
+    pub fn add_lists<S>(&mut self, key: S, lists: &[Vec<f64>])
+    where
+        S: Into<String>,
+    {
+        self.columns
+            .insert(key.into(), Column::Lists(lists.to_vec()));
+    }
+
+    /// Id of the data source
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// Rows currently selected
+    pub fn selected(&self) -> &Selection {
+        &self.selected
+    }
+
+    /// Select the rows at `indices`, replacing the current selection
+    pub fn select(&mut self, indices: &[usize]) {
+        self.selected.indices = indices.to_vec();
+    }
+
+    /// Run `callback` in the browser when `event` happens to the source
+    ///
+    /// `event` is either the name of a property, such as `"data"`, to run the callback whenever it
+    /// changes, or `"streaming"` or `"patching"` to run it whenever rows are streamed to or patched
+    /// in the source.
+    pub fn js_on_change<S>(&mut self, event: S, callback: CustomJS)
+    where
+        S: AsRef<str>,
+    {
+        match event.as_ref() {
+            event @ "streaming" | event @ "patching" => {
+                self.js_callbacks.add(event.to_string(), callback)
+            }
+            attr => self.js_callbacks.add_change(attr, callback),
+        }
+    }
+
+    /// Append rows to the columns, keeping at most `rollover` rows if given
+    ///
+    /// `data` must hold the same number of new values for every column of the source.
+    pub fn stream(
+        &mut self,
+        data: &HashMap<String, Vec<f64>>,
+        rollover: Option<usize>,
+    ) -> Result<()> {
+        if !self.columns.is_empty()
+            && (data.len() != self.columns.len()
+                || data.keys().any(|name| !self.columns.contains_key(name)))
+        {
+            return Err(format_err!(
+                "streamed data must contain exactly the columns of the source"
+            ));
+        }
+        for name in data.keys() {
+            match self.columns.get(name) {
+                Some(Column::Numbers(_)) | None => {}
+                Some(column) => {
+                    return Err(format_err!(
+                        "column `{}` of {} can't be streamed",
+                        name,
+                        column.kind()
+                    ))
+                }
+            }
+        }
+        let mut lengths = data.values().map(Vec::len);
+        if let Some(length) = lengths.next() {
+            if lengths.any(|l| l != length) {
+                return Err(format_err!("streamed columns must have the same length"));
+            }
+        }
+
+        for (name, values) in data {
+            let column = match self
+                .columns
+                .entry(name.clone())
+                .or_insert_with(|| Column::Numbers(Vec::new()))
+            {
+                Column::Numbers(column) => column,
+                _ => unreachable!("checked above"),
+            };
+            column.extend_from_slice(values);
+            if let Some(rollover) = rollover {
+                if column.len() > rollover {
+                    column.drain(..column.len() - rollover);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace individual values, given as `(index, value)` pairs for each column
+    pub fn patch(&mut self, patches: &HashMap<String, Vec<(usize, f64)>>) -> Result<()> {
+        for (name, changes) in patches {
+            let column = match self.columns.get(name) {
+                Some(Column::Numbers(column)) => column,
+                Some(column) => {
+                    return Err(format_err!(
+                        "column `{}` of {} can't be patched",
+                        name,
+                        column.kind()
+                    ))
+                }
+                None => return Err(format_err!("no column `{}` to patch", name)),
+            };
+            if let Some((index, _)) = changes.iter().find(|(index, _)| *index >= column.len()) {
+                return Err(format_err!(
+                    "index {} out of range for column `{}`",
+                    index,
+                    name
+                ));
+            }
+        }
+
+        for (name, changes) in patches {
+            let column = match self.columns.get_mut(name) {
+                Some(Column::Numbers(column)) => column,
+                _ => unreachable!("checked above"),
+            };
+            for &(index, value) in changes {
+                column[index] = value;
+            }
+        }
+        Ok(())
     }
 }
 
 impl ToBokeh for ColumnDataSource {
     fn as_bokeh_value(&self) -> Value {
-        json!(null)
+        let mut attributes = json!({
+            "data": self.columns,
+            "selected": self.selected.as_bokeh_ref(),
+        });
+        self.js_callbacks.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "ColumnDataSource",
+        })
+    }
+
+    fn as_bokeh_ref(&self) -> Value {
+        json!({
+            "id": self.id,
+            "type": "ColumnDataSource",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value(), self.selected.as_bokeh_value()];
+        out.extend(self.js_callbacks.references());
+        out
+    }
+}
+
+/// Selected rows of a data source
+///
+/// Selection tools in the browser update the selection of the sources of the glyphs they act on.
+#[derive(Clone, Default)]
+pub struct Selection {
+    id: Id,
+    /// Indices of the selected rows
+    pub indices: Vec<usize>,
+}
+
+impl Selection {
+    /// Create a new empty selection
+    pub fn new() -> Self {
+        Selection::default()
+    }
+
+    /// Create a new empty selection with a known id
+    pub fn with_id(id: Id) -> Self {
+        Selection {
+            id,
+            ..Selection::default()
+        }
+    }
+}
+
+impl ToBokeh for Selection {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "indices": self.indices,
+            },
+            "id": self.id,
+            "type": "Selection",
+        })
+    }
+}
+
+/// View onto a `ColumnDataSource`, used by glyph renderers
+#[derive(Clone)]
+struct CDSView {
+    id: Id,
+    source: Value,
+}
+
+impl CDSView {
+    fn new(source: &ColumnDataSource) -> Self {
+        CDSView {
+            id: Id::new(),
+            source: source.as_bokeh_ref(),
+        }
+    }
+}
+
+impl ToBokeh for CDSView {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "source": self.source,
+            },
+            "id": self.id,
+            "type": "CDSView",
+        })
     }
 }
 
 // Plot
 
 /// Position for layout
-#[derive(Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum Position {
     #[doc(hidden)]
     Below,
@@ -68,225 +447,1388 @@ pub enum Position {
     Right,
     #[doc(hidden)]
     Above,
+    /// Within the plot area, as for annotations such as `Span`
+    Center,
+}
+
+impl Position {
+    fn name(self) -> &'static str {
+        match self {
+            Position::Below => "below",
+            Position::Left => "left",
+            Position::Right => "right",
+            Position::Above => "above",
+            Position::Center => "center",
+        }
+    }
 }
 
 /// A plot object
-pub struct Plot<'s> {
+#[derive(Clone, Default)]
+pub struct Plot {
+    id: Id,
     /// Minimum border width
     pub min_border: Option<u32>,
-    source: Option<&'s ColumnDataSource>,
-    glyphs: Vec<Glyph>,
-    layouts: HashMap<Position, Layout>,
-    tools: Vec<Tool>,
+    /// Width of the whole plot in pixels, including its axes and border, 600 if not set
+    pub plot_width: Option<u32>,
+    /// Height of the whole plot in pixels, including its axes and border, 600 if not set
+    pub plot_height: Option<u32>,
+    /// Range of the x axis, fitted to the data if not set
+    pub x_range: Option<Range>,
+    /// Range of the y axis, fitted to the data if not set
+    pub y_range: Option<Range>,
+    /// Scale of the x axis, linear if not set
+    pub x_scale: Option<Scale>,
+    /// Scale of the y axis, linear if not set
+    pub y_scale: Option<Scale>,
+    extra_x_ranges: BTreeMap<String, Range>,
+    extra_y_ranges: BTreeMap<String, Range>,
+    renderers: Vec<GlyphRenderer>,
+    grids: Vec<Grid>,
+    layouts: Vec<(Position, Layout)>,
+    /// Tools of the plot, and how they are shown
+    pub toolbar: Toolbar,
+    /// Where the toolbar is placed, to the right of the plot if not set
+    pub toolbar_location: Option<ToolbarLocation>,
+    /// Legend of the plot, drawn if it has any items
+    pub legend: Legend,
+    /// Side of the plot the legend is drawn beside, inside the plot area if not set
+    ///
+    /// Legends with their own items can also be added to a side with `add_layout`.
+    pub legend_panel: Option<Position>,
+    /// How the plot is sized within its layout, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+    /// Colour of the outline around the plot area
+    pub outline_line_color: Option<String>,
+    /// Width of the outline around the plot area in pixels
+    pub outline_line_width: Option<f64>,
+    /// Opacity of the outline around the plot area
+    pub outline_line_alpha: Option<f64>,
 }
 
-impl<'s> Plot<'s> {
+impl Plot {
     /// Create a new empty plot
     pub fn new() -> Self {
+        Plot::default()
+    }
+
+    /// Create a new empty plot with a known id
+    pub fn with_id(id: Id) -> Self {
         Plot {
-            min_border: None,
-            source: None,
-            glyphs: Vec::new(),
-            layouts: HashMap::new(),
-            tools: Vec::new(),
+            id,
+            ..Plot::default()
         }
     }
 
     /// Add a glyph to the plot
-    pub fn add_glyph<G>(&mut self, source: &'s ColumnDataSource, glyph: G)
+    ///
+    /// The plot keeps its own copy of the source. Copies of a source share its id, so adding
+    /// several glyphs for the same source only serializes the data once.
+    ///
+    /// Returns the id of the renderer drawing the glyph, which tools such as the edit tools are
+    /// bound to.
+    pub fn add_glyph<G>(&mut self, source: &ColumnDataSource, glyph: G) -> Id
     where
         G: Into<Glyph>,
     {
-        self.source = Some(source);
-        self.glyphs.push(glyph.into());
+        let renderer = GlyphRenderer::new(source, glyph.into());
+        let id = renderer.id.clone();
+        self.renderers.push(renderer);
+        id
+    }
+
+    /// Renderer with id `id`, as returned by `add_glyph`
+    pub fn renderer_mut(&mut self, id: &Id) -> Option<&mut GlyphRenderer> {
+        self.renderers
+            .iter_mut()
+            .find(|renderer| renderer.id == *id)
+    }
+
+    /// Add a further x range called `name`, for glyphs and axes with that `x_range_name`
+    pub fn add_extra_x_range<S, R>(&mut self, name: S, range: R)
+    where
+        S: Into<String>,
+        R: Into<Range>,
+    {
+        self.extra_x_ranges.insert(name.into(), range.into());
+    }
+
+    /// Add a further y range called `name`, for glyphs and axes with that `y_range_name`
+    ///
+    /// Together with an axis on the right of the plot, this lets two series with different units
+    /// share the plot.
+    pub fn add_extra_y_range<S, R>(&mut self, name: S, range: R)
+    where
+        S: Into<String>,
+        R: Into<Range>,
+    {
+        self.extra_y_ranges.insert(name.into(), range.into());
+    }
+
+    /// Add a grid to the plot
+    pub fn add_grid(&mut self, grid: Grid) {
+        self.grids.push(grid);
+    }
+
+    /// Remove all of the grids from the plot
+    pub fn remove_grids(&mut self) {
+        self.grids.clear();
+    }
+
+    /// Add a layout to the plot
+    ///
+    /// Layouts on the same side of the plot are stacked in the order they are added, outwards
+    /// from the plot.
+    pub fn add_layout<L>(&mut self, position: Position, layout: L)
+    where
+        L: Into<Layout>,
+    {
+        self.layouts.push((position, layout.into()));
+    }
+
+    /// Add a layout to the plot at `index` among the layouts already on the same side
+    ///
+    /// Index 0 is next to the plot, so a subtitle can go between an axis and a title above the
+    /// plot. Indices past the last layout on the side add the layout outermost.
+    pub fn insert_layout<L>(&mut self, position: Position, index: usize, layout: L)
+    where
+        L: Into<Layout>,
+    {
+        let at = self
+            .layouts
+            .iter()
+            .enumerate()
+            .filter(|(_, (p, _))| *p == position)
+            .nth(index)
+            .map(|(i, _)| i)
+            .unwrap_or_else(|| self.layouts.len());
+        self.layouts.insert(at, (position, layout.into()));
+    }
+
+    /// Show the x values on the scale of `axis_type`, with a matching axis below the plot
+    ///
+    /// This replaces any axis already below the plot, as `x_axis_type` does in Python.
+    pub fn set_x_axis_type(&mut self, axis_type: AxisType) {
+        self.x_scale = Some(axis_type.scale());
+        self.remove_axes(Position::Below);
+        self.add_layout(Position::Below, axis_type.axis(Dimension::Width));
+    }
+
+    /// Show the y values on the scale of `axis_type`, with a matching axis left of the plot
+    ///
+    /// This replaces any axis already left of the plot, as `y_axis_type` does in Python.
+    pub fn set_y_axis_type(&mut self, axis_type: AxisType) {
+        self.y_scale = Some(axis_type.scale());
+        self.remove_axes(Position::Left);
+        self.add_layout(Position::Left, axis_type.axis(Dimension::Height));
+    }
+
+    /// Add a tool to the plot
+    pub fn add_tool<T>(&mut self, tool: T)
+    where
+        T: Into<Tool>,
+    {
+        self.toolbar.add_tool(tool);
     }
 
-    /// Add a layout to the plot
-    pub fn add_layout<L>(&mut self, position: Position, layout: L)
-    where
-        L: Into<Layout>,
-    {
-        self.layouts.insert(position, layout.into());
+    /// Validate the plot for rendering
+    pub fn validate(self) -> Result<ValidatedPlot> {
+        self.check()?;
+        Ok(ValidatedPlot { plot: self })
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.renderers.is_empty() {
+            return Err(format_err!("no ColumnDataSource found"));
+        }
+        self.toolbar.validate()?;
+        for renderer in &self.renderers {
+            renderer.validate_legend()?;
+        }
+
+        let range_names = self
+            .renderers
+            .iter()
+            .map(|r| (&r.x_range_name, &r.y_range_name))
+            .chain(self.layouts.iter().filter_map(|(_, layout)| {
+                layout
+                    .axis_properties()
+                    .map(|p| (&p.x_range_name, &p.y_range_name))
+            }));
+        for (x_range_name, y_range_name) in range_names {
+            if let Some(name) = x_range_name {
+                if !self.extra_x_ranges.contains_key(name) {
+                    return Err(format_err!("no extra x range called `{}`", name));
+                }
+            }
+            if let Some(name) = y_range_name {
+                if !self.extra_y_ranges.contains_key(name) {
+                    return Err(format_err!("no extra y range called `{}`", name));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Every copy of the data source with id `id`
+    fn sources_mut<'a>(
+        &'a mut self,
+        id: &'a Id,
+    ) -> impl Iterator<Item = &'a mut ColumnDataSource> + 'a {
+        let layout_sources = self
+            .layouts
+            .iter_mut()
+            .filter_map(|(_, layout)| layout.source_mut());
+        self.renderers
+            .iter_mut()
+            .map(|renderer| &mut renderer.data_source)
+            .chain(layout_sources)
+            .filter(move |source| source.id == *id)
+    }
+
+    /// Strip the plot down to its glyphs, for sparklines and thumbnails
+    ///
+    /// This removes the axes and grids, hides the toolbar and outline, and drops the border.
+    pub fn minimal(&mut self) {
+        self.layouts
+            .retain(|(_, layout)| layout.axis_properties().is_none());
+        self.remove_grids();
+        self.toolbar_location = Some(ToolbarLocation::Hidden);
+        self.outline_line_alpha = Some(0.0);
+        self.min_border = Some(0);
+    }
+
+    // Items of the legend, those added to it followed by those of the renderers
+    fn legend_items(&self) -> Vec<LegendItem> {
+        let mut items = self.legend.items().to_vec();
+        for renderer in &self.renderers {
+            for (n, (label, index)) in renderer.legend_entries().into_iter().enumerate() {
+                let shared = items
+                    .iter_mut()
+                    .find(|item| index.is_none() && item.index.is_none() && item.label == label);
+                match shared {
+                    Some(item) => item.add_renderer(&renderer.id),
+                    None => {
+                        // Derived from the renderer so the item keeps its id between serializations
+                        let id = Id::from(format!("{}-legend-{}", renderer.id, n));
+                        let mut item = LegendItem::with_id(id, label);
+                        item.index = index;
+                        item.add_renderer(&renderer.id);
+                        items.push(item);
+                    }
+                }
+            }
+        }
+        items
+    }
+
+    fn remove_axes(&mut self, position: Position) {
+        self.layouts
+            .retain(|(p, layout)| *p != position || layout.axis_properties().is_none());
+    }
+}
+
+impl ToBokeh for Plot {
+    fn as_bokeh_value(&self) -> Value {
+        let legend_drawn = !self.legend_items().is_empty();
+        let mut sides: Vec<(Position, Value)> = self
+            .layouts
+            .iter()
+            .map(|(position, layout)| (*position, layout.as_bokeh_ref()))
+            .collect();
+        if let (true, Some(panel)) = (legend_drawn, self.legend_panel) {
+            sides.push((panel, self.legend.as_bokeh_ref()));
+        }
+
+        let mut renderers: Vec<Value> = self.renderers.iter().map(|r| r.as_bokeh_ref()).collect();
+        renderers.extend(self.grids.iter().map(|grid| grid.as_bokeh_ref()));
+        if legend_drawn {
+            renderers.push(self.legend.as_bokeh_ref());
+        }
+        renderers.extend(self.layouts.iter().map(|(_, layout)| layout.as_bokeh_ref()));
+
+        let mut attributes = json!({
+            "renderers": renderers,
+            "toolbar": self.toolbar.as_bokeh_ref(),
+        });
+        for (position, layout) in sides {
+            if position == Position::Center {
+                continue;
+            }
+            match attributes[position.name()].as_array_mut() {
+                Some(side) => side.push(layout),
+                None => attributes[position.name()] = json!([layout]),
+            }
+        }
+        if let Some(min_border) = self.min_border {
+            attributes["min_border"] = json!(min_border);
+        }
+        if let Some(plot_width) = self.plot_width {
+            attributes["plot_width"] = json!(plot_width);
+        }
+        if let Some(plot_height) = self.plot_height {
+            attributes["plot_height"] = json!(plot_height);
+        }
+        if let Some(toolbar_location) = self.toolbar_location {
+            attributes["toolbar_location"] = toolbar_location.value();
+        }
+        if let Some(sizing_mode) = self.sizing_mode {
+            attributes["sizing_mode"] = json!(sizing_mode.name());
+        }
+        if let Some(color) = &self.outline_line_color {
+            attributes["outline_line_color"] = json!({ "value": color });
+        }
+        if let Some(width) = self.outline_line_width {
+            attributes["outline_line_width"] = json!({ "value": width });
+        }
+        if let Some(alpha) = self.outline_line_alpha {
+            attributes["outline_line_alpha"] = json!({ "value": alpha });
+        }
+        if let Some(x_range) = &self.x_range {
+            attributes["x_range"] = x_range.as_bokeh_ref();
+        }
+        if let Some(y_range) = &self.y_range {
+            attributes["y_range"] = y_range.as_bokeh_ref();
+        }
+        if let Some(x_scale) = &self.x_scale {
+            attributes["x_scale"] = x_scale.as_bokeh_ref();
+        }
+        if let Some(y_scale) = &self.y_scale {
+            attributes["y_scale"] = y_scale.as_bokeh_ref();
+        }
+        if !self.extra_x_ranges.is_empty() {
+            let ranges: Map<String, Value> = self
+                .extra_x_ranges
+                .iter()
+                .map(|(name, range)| (name.clone(), range.as_bokeh_ref()))
+                .collect();
+            attributes["extra_x_ranges"] = json!(ranges);
+        }
+        if !self.extra_y_ranges.is_empty() {
+            let ranges: Map<String, Value> = self
+                .extra_y_ranges
+                .iter()
+                .map(|(name, range)| (name.clone(), range.as_bokeh_ref()))
+                .collect();
+            attributes["extra_y_ranges"] = json!(ranges);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Plot",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for renderer in &self.renderers {
+            out.extend(renderer.references());
+        }
+        for grid in &self.grids {
+            out.extend(grid.references());
+        }
+        let legend_items = self.legend_items();
+        if !legend_items.is_empty() {
+            out.push(self.legend.value_with_items(&legend_items));
+            out.extend(legend_items.iter().map(|item| item.as_bokeh_value()));
+        }
+        for (_, layout) in &self.layouts {
+            out.extend(layout.references());
+        }
+        out.extend(self.toolbar.references());
+        for range in self.x_range.iter().chain(&self.y_range) {
+            out.extend(range.references());
+        }
+        for scale in self.x_scale.iter().chain(&self.y_scale) {
+            out.extend(scale.references());
+        }
+        for range in self
+            .extra_x_ranges
+            .values()
+            .chain(self.extra_y_ranges.values())
+        {
+            out.extend(range.references());
+        }
+        out
+    }
+}
+
+/// Plot that has passed validations
+pub struct ValidatedPlot {
+    plot: Plot,
+}
+
+impl ToBokeh for ValidatedPlot {
+    fn as_bokeh_value(&self) -> Value {
+        self.plot.as_bokeh_value()
+    }
+
+    fn references(&self) -> Vec<Value> {
+        self.plot.references()
+    }
+}
+
+// Renderers
+
+/// Renders a glyph using the data from a `ColumnDataSource`
+///
+/// Renderers are created by `Plot::add_glyph`, and found again with `Plot::renderer_mut`.
+#[derive(Clone)]
+pub struct GlyphRenderer {
+    id: Id,
+    data_source: ColumnDataSource,
+    glyph: Glyph,
+    view: CDSView,
+    /// Extra x range of the plot to draw the glyph against, rather than its `x_range`
+    pub x_range_name: Option<String>,
+    /// Extra y range of the plot to draw the glyph against, rather than its `y_range`
+    pub y_range_name: Option<String>,
+    /// Label of the glyph in the legend of the plot
+    ///
+    /// Renderers with the same label share an item of the legend.
+    pub legend_label: Option<String>,
+    /// Column of the data source labelling the glyph in the legend, with an item for each
+    /// distinct value, grouped in the browser
+    pub legend_field: Option<String>,
+    /// Column of the data source labelling the glyph in the legend, with an item for each
+    /// distinct value, grouped before the plot is sent
+    pub legend_group: Option<String>,
+    /// Glyph drawn instead while the renderer is muted, e.g. by a legend with
+    /// `ClickPolicy::Mute`
+    ///
+    /// This should be a separate glyph from the one passed to `Plot::add_glyph`, typically with
+    /// lower alphas. The renderer is drawn as usual while muted if this is not set.
+    pub muted_glyph: Option<Glyph>,
+    /// Whether the renderer starts muted
+    pub muted: Option<bool>,
+}
+
+impl GlyphRenderer {
+    fn new(source: &ColumnDataSource, glyph: Glyph) -> Self {
+        GlyphRenderer {
+            id: Id::new(),
+            data_source: source.clone(),
+            glyph,
+            view: CDSView::new(source),
+            x_range_name: None,
+            y_range_name: None,
+            legend_label: None,
+            legend_field: None,
+            legend_group: None,
+            muted_glyph: None,
+            muted: None,
+        }
+    }
+
+    /// Id of the renderer
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    // Labels and rows of the legend items of the renderer
+    fn legend_entries(&self) -> Vec<(LegendLabel, Option<usize>)> {
+        if let Some(label) = &self.legend_label {
+            return vec![(LegendLabel::Value(label.clone()), None)];
+        }
+        if let Some(field) = &self.legend_field {
+            return vec![(LegendLabel::Field(field.clone()), None)];
+        }
+        let column = self
+            .legend_group
+            .as_ref()
+            .and_then(|group| self.data_source.columns.get(group));
+        let mut entries: Vec<(LegendLabel, Option<usize>)> = Vec::new();
+        for (index, label) in column
+            .map(Column::labels)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let label = LegendLabel::Value(label);
+            if !entries.iter().any(|(l, _)| *l == label) {
+                entries.push((label, Some(index)));
+            }
+        }
+        entries
+    }
+
+    fn validate_legend(&self) -> Result<()> {
+        let options = [&self.legend_label, &self.legend_field, &self.legend_group];
+        if options.iter().filter(|option| option.is_some()).count() > 1 {
+            return Err(format_err!(
+                "only one of legend_label, legend_field and legend_group can be set"
+            ));
+        }
+        for column in self.legend_field.iter().chain(&self.legend_group) {
+            if !self.data_source.columns.contains_key(column) {
+                return Err(format_err!("no column `{}` for the legend", column));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ToBokeh for GlyphRenderer {
+    fn as_bokeh_value(&self) -> Value {
+        let mut value = json!({
+            "attributes": {
+                "data_source": self.data_source.as_bokeh_ref(),
+                "glyph": self.glyph.as_bokeh_ref(),
+                "view": self.view.as_bokeh_ref(),
+            },
+            "id": self.id,
+            "type": "GlyphRenderer",
+        });
+        if let Some(x_range_name) = &self.x_range_name {
+            value["attributes"]["x_range_name"] = json!(x_range_name);
+        }
+        if let Some(y_range_name) = &self.y_range_name {
+            value["attributes"]["y_range_name"] = json!(y_range_name);
+        }
+        if let Some(muted_glyph) = &self.muted_glyph {
+            value["attributes"]["muted_glyph"] = muted_glyph.as_bokeh_ref();
+        }
+        if let Some(muted) = self.muted {
+            value["attributes"]["muted"] = json!(muted);
+        }
+        value
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.data_source.references());
+        out.push(self.glyph.as_bokeh_value());
+        out.extend(self.muted_glyph.iter().map(|glyph| glyph.as_bokeh_value()));
+        out.push(self.view.as_bokeh_value());
+        out
+    }
+}
+
+// Glyphs
+
+/// Represents all available glyphs
+#[derive(Clone)]
+pub enum Glyph {
+    /// Circle type
+    Circle(Circle),
+    /// Rectangles
+    Rect(Rect),
+    /// Filled polygons
+    Patches(Patches),
+    /// Lines through lists of points
+    MultiLine(MultiLine),
+}
+
+impl ToBokeh for Glyph {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Glyph::Circle(c) => c.as_bokeh_value(),
+            Glyph::Rect(r) => r.as_bokeh_value(),
+            Glyph::Patches(p) => p.as_bokeh_value(),
+            Glyph::MultiLine(m) => m.as_bokeh_value(),
+        }
+    }
+}
+
+// Sets the fill and line properties shared by glyphs
+fn add_fill_and_line(
+    attributes: &mut Value,
+    fill_color: &Option<String>,
+    fill_alpha: Option<f64>,
+    line_color: &Option<String>,
+    line_alpha: Option<f64>,
+) {
+    if let Some(fill_color) = fill_color {
+        attributes["fill_color"] = json!({ "value": fill_color });
+    }
+    if let Some(fill_alpha) = fill_alpha {
+        attributes["fill_alpha"] = json!({ "value": fill_alpha });
+    }
+    if let Some(line_color) = line_color {
+        attributes["line_color"] = json!({ "value": line_color });
+    }
+    if let Some(line_alpha) = line_alpha {
+        attributes["line_alpha"] = json!({ "value": line_alpha });
+    }
+}
+
+/// Circle marker
+#[derive(Clone, Default)]
+pub struct Circle {
+    id: Id,
+    /// X key to extract from ColumnDataSource
+    pub x: Option<String>,
+    /// Y key to extract from ColumnDataSource
+    pub y: Option<String>,
+    /// fill color key to extract from ColumnDataSource
+    pub fill_color: Option<String>,
+    /// size key to extract from ColumnDataSource
+    pub size: Option<u32>,
+    /// line color key to extract from ColumnDataSource
+    pub line_color: Option<String>,
+    /// Opacity of the fill
+    pub fill_alpha: Option<f64>,
+    /// Opacity of the outline
+    pub line_alpha: Option<f64>,
+}
+
+impl Circle {
+    /// Create a new circle marker representation
+    pub fn new() -> Self {
+        Circle::default()
+    }
+
+    /// Create a new circle marker with a known id
+    pub fn with_id(id: Id) -> Self {
+        Circle {
+            id,
+            ..Circle::default()
+        }
+    }
+}
+
+impl From<Circle> for Glyph {
+    fn from(c: Circle) -> Glyph {
+        Glyph::Circle(c)
+    }
+}
+
+impl ToBokeh for Circle {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(ref x) = self.x {
+            attributes["x"] = json!({ "field": x });
+        }
+        if let Some(ref y) = self.y {
+            attributes["y"] = json!({ "field": y });
+        }
+        if let Some(ref fill_color) = self.fill_color {
+            attributes["fill_color"] = json!({ "value": fill_color });
+        }
+        if let Some(size) = self.size {
+            attributes["size"] = json!({ "units": "screen", "value": size });
+        }
+        if let Some(ref line_color) = self.line_color {
+            attributes["line_color"] = json!({ "value": line_color });
+        }
+        if let Some(fill_alpha) = self.fill_alpha {
+            attributes["fill_alpha"] = json!({ "value": fill_alpha });
+        }
+        if let Some(line_alpha) = self.line_alpha {
+            attributes["line_alpha"] = json!({ "value": line_alpha });
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Circle",
+        })
+    }
+}
+
+/// Rectangles centred on `x` and `y`
+///
+/// Boxes drawn by a `BoxEditTool` are added to the columns of all four coordinates.
+#[derive(Clone, Default)]
+pub struct Rect {
+    id: Id,
+    /// Column of the x coordinates of the centres
+    pub x: Option<String>,
+    /// Column of the y coordinates of the centres
+    pub y: Option<String>,
+    /// Column of the widths, in data units
+    pub width: Option<String>,
+    /// Column of the heights, in data units
+    pub height: Option<String>,
+    /// Colour of the fill
+    pub fill_color: Option<String>,
+    /// Opacity of the fill
+    pub fill_alpha: Option<f64>,
+    /// Colour of the outline
+    pub line_color: Option<String>,
+    /// Opacity of the outline
+    pub line_alpha: Option<f64>,
+}
+
+impl Rect {
+    /// Create a new rectangle glyph
+    pub fn new() -> Self {
+        Rect::default()
+    }
+
+    /// Create a new rectangle glyph with a known id
+    pub fn with_id(id: Id) -> Self {
+        Rect {
+            id,
+            ..Rect::default()
+        }
+    }
+}
+
+impl From<Rect> for Glyph {
+    fn from(r: Rect) -> Glyph {
+        Glyph::Rect(r)
+    }
+}
+
+impl ToBokeh for Rect {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(ref x) = self.x {
+            attributes["x"] = json!({ "field": x });
+        }
+        if let Some(ref y) = self.y {
+            attributes["y"] = json!({ "field": y });
+        }
+        if let Some(ref width) = self.width {
+            attributes["width"] = json!({ "field": width });
+        }
+        if let Some(ref height) = self.height {
+            attributes["height"] = json!({ "field": height });
+        }
+        add_fill_and_line(
+            &mut attributes,
+            &self.fill_color,
+            self.fill_alpha,
+            &self.line_color,
+            self.line_alpha,
+        );
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Rect",
+        })
+    }
+}
+
+/// Filled polygons, with the vertices of each held in columns of lists
+///
+/// Shapes drawn by a `PolyDrawTool` or `FreehandDrawTool` are added to the columns.
+#[derive(Clone, Default)]
+pub struct Patches {
+    id: Id,
+    /// Column of the x coordinates of the vertices of each polygon
+    pub xs: Option<String>,
+    /// Column of the y coordinates of the vertices of each polygon
+    pub ys: Option<String>,
+    /// Colour of the fill
+    pub fill_color: Option<String>,
+    /// Opacity of the fill
+    pub fill_alpha: Option<f64>,
+    /// Colour of the outline
+    pub line_color: Option<String>,
+    /// Opacity of the outline
+    pub line_alpha: Option<f64>,
+}
+
+impl Patches {
+    /// Create a new polygon glyph
+    pub fn new() -> Self {
+        Patches::default()
+    }
+
+    /// Create a new polygon glyph with a known id
+    pub fn with_id(id: Id) -> Self {
+        Patches {
+            id,
+            ..Patches::default()
+        }
+    }
+}
+
+impl From<Patches> for Glyph {
+    fn from(p: Patches) -> Glyph {
+        Glyph::Patches(p)
+    }
+}
+
+impl ToBokeh for Patches {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(ref xs) = self.xs {
+            attributes["xs"] = json!({ "field": xs });
+        }
+        if let Some(ref ys) = self.ys {
+            attributes["ys"] = json!({ "field": ys });
+        }
+        add_fill_and_line(
+            &mut attributes,
+            &self.fill_color,
+            self.fill_alpha,
+            &self.line_color,
+            self.line_alpha,
+        );
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Patches",
+        })
+    }
+}
+
+/// Lines through the points held in columns of lists, one line for each row
+///
+/// Shapes drawn by a `PolyDrawTool` or `FreehandDrawTool` are added to the columns.
+#[derive(Clone, Default)]
+pub struct MultiLine {
+    id: Id,
+    /// Column of the x coordinates of the points of each line
+    pub xs: Option<String>,
+    /// Column of the y coordinates of the points of each line
+    pub ys: Option<String>,
+    /// Colour of the lines
+    pub line_color: Option<String>,
+    /// Opacity of the lines
+    pub line_alpha: Option<f64>,
+    /// Width of the lines in pixels
+    pub line_width: Option<f64>,
+}
+
+impl MultiLine {
+    /// Create a new multi-line glyph
+    pub fn new() -> Self {
+        MultiLine::default()
+    }
+
+    /// Create a new multi-line glyph with a known id
+    pub fn with_id(id: Id) -> Self {
+        MultiLine {
+            id,
+            ..MultiLine::default()
+        }
+    }
+}
+
+impl From<MultiLine> for Glyph {
+    fn from(m: MultiLine) -> Glyph {
+        Glyph::MultiLine(m)
+    }
+}
+
+impl ToBokeh for MultiLine {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(ref xs) = self.xs {
+            attributes["xs"] = json!({ "field": xs });
+        }
+        if let Some(ref ys) = self.ys {
+            attributes["ys"] = json!({ "field": ys });
+        }
+        if let Some(ref line_color) = self.line_color {
+            attributes["line_color"] = json!({ "value": line_color });
+        }
+        if let Some(line_alpha) = self.line_alpha {
+            attributes["line_alpha"] = json!({ "value": line_alpha });
+        }
+        if let Some(line_width) = self.line_width {
+            attributes["line_width"] = json!({ "value": line_width });
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "MultiLine",
+        })
+    }
+}
+
+// Text
+
+/// Style of the font of a text property
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontStyle {
+    /// Upright text
+    Normal,
+    /// Slanted text
+    Italic,
+    /// Heavy text
+    Bold,
+    /// Heavy slanted text
+    BoldItalic,
+}
+
+impl FontStyle {
+    fn name(self) -> &'static str {
+        match self {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+            FontStyle::Bold => "bold",
+            FontStyle::BoldItalic => "bold italic",
+        }
+    }
+}
+
+/// Horizontal alignment of text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+    /// Aligned to the left
+    Left,
+    /// Centered
+    Center,
+    /// Aligned to the right
+    Right,
+}
+
+impl TextAlign {
+    fn name(self) -> &'static str {
+        match self {
+            TextAlign::Left => "left",
+            TextAlign::Center => "center",
+            TextAlign::Right => "right",
+        }
+    }
+}
+
+// Layout
+
+/// All of the enumerated layout options
+#[derive(Clone)]
+pub enum Layout {
+    /// Axis with linearly spaced ticks
+    LinearAxis(LinearAxis),
+    /// Axis with ticks at powers of ten
+    LogAxis(LogAxis),
+    /// Axis with ticks at round dates and times
+    DatetimeAxis(DatetimeAxis),
+    /// Axis labelled with categories
+    CategoricalAxis(CategoricalAxis),
+    /// Axis labelled with latitudes or longitudes
+    MercatorAxis(MercatorAxis),
+    /// Title of the plot
+    Title(Title),
+    /// Legend with its own items
+    Legend(Legend),
+    /// Line across the plot
+    Span(Span),
+    /// Shaded box within the plot
+    BoxAnnotation(BoxAnnotation),
+    /// Text at a point of the plot
+    Label(Label),
+    /// Text at each row of a data source
+    LabelSet(LabelSet),
+    /// Arrow between two points
+    Arrow(Arrow),
+    /// Shaded region between two columns of a data source
+    Band(Band),
+    /// Error bars from a data source
+    Whisker(Whisker),
+    /// Line with a gradient and intercept
+    Slope(Slope),
+    /// Shaded polygon
+    PolyAnnotation(PolyAnnotation),
+}
+
+impl Layout {
+    fn axis_properties(&self) -> Option<&AxisProperties> {
+        match self {
+            Layout::LinearAxis(a) => Some(&a.properties),
+            Layout::LogAxis(a) => Some(&a.properties),
+            Layout::DatetimeAxis(a) => Some(&a.properties),
+            Layout::CategoricalAxis(a) => Some(&a.properties),
+            Layout::MercatorAxis(a) => Some(&a.properties),
+            _ => None,
+        }
+    }
+
+    // Copy of a data source held by the layout, to keep up to date with changes to the source
+    fn source_mut(&mut self) -> Option<&mut ColumnDataSource> {
+        match self {
+            Layout::LabelSet(a) => Some(&mut a.source),
+            Layout::Band(a) => Some(&mut a.source),
+            Layout::Whisker(a) => Some(&mut a.source),
+            _ => None,
+        }
+    }
+}
+
+impl ToBokeh for Layout {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Layout::LinearAxis(a) => a.as_bokeh_value(),
+            Layout::LogAxis(a) => a.as_bokeh_value(),
+            Layout::DatetimeAxis(a) => a.as_bokeh_value(),
+            Layout::CategoricalAxis(a) => a.as_bokeh_value(),
+            Layout::MercatorAxis(a) => a.as_bokeh_value(),
+            Layout::Title(a) => a.as_bokeh_value(),
+            Layout::Legend(a) => a.as_bokeh_value(),
+            Layout::Span(a) => a.as_bokeh_value(),
+            Layout::BoxAnnotation(a) => a.as_bokeh_value(),
+            Layout::Label(a) => a.as_bokeh_value(),
+            Layout::LabelSet(a) => a.as_bokeh_value(),
+            Layout::Arrow(a) => a.as_bokeh_value(),
+            Layout::Band(a) => a.as_bokeh_value(),
+            Layout::Whisker(a) => a.as_bokeh_value(),
+            Layout::Slope(a) => a.as_bokeh_value(),
+            Layout::PolyAnnotation(a) => a.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Layout::LinearAxis(a) => a.references(),
+            Layout::LogAxis(a) => a.references(),
+            Layout::DatetimeAxis(a) => a.references(),
+            Layout::CategoricalAxis(a) => a.references(),
+            Layout::MercatorAxis(a) => a.references(),
+            Layout::Title(a) => a.references(),
+            Layout::Legend(a) => a.references(),
+            Layout::Span(a) => a.references(),
+            Layout::BoxAnnotation(a) => a.references(),
+            Layout::Label(a) => a.references(),
+            Layout::LabelSet(a) => a.references(),
+            Layout::Arrow(a) => a.references(),
+            Layout::Band(a) => a.references(),
+            Layout::Whisker(a) => a.references(),
+            Layout::Slope(a) => a.references(),
+            Layout::PolyAnnotation(a) => a.references(),
+        }
+    }
+}
+
+// Document callbacks
+
+static NEXT_CALLBACK_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Function run against a document by the server
+pub type DocumentCallbackFn = dyn FnMut(&mut Document) + Send;
+
+/// When a document callback runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallbackSchedule {
+    /// Repeatedly, once every period
+    Periodic(Duration),
+    /// Once, after the timeout has elapsed
+    Timeout(Duration),
+    /// Once, as soon as possible
+    NextTick,
+}
+
+/// Identifies a callback added to a document, so that it can be removed again
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CallbackId(usize);
+
+/// Callback added to a document, along with its schedule
+#[derive(Clone)]
+pub struct DocumentCallback {
+    id: CallbackId,
+    schedule: CallbackSchedule,
+    callback: Arc<Mutex<DocumentCallbackFn>>,
+}
+
+impl DocumentCallback {
+    /// Id of the callback
+    pub fn id(&self) -> CallbackId {
+        self.id
     }
 
-    /// Add a tool to the plot
-    pub fn add_tool<T>(&mut self, tool: T)
-    where
-        T: Into<Tool>,
-    {
-        self.tools.push(tool.into());
+    /// When the callback runs
+    pub fn schedule(&self) -> CallbackSchedule {
+        self.schedule
     }
 
-    /// Validate the plot for rendering
-    pub fn validate(self) -> Result<ValidatedPlot<'s>> {
-        let source = self
-            .source
-            .ok_or(format_err!("no ColumnDataSource found"))?;
-        Ok(ValidatedPlot {
-            min_border: self.min_border,
-            source,
-            glyphs: self.glyphs,
-            layouts: self.layouts,
-            tools: self.tools,
-        })
+    /// Run the callback against `document`
+    pub fn run(&self, document: &mut Document) {
+        (self.callback.lock().unwrap())(document)
     }
 }
 
-/// Plot that has passed validations
-pub struct ValidatedPlot<'s> {
-    /// Minimum border width
-    pub min_border: Option<u32>,
-    source: &'s ColumnDataSource,
-    glyphs: Vec<Glyph>,
-    layouts: HashMap<Position, Layout>,
-    tools: Vec<Tool>,
+#[derive(Default)]
+struct CallbackSignalState {
+    added: bool,
+    closed: bool,
 }
 
-// Glyphs
+/// Wakes the server's callback scheduler of a document when callbacks are added to it
+#[derive(Default)]
+pub(crate) struct CallbackSignal {
+    state: Mutex<CallbackSignalState>,
+    condvar: Condvar,
+}
 
-/// Represents all available glyphs
-pub enum Glyph {
-    /// Circle type
-    Circle(Circle),
+impl CallbackSignal {
+    fn notify(&self) {
+        self.state.lock().unwrap().added = true;
+        self.condvar.notify_all();
+    }
+
+    /// Wake the scheduler for good, once the document is no longer served
+    pub(crate) fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.condvar.notify_all();
+    }
+
+    /// Block until a callback is added, the signal is closed or `deadline` passes
+    ///
+    /// Without a deadline this waits for as long as it takes for a callback to be added.
+    pub(crate) fn wait(&self, deadline: Option<Instant>) {
+        let mut state = self.state.lock().unwrap();
+        while !state.added && !state.closed {
+            state = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+                    self.condvar.wait_timeout(state, deadline - now).unwrap().0
+                }
+                None => self.condvar.wait(state).unwrap(),
+            };
+        }
+        state.added = false;
+    }
 }
 
-/// Circle marker
+// Document
+
+/// Main document object for the plot
 #[derive(Default)]
-pub struct Circle {
-    /// X key to extract from ColumnDataSource
-    pub x: Option<String>,
-    /// Y key to extract from ColumnDataSource
-    pub y: Option<String>,
-    /// fill color key to extract from ColumnDataSource
-    pub fill_color: Option<String>,
-    /// size key to extract from ColumnDataSource
-    pub size: Option<u32>,
-    /// line color key to extract from ColumnDataSource
-    pub line_color: Option<String>,
+pub struct Document {
+    root: Option<LayoutDom>,
+    theme: Option<themes::Theme>,
+    callbacks: Vec<DocumentCallback>,
+    events: events::Patch,
+    callback_signal: Arc<CallbackSignal>,
 }
 
-impl Circle {
-    /// Create a new circle marker representation
-    pub fn new() -> Self {
-        Circle::default()
+impl Clone for Document {
+    fn clone(&self) -> Self {
+        // Each copy is served on its own, so it gets its own scheduler signal
+        Document {
+            root: self.root.clone(),
+            theme: self.theme.clone(),
+            callbacks: self.callbacks.clone(),
+            events: self.events.clone(),
+            callback_signal: Arc::default(),
+        }
     }
 }
 
-impl From<Circle> for Glyph {
-    fn from(c: Circle) -> Glyph {
-        Glyph::Circle(c)
+impl Document {
+    /// Create a new document
+    pub fn new() -> Self {
+        Document::default()
     }
-}
 
-// Layout
+    /// Set the root of the document, either a single plot or a layout of several
+    pub fn add_root<L>(&mut self, root: L)
+    where
+        L: Into<LayoutDom>,
+    {
+        self.root = Some(root.into());
+    }
 
-/// All of the enumerated layout options
-pub enum Layout {
-    /// Linear range
-    LinearAxis,
-}
+    /// Style the models of the document with `theme`, replacing any theme set before
+    ///
+    /// Themed values are used for properties which are not set on the models themselves.
+    pub fn set_theme(&mut self, theme: themes::Theme) {
+        self.theme = Some(theme);
+    }
 
-// Tools
+    /// The theme of the document
+    pub fn theme(&self) -> Option<&themes::Theme> {
+        self.theme.as_ref()
+    }
 
-/// Tools for the plot
-pub enum Tool {
-    /// Allow the plot to pan
-    PanTool,
-    /// Zoom in and out with the mouse wheel
-    WheelZoomTool,
-}
+    /// The root of the document
+    pub fn root(&self) -> Option<&LayoutDom> {
+        self.root.as_ref()
+    }
 
-// BasicTicker
+    /// Mutable access to the root of the document
+    pub fn root_mut(&mut self) -> Option<&mut LayoutDom> {
+        self.root.as_mut()
+    }
 
-/// Struct representing ticks
-pub struct BasicTicker;
+    /// Run `callback` once every `period` while the document is being served
+    pub fn add_periodic_callback<F>(&mut self, callback: F, period: Duration) -> CallbackId
+    where
+        F: FnMut(&mut Document) + Send + 'static,
+    {
+        self.add_callback(callback, CallbackSchedule::Periodic(period))
+    }
 
-impl BasicTicker {
-    /// Create a new BasicTicker
-    pub fn new() -> BasicTicker {
-        BasicTicker {}
+    /// Run `callback` once after `timeout` while the document is being served
+    pub fn add_timeout_callback<F>(&mut self, callback: F, timeout: Duration) -> CallbackId
+    where
+        F: FnMut(&mut Document) + Send + 'static,
+    {
+        self.add_callback(callback, CallbackSchedule::Timeout(timeout))
     }
-}
 
-impl ToBokeh for BasicTicker {
-    fn as_bokeh_value(&self) -> Value {
-        json!({
-            "attributes": {},
-            "type": "BasicTicker",
-        })
+    /// Run `callback` once, as soon as possible, while the document is being served
+    pub fn add_next_tick_callback<F>(&mut self, callback: F) -> CallbackId
+    where
+        F: FnMut(&mut Document) + Send + 'static,
+    {
+        self.add_callback(callback, CallbackSchedule::NextTick)
     }
-}
 
-// Basic tick formatter
+    fn add_callback<F>(&mut self, callback: F, schedule: CallbackSchedule) -> CallbackId
+    where
+        F: FnMut(&mut Document) + Send + 'static,
+    {
+        let id = CallbackId(NEXT_CALLBACK_ID.fetch_add(1, Ordering::SeqCst));
+        self.callbacks.push(DocumentCallback {
+            id,
+            schedule,
+            callback: Arc::new(Mutex::new(callback)),
+        });
+        self.callback_signal.notify();
+        id
+    }
 
-/// Struct dealing with basic tick formatting.
-pub struct BasicTickFormatter;
+    /// Remove a callback, returning whether it was found
+    pub fn remove_callback(&mut self, id: CallbackId) -> bool {
+        let before = self.callbacks.len();
+        self.callbacks.retain(|c| c.id != id);
+        self.callbacks.len() != before
+    }
 
-impl BasicTickFormatter {
-    /// Create a new BasicTickFormatter
-    pub fn new() -> BasicTickFormatter {
-        BasicTickFormatter {}
+    /// Callbacks currently added to the document
+    pub fn callbacks(&self) -> &[DocumentCallback] {
+        &self.callbacks
     }
-}
 
-impl ToBokeh for BasicTickFormatter {
-    fn as_bokeh_value(&self) -> Value {
-        json!({
-            "attributes": {},
-            "type": "BasicTickFormatter",
-        })
+    /// Signal raised whenever a callback is added to the document
+    pub(crate) fn callback_signal(&self) -> &Arc<CallbackSignal> {
+        &self.callback_signal
     }
-}
 
-// Document
+    /// Append rows to the data source with id `source`, keeping at most `rollover` rows
+    ///
+    /// Unlike other changes, the new rows are recorded as an event so that a server only sends
+    /// them rather than the whole source.
+    pub fn stream(
+        &mut self,
+        source: &Id,
+        data: HashMap<String, Vec<f64>>,
+        rollover: Option<usize>,
+    ) -> Result<()> {
+        let source_ref = self.update_source(source, |s| s.stream(&data, rollover))?;
+        self.events.push(events::DocumentEvent::ColumnsStreamed {
+            source: source_ref,
+            data: json!(data),
+            rollover,
+        });
+        Ok(())
+    }
 
-/// Main document object for the plot
-pub struct Document<'s> {
-    plot: Option<Plot<'s>>,
-}
+    /// Replace individual values of the data source with id `source`
+    ///
+    /// Like `stream`, the change is recorded as an event.
+    pub fn patch(
+        &mut self,
+        source: &Id,
+        patches: HashMap<String, Vec<(usize, f64)>>,
+    ) -> Result<()> {
+        let source_ref = self.update_source(source, |s| s.patch(&patches))?;
+        self.events.push(events::DocumentEvent::ColumnsPatched {
+            source: source_ref,
+            patches: json!(patches),
+        });
+        Ok(())
+    }
 
-impl<'s> Document<'s> {
-    /// Create a new document
-    pub fn new() -> Self {
-        Document { plot: None }
+    fn update_source<F>(&mut self, id: &Id, mut update: F) -> Result<Value>
+    where
+        F: FnMut(&mut ColumnDataSource) -> Result<()>,
+    {
+        let mut source_ref = None;
+        if let Some(root) = self.root.as_mut() {
+            for source in root.sources_mut(id) {
+                update(source)?;
+                source_ref = Some(source.as_bokeh_ref());
+            }
+        }
+        source_ref.ok_or_else(|| format_err!("no data source with id `{}`", id))
     }
 
-    /// Add the root plot to the document
-    pub fn add_root(&mut self, plot: Plot<'s>) {
-        self.plot = Some(plot);
+    /// Remove and return the stream and patch events recorded since the last call
+    pub fn take_events(&mut self) -> events::Patch {
+        std::mem::take(&mut self.events)
     }
 
     /// Check the document is sane
-    pub fn validate(self) -> Result<ValidatedDocument<'s>> {
-        let plot = self
-            .plot
-            .ok_or(format_err!("document requires a plot"))?
-            .validate()?;
+    pub fn validate(self) -> Result<ValidatedDocument> {
+        let root = self
+            .root
+            .ok_or_else(|| format_err!("document requires a plot"))?;
+        root.validate()?;
 
-        Ok(ValidatedDocument { plot })
+        Ok(ValidatedDocument {
+            root,
+            theme: self.theme,
+        })
     }
 }
 
 /// Represents a valid document
-pub struct ValidatedDocument<'s> {
-    plot: ValidatedPlot<'s>,
+pub struct ValidatedDocument {
+    root: LayoutDom,
+    theme: Option<themes::Theme>,
 }
 
-impl<'s> ValidatedDocument<'s> {
+impl ValidatedDocument {
     /// Get the references of all sub-objects to put into the JSON graph
+    ///
+    /// Models which are referenced from more than one place are only included once, and the
+    /// theme of the document, if any, is applied to each of them.
     pub fn references(&self) -> Vec<Value> {
-        let mut out = Vec::new();
-        out.push(self.plot.source.as_bokeh_value());
-        out
+        let mut seen = HashSet::new();
+        self.root
+            .references()
+            .into_iter()
+            .filter(|value| seen.insert(value["id"].to_string()))
+            .map(|mut value| {
+                if let Some(theme) = &self.theme {
+                    theme.apply(&mut value);
+                }
+                value
+            })
+            .collect()
+    }
+
+    /// Ids of the root models of the document
+    pub fn root_ids(&self) -> Vec<Value> {
+        vec![self.root.as_bokeh_ref()["id"].clone()]
     }
 }
 
-/// Write a document to a file at path `path`
-pub fn file_html<S>(_doc: &ValidatedDocument, _title: S) -> Result<String>
+#[derive(Template)]
+#[template(path = "file.html")]
+struct FileTemplate<'a> {
+    title: &'a str,
+    element_id: &'a str,
+    data_id: &'a str,
+    doc_id: &'a str,
+    root_id: &'a str,
+    docs_json: &'a str,
+}
+
+/// Render a document as a standalone HTML page, loading BokehJS from the CDN
+pub fn file_html<S>(doc: &ValidatedDocument, title: S) -> Result<String>
 where
     S: Into<String>,
 {
-    unimplemented!()
+    let title = title.into();
+    let doc_id = Id::new().to_string();
+    let docs_json = json!({ doc_id.as_str(): to_bokeh_json(doc, title.as_str())? });
+
+    let root_id = doc.root_ids()[0].as_str().unwrap_or_default().to_string();
+    FileTemplate {
+        title: &title,
+        element_id: &Id::new().to_string(),
+        data_id: &Id::new().to_string(),
+        doc_id: &doc_id,
+        root_id: &root_id,
+        // Stop the document from closing the script tag it is embedded in
+        docs_json: &docs_json.to_string().replace("</", "<\\/"),
+    }
+    .render()
+    .map_err(|e| format_err!("rendering page: {}", e))
 }
 
 /// Return the JSON representation as a serde_json::Value
@@ -299,9 +1841,10 @@ where
     let out = json!({
         "roots": {
             "references": references,
+            "root_ids": doc.root_ids(),
         },
         "title": title.into(),
-        "version": "1.0.3",
+        "version": BOKEH_VERSION,
     });
     Ok(out)
 }
@@ -333,13 +1876,10 @@ mod tests {
         };
     }
 
-    // TODO: test ids somehow
-
-    /*
     #[test]
     fn test_basic_tick_formatter() {
         let tf = BasicTickFormatter::new();
-        let json_value: Value = session.serialize(&tf).unwrap();
+        let json_value: Value = tf.as_bokeh_value();
 
         assert_without_id_equal!(
             json_value,
@@ -352,15 +1892,577 @@ mod tests {
 
     #[test]
     fn test_basic_ticker() {
-        let tf = BasicTicker::new();
+        let tf = BasicTicker::with_id(Id::from("1001"));
         let json_value: Value = tf.as_bokeh_value();
-        assert_without_id_equal!(
+        assert_eq!(
             json_value,
             json!({
                 "attributes": {},
+                "id": "1001",
                 "type": "BasicTicker",
             })
         );
     }
-    */
+
+    #[test]
+    fn test_circle() {
+        let mut circle = Circle::with_id(Id::from("1003"));
+        circle.x = Some("x".to_string());
+        circle.y = Some("y".to_string());
+        circle.fill_color = Some("red".to_string());
+        circle.size = Some(5);
+
+        assert_eq!(
+            circle.as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "fill_color": {"value": "red"},
+                    "size": {"units": "screen", "value": 5},
+                    "x": {"field": "x"},
+                    "y": {"field": "y"},
+                },
+                "id": "1003",
+                "type": "Circle",
+            })
+        );
+    }
+
+    #[test]
+    fn test_document_references() {
+        let mut source = ColumnDataSource::with_id(Id::from("1001"));
+        source.add("x", &[1.0, 2.0, 3.0]);
+
+        let mut plot = Plot::with_id(Id::from("1002"));
+        plot.add_glyph(&source, Circle::new());
+        plot.add_glyph(&source, Circle::new());
+        plot.add_layout(Position::Below, LinearAxis::new());
+        plot.add_tool(PanTool::new());
+
+        let mut doc = Document::new();
+        doc.add_root(plot);
+        let doc = doc.validate().unwrap();
+
+        let json_value = to_bokeh_json(&doc, "Test").unwrap();
+        assert_eq!(json_value["roots"]["root_ids"], json!(["1002"]));
+
+        let references = doc.references();
+        let types: Vec<&str> = references
+            .iter()
+            .map(|r| r["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types.iter().filter(|t| **t == "ColumnDataSource").count(),
+            1
+        );
+        assert!(types.contains(&"Plot"));
+        assert!(types.contains(&"LinearAxis"));
+        assert!(types.contains(&"BasicTicker"));
+        assert!(types.contains(&"Toolbar"));
+        assert!(types.contains(&"PanTool"));
+    }
+
+    #[test]
+    fn test_document_callbacks() {
+        let mut doc = Document::new();
+        let periodic = doc.add_periodic_callback(|_| {}, Duration::from_millis(500));
+        let timeout = doc.add_timeout_callback(|_| {}, Duration::from_millis(100));
+        doc.add_next_tick_callback(|doc| doc.add_root(Plot::new()));
+
+        let schedules: Vec<_> = doc.callbacks().iter().map(|c| c.schedule()).collect();
+        assert_eq!(
+            schedules,
+            vec![
+                CallbackSchedule::Periodic(Duration::from_millis(500)),
+                CallbackSchedule::Timeout(Duration::from_millis(100)),
+                CallbackSchedule::NextTick,
+            ]
+        );
+
+        assert!(doc.remove_callback(periodic));
+        assert!(!doc.remove_callback(periodic));
+        assert!(doc.remove_callback(timeout));
+
+        let next_tick = doc.callbacks()[0].clone();
+        next_tick.run(&mut doc);
+        assert!(doc.root().is_some());
+    }
+
+    #[test]
+    fn test_callback_signal() {
+        let mut doc = Document::new();
+        let signal = doc.callback_signal().clone();
+        let start = Instant::now();
+        signal.wait(Some(start + Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+
+        let waiter = {
+            let signal = signal.clone();
+            std::thread::spawn(move || signal.wait(None))
+        };
+        doc.add_next_tick_callback(|_| {});
+        waiter.join().unwrap();
+
+        // Copies are served separately, so adding callbacks to them doesn't wake the original
+        let mut copy = doc.clone();
+        assert!(!Arc::ptr_eq(copy.callback_signal(), &signal));
+        copy.add_next_tick_callback(|_| {});
+
+        let waiter = {
+            let signal = signal.clone();
+            std::thread::spawn(move || signal.wait(None))
+        };
+        signal.close();
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn test_plot_requires_glyph() {
+        let mut doc = Document::new();
+        doc.add_root(Plot::new());
+        assert!(doc.validate().is_err());
+    }
+
+    #[test]
+    fn test_file_html() {
+        let mut plot = Plot::with_id(Id::from("1001"));
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut doc = Document::new();
+        doc.add_root(plot);
+
+        let html = file_html(&doc.validate().unwrap(), "Example </plot>").unwrap();
+        assert!(html.contains("<title>Example &lt;&#x2f;plot&gt;</title>"));
+        assert!(html.contains(r#""roots":{"1001":"#));
+        assert!(html.contains(r#""title":"Example <\/plot>""#));
+        for bundle in &["bokeh", "bokeh-widgets"] {
+            let script = format!("release/{}-{}.min.js", bundle, BOKEH_VERSION);
+            assert!(html.contains(&script));
+        }
+    }
+
+    #[test]
+    fn test_column_data_source_stream_and_patch() {
+        let mut source = ColumnDataSource::new();
+        source.add("x", &[1.0, 2.0]);
+        source.add("y", &[3.0, 4.0]);
+
+        let mut data = HashMap::new();
+        data.insert("x".to_string(), vec![5.0]);
+        assert!(source.stream(&data, None).is_err());
+        data.insert("y".to_string(), vec![6.0]);
+        source.stream(&data, Some(2)).unwrap();
+        assert_eq!(source.columns["x"], Column::Numbers(vec![2.0, 5.0]));
+        assert_eq!(source.columns["y"], Column::Numbers(vec![4.0, 6.0]));
+
+        let mut patches = HashMap::new();
+        patches.insert("x".to_string(), vec![(1, 7.0)]);
+        source.patch(&patches).unwrap();
+        assert_eq!(source.columns["x"], Column::Numbers(vec![2.0, 7.0]));
+
+        patches.insert("y".to_string(), vec![(2, 8.0)]);
+        assert!(source.patch(&patches).is_err());
+        assert_eq!(source.columns["x"], Column::Numbers(vec![2.0, 7.0]));
+    }
+
+    #[test]
+    fn test_column_data_source_selection() {
+        let mut source = ColumnDataSource::with_id(Id::from("1001"));
+        source.selected = Selection::with_id(Id::from("1002"));
+        source.select(&[0, 2]);
+
+        let references = source.references();
+        assert_eq!(
+            references[0]["attributes"]["selected"],
+            json!({"id": "1002", "type": "Selection"})
+        );
+        assert_eq!(
+            references[1],
+            json!({"attributes": {"indices": [0, 2]}, "id": "1002", "type": "Selection"})
+        );
+    }
+
+    #[test]
+    fn test_plot_ranges() {
+        let range = Range1d::with_id(Id::from("1002"), 0.0, 10.0);
+
+        let mut plot = Plot::with_id(Id::from("1001"));
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        plot.x_range = Some(range.clone().into());
+
+        let mut range_tool = RangeTool::new();
+        range_tool.x_range = Some(range);
+        plot.add_tool(range_tool);
+
+        assert_eq!(
+            plot.as_bokeh_value()["attributes"]["x_range"],
+            json!({"id": "1002", "type": "Range1d"})
+        );
+        let references = plot.references();
+        assert!(references.iter().any(|r| r["type"] == "Range1d"));
+        assert!(plot.as_bokeh_value()["attributes"].get("y_range").is_none());
+    }
+
+    #[test]
+    fn test_plot_extra_y_range() {
+        let source = ColumnDataSource::new();
+        let mut plot = Plot::new();
+        plot.add_glyph(&source, Circle::new());
+        let renderer = plot.add_glyph(&source, Circle::new());
+        plot.renderer_mut(&renderer).unwrap().y_range_name = Some("rate".to_string());
+
+        let mut axis = LinearAxis::new();
+        axis.properties.y_range_name = Some("rate".to_string());
+        plot.add_layout(Position::Right, axis);
+        assert!(plot.clone().validate().is_err());
+
+        plot.add_extra_y_range("rate", Range1d::with_id(Id::from("1002"), 0.0, 1.0));
+        let value = plot.as_bokeh_value();
+        assert_eq!(
+            value["attributes"]["extra_y_ranges"],
+            json!({"rate": {"id": "1002", "type": "Range1d"}})
+        );
+        assert!(value["attributes"].get("extra_x_ranges").is_none());
+        assert!(plot
+            .references()
+            .iter()
+            .any(|r| r["attributes"]["y_range_name"] == "rate" && r["type"] == "GlyphRenderer"));
+        assert!(plot.validate().is_ok());
+    }
+
+    #[test]
+    fn test_plot_grids() {
+        let axis = LinearAxis::new();
+        let mut plot = Plot::new();
+        plot.add_grid(Grid::with_id(
+            Id::from("1001"),
+            Dimension::Width,
+            axis.ticker.clone(),
+        ));
+        plot.add_layout(Position::Below, axis);
+
+        let value = plot.as_bokeh_value();
+        assert_eq!(
+            value["attributes"]["renderers"][0],
+            json!({"id": "1001", "type": "Grid"})
+        );
+        assert_eq!(value["attributes"]["below"][0]["type"], json!("LinearAxis"));
+        let references = plot.references();
+        let tickers = references
+            .iter()
+            .filter(|r| r["type"] == "BasicTicker")
+            .map(|r| &r["id"])
+            .collect::<HashSet<_>>();
+        assert_eq!(tickers.len(), 1);
+
+        plot.remove_grids();
+        assert_eq!(
+            plot.as_bokeh_value()["attributes"]["renderers"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_minimal_plot() {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        plot.set_x_axis_type(AxisType::Linear);
+        plot.set_y_axis_type(AxisType::Linear);
+        plot.add_grid(Grid::new(Dimension::Width, BasicTicker::new()));
+        plot.minimal();
+
+        let attributes = plot.as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["renderers"].as_array().unwrap().len(), 1);
+        assert!(attributes.get("below").is_none());
+        assert!(attributes.get("left").is_none());
+        assert_eq!(attributes["toolbar_location"], Value::Null);
+        assert_eq!(attributes["outline_line_alpha"], json!({"value": 0.0}));
+        assert_eq!(attributes["min_border"], 0);
+    }
+
+    #[test]
+    fn test_plot_legend() {
+        let mut source = ColumnDataSource::with_id(Id::from("1001"));
+        source.add("x", &[1.0, 2.0, 3.0]);
+        source.add_factors("kind", vec!["a", "b", "a"]);
+
+        let mut plot = Plot::new();
+        assert!(plot.as_bokeh_value()["attributes"]["renderers"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+
+        let first = plot.add_glyph(&source, Circle::new());
+        let second = plot.add_glyph(&source, Circle::new());
+        let grouped = plot.add_glyph(&source, Circle::new());
+        plot.renderer_mut(&first).unwrap().legend_label = Some("x".to_string());
+        plot.renderer_mut(&second).unwrap().legend_label = Some("x".to_string());
+        plot.renderer_mut(&grouped).unwrap().legend_group = Some("kind".to_string());
+
+        let references = plot.references();
+        let legend = references.iter().find(|r| r["type"] == "Legend").unwrap();
+        assert_eq!(legend["attributes"]["items"].as_array().unwrap().len(), 3);
+        let items: Vec<_> = references
+            .iter()
+            .filter(|r| r["type"] == "LegendItem")
+            .map(|r| &r["attributes"])
+            .collect();
+        assert_eq!(items[0]["label"], json!({"value": "x"}));
+        assert_eq!(items[0]["renderers"].as_array().unwrap().len(), 2);
+        assert_eq!(items[1]["label"], json!({"value": "a"}));
+        assert_eq!(items[1]["index"], 0);
+        assert_eq!(items[2]["label"], json!({"value": "b"}));
+        assert_eq!(items[2]["index"], 1);
+        assert_eq!(plot.references(), references);
+        assert!(plot.clone().validate().is_ok());
+
+        plot.renderer_mut(&grouped).unwrap().legend_field = Some("kind".to_string());
+        assert!(plot.clone().validate().is_err());
+        plot.renderer_mut(&grouped).unwrap().legend_group = None;
+        plot.renderer_mut(&grouped).unwrap().legend_field = Some("missing".to_string());
+        assert!(plot.validate().is_err());
+    }
+
+    #[test]
+    fn test_legend_panel() {
+        let mut plot = Plot::with_id(Id::from("1001"));
+        let renderer = plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        plot.renderer_mut(&renderer).unwrap().legend_label = Some("series".to_string());
+        plot.legend = Legend::with_id(Id::from("1002"));
+        plot.legend_panel = Some(Position::Right);
+        plot.add_layout(Position::Right, LinearAxis::new());
+
+        let mut other = Legend::with_id(Id::from("1003"));
+        other.add_item(LegendItem::new(LegendLabel::Value("other".to_string())));
+        plot.add_layout(Position::Below, other);
+
+        let attributes = plot.as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["right"][0]["type"], "LinearAxis");
+        assert_eq!(
+            attributes["right"][1],
+            json!({"id": "1002", "type": "Legend"})
+        );
+        assert_eq!(
+            attributes["below"][0],
+            json!({"id": "1003", "type": "Legend"})
+        );
+        let renderers = attributes["renderers"].as_array().unwrap();
+        assert_eq!(
+            renderers.iter().filter(|r| r["type"] == "Legend").count(),
+            2
+        );
+        assert_eq!(
+            plot.references()
+                .iter()
+                .filter(|r| r["type"] == "LegendItem")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_insert_layout() {
+        let mut plot = Plot::new();
+        plot.add_layout(Position::Above, LinearAxis::with_id(Id::from("1001")));
+        plot.add_layout(Position::Below, Title::with_id(Id::from("1002"), "Caption"));
+        plot.add_layout(Position::Above, Title::with_id(Id::from("1003"), "Title"));
+        plot.insert_layout(
+            Position::Above,
+            1,
+            Title::with_id(Id::from("1004"), "Subtitle"),
+        );
+        plot.insert_layout(Position::Below, 0, LinearAxis::with_id(Id::from("1005")));
+        plot.insert_layout(
+            Position::Below,
+            5,
+            Title::with_id(Id::from("1006"), "Source"),
+        );
+
+        let attributes = plot.as_bokeh_value()["attributes"].clone();
+        let ids = |side: &str| -> Vec<Value> {
+            attributes[side]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|layout| layout["id"].clone())
+                .collect()
+        };
+        assert_eq!(ids("above"), vec!["1001", "1004", "1003"]);
+        assert_eq!(ids("below"), vec!["1005", "1002", "1006"]);
+    }
+
+    #[test]
+    fn test_stream_updates_label_set() {
+        let mut source = ColumnDataSource::with_id(Id::from("source"));
+        source.add("x", &[1.0]);
+        let mut plot = Plot::new();
+        plot.add_glyph(&source, Circle::new());
+        plot.add_layout(Position::Center, LabelSet::new(&source, "x", "x", "x"));
+
+        for source in plot.sources_mut(&Id::from("source")) {
+            source.add("x", &[1.0, 2.0]);
+        }
+        let references = plot.references();
+        let sources: Vec<_> = references.iter().filter(|r| r["id"] == "source").collect();
+        assert_eq!(sources.len(), 2);
+        assert!(sources
+            .iter()
+            .all(|s| s["attributes"]["data"]["x"] == json!([1.0, 2.0])));
+    }
+
+    #[test]
+    fn test_shape_glyphs() {
+        let mut source = ColumnDataSource::with_id(Id::from("source"));
+        source.add_lists("xs", &[vec![0.0, 1.0, 1.0], vec![2.0, 3.0]]);
+        source.add_lists("ys", &[vec![0.0, 0.0, 1.0], vec![2.0, 2.0]]);
+        assert_eq!(
+            source.as_bokeh_value()["attributes"]["data"],
+            json!({"xs": [[0.0, 1.0, 1.0], [2.0, 3.0]], "ys": [[0.0, 0.0, 1.0], [2.0, 2.0]]})
+        );
+        let mut data = HashMap::new();
+        data.insert("xs".to_string(), vec![4.0]);
+        data.insert("ys".to_string(), vec![4.0]);
+        assert!(source.stream(&data, None).is_err());
+
+        let mut patches = Patches::with_id(Id::from("1001"));
+        patches.xs = Some("xs".to_string());
+        patches.ys = Some("ys".to_string());
+        patches.fill_alpha = Some(0.5);
+        assert_eq!(
+            Glyph::from(patches.clone()).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "fill_alpha": {"value": 0.5},
+                    "xs": {"field": "xs"},
+                    "ys": {"field": "ys"},
+                },
+                "id": "1001",
+                "type": "Patches",
+            })
+        );
+
+        let mut lines = MultiLine::with_id(Id::from("1002"));
+        lines.xs = Some("xs".to_string());
+        lines.line_width = Some(2.0);
+        assert_eq!(
+            Glyph::from(lines).as_bokeh_value()["attributes"],
+            json!({"line_width": {"value": 2.0}, "xs": {"field": "xs"}})
+        );
+
+        let mut rect = Rect::with_id(Id::from("1003"));
+        rect.x = Some("x".to_string());
+        rect.width = Some("w".to_string());
+        rect.fill_color = Some("red".to_string());
+        assert_eq!(
+            Glyph::from(rect).as_bokeh_value()["attributes"],
+            json!({
+                "fill_color": {"value": "red"},
+                "width": {"field": "w"},
+                "x": {"field": "x"},
+            })
+        );
+
+        // Drawing tools edit the sources of the renderers they are given
+        let mut plot = Plot::new();
+        let renderer = plot.add_glyph(&source, patches);
+        let mut tool = PolyDrawTool::new();
+        tool.add_renderer(&renderer);
+        plot.add_tool(tool);
+        let mut doc = Document::new();
+        doc.add_root(plot);
+        let references = doc.validate().unwrap().references();
+        let tool = references
+            .iter()
+            .find(|r| r["type"] == "PolyDrawTool")
+            .unwrap();
+        assert_eq!(tool["attributes"]["renderers"][0]["id"], json!(renderer));
+        assert!(references.iter().any(|r| r["type"] == "Patches"));
+    }
+
+    #[test]
+    fn test_muted_glyph() {
+        let mut plot = Plot::new();
+        let renderer = plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut muted = Circle::with_id(Id::from("1001"));
+        muted.fill_alpha = Some(0.2);
+        muted.line_alpha = Some(0.2);
+        plot.renderer_mut(&renderer).unwrap().muted_glyph = Some(muted.into());
+        plot.legend.click_policy = Some(ClickPolicy::Mute);
+
+        let references = plot.references();
+        let renderer = references
+            .iter()
+            .find(|r| r["type"] == "GlyphRenderer")
+            .unwrap();
+        assert_eq!(
+            renderer["attributes"]["muted_glyph"],
+            json!({"id": "1001", "type": "Circle"})
+        );
+        let muted = references.iter().find(|r| r["id"] == "1001").unwrap();
+        assert_eq!(muted["attributes"]["fill_alpha"], json!({"value": 0.2}));
+    }
+
+    #[test]
+    fn test_plot_toolbar_location() {
+        let mut plot = Plot::new();
+        assert!(plot.as_bokeh_value()["attributes"]
+            .get("toolbar_location")
+            .is_none());
+
+        plot.toolbar_location = Some(ToolbarLocation::Above);
+        assert_eq!(
+            plot.as_bokeh_value()["attributes"]["toolbar_location"],
+            "above"
+        );
+        plot.toolbar_location = Some(ToolbarLocation::Hidden);
+        assert_eq!(
+            plot.as_bokeh_value()["attributes"]["toolbar_location"],
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_plot_log_axis_type() {
+        let mut plot = Plot::new();
+        plot.set_y_axis_type(AxisType::Log);
+
+        let attributes = &plot.as_bokeh_value()["attributes"];
+        assert_eq!(attributes["left"][0]["type"], "LogAxis");
+        assert_eq!(attributes["y_scale"]["type"], "LogScale");
+        assert!(attributes.get("x_scale").is_none());
+
+        let types: Vec<Value> = plot
+            .references()
+            .iter()
+            .map(|r| r["type"].clone())
+            .collect();
+        for kind in &["LogAxis", "LogTicker", "LogTickFormatter", "LogScale"] {
+            assert!(types.contains(&json!(kind)));
+        }
+    }
+
+    #[test]
+    fn test_categorical_plot() {
+        let mut source = ColumnDataSource::with_id(Id::from("1001"));
+        source.add_factors("fruit", vec![("2019", "apples"), ("2019", "pears")]);
+        source.add("count", &[3.0, 5.0]);
+        assert_eq!(
+            source.as_bokeh_value()["attributes"]["data"],
+            json!({"count": [3.0, 5.0], "fruit": [["2019", "apples"], ["2019", "pears"]]})
+        );
+        assert!(source
+            .stream(&[("fruit".to_string(), vec![1.0])].into(), None)
+            .is_err());
+
+        let mut plot = Plot::new();
+        plot.add_glyph(&source, Circle::new());
+        plot.x_range = Some(FactorRange::new(vec![("2019", "apples"), ("2019", "pears")]).into());
+        plot.set_x_axis_type(AxisType::Categorical);
+
+        let attributes = &plot.as_bokeh_value()["attributes"];
+        assert_eq!(attributes["x_range"]["type"], "FactorRange");
+        assert_eq!(attributes["x_scale"]["type"], "CategoricalScale");
+        assert_eq!(attributes["below"][0]["type"], "CategoricalAxis");
+    }
 }
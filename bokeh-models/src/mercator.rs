@@ -0,0 +1,69 @@
+//! Conversion between longitudes and latitudes and Web Mercator coordinates
+//!
+//! Map tiles use Web Mercator coordinates, in metres. Data given as longitudes and latitudes must
+//! be converted before it is plotted over them; a `MercatorAxis` then labels the converted values
+//! with their longitudes and latitudes again.
+
+use crate::Result;
+use failure::format_err;
+use std::f64::consts::PI;
+
+/// Radius of the earth used by Web Mercator, in metres
+const EARTH_RADIUS: f64 = 6_378_137.0;
+
+/// Convert a longitude and latitude in degrees to Web Mercator `(x, y)` coordinates
+///
+/// Latitudes beyond about ±85.05° lie outside the square covered by map tiles.
+pub fn from_lon_lat(lon: f64, lat: f64) -> (f64, f64) {
+    let x = lon.to_radians() * EARTH_RADIUS;
+    let y = (PI / 4.0 + lat.to_radians() / 2.0).tan().ln() * EARTH_RADIUS;
+    (x, y)
+}
+
+/// Convert Web Mercator `(x, y)` coordinates to a longitude and latitude in degrees
+pub fn to_lon_lat(x: f64, y: f64) -> (f64, f64) {
+    let lon = (x / EARTH_RADIUS).to_degrees();
+    let lat = (2.0 * (y / EARTH_RADIUS).exp().atan() - PI / 2.0).to_degrees();
+    (lon, lat)
+}
+
+/// Convert columns of longitudes and latitudes to columns of Web Mercator `x` and `y` coordinates
+///
+/// The columns must be the same length.
+pub fn from_lon_lat_columns(lons: &[f64], lats: &[f64]) -> Result<(Vec<f64>, Vec<f64>)> {
+    if lons.len() != lats.len() {
+        return Err(format_err!(
+            "{} longitudes but {} latitudes",
+            lons.len(),
+            lats.len()
+        ));
+    }
+    Ok(lons
+        .iter()
+        .zip(lats)
+        .map(|(&lon, &lat)| from_lon_lat(lon, lat))
+        .unzip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mercator_conversion() {
+        let (x, y) = from_lon_lat(-0.1278, 51.5074);
+        assert!((x - -14_226.63).abs() < 0.01);
+        assert!((y - 6_711_542.47).abs() < 0.01);
+
+        let (lon, lat) = to_lon_lat(x, y);
+        assert!((lon - -0.1278).abs() < 1e-9);
+        assert!((lat - 51.5074).abs() < 1e-9);
+
+        let (xs, ys) = from_lon_lat_columns(&[0.0, 180.0], &[0.0, 85.0511]).unwrap();
+        assert_eq!(xs[0], 0.0);
+        assert!((xs[1] - 20_037_508.34).abs() < 0.01);
+        assert!(ys[0].abs() < 1e-6);
+        assert!((ys[1] - 20_037_471.21).abs() < 0.01);
+        assert!(from_lon_lat_columns(&[0.0], &[]).is_err());
+    }
+}
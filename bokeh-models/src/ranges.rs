@@ -0,0 +1,648 @@
+//! Ranges, the extent of the values shown along each dimension of a plot
+
+use crate::callbacks::JsCallbacks;
+use crate::{CustomJS, Id, ToBokeh};
+use serde_derive::Serialize;
+use serde_json::{json, Value};
+
+/// Range of values shown along one dimension of a plot
+///
+/// Ranges are serialized as references, and copies of a range share its id. Giving copies of the
+/// same range to several plots links them, so panning or zooming one plot moves the others.
+#[derive(Clone)]
+pub enum Range {
+    /// Range with explicit start and end
+    Range1d(Range1d),
+    /// Range fitted to the data of the plot
+    DataRange1d(DataRange1d),
+    /// Range of categories
+    FactorRange(FactorRange),
+}
+
+impl ToBokeh for Range {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Range::Range1d(r) => r.as_bokeh_value(),
+            Range::DataRange1d(r) => r.as_bokeh_value(),
+            Range::FactorRange(r) => r.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Range::Range1d(r) => r.references(),
+            Range::DataRange1d(r) => r.references(),
+            Range::FactorRange(r) => r.references(),
+        }
+    }
+}
+
+/// Range with an explicit start and end
+///
+/// Setting one as the `x_range` or `y_range` of a plot fixes the extent of that axis rather than
+/// fitting it to the data. A `start` greater than `end` flips the axis.
+///
+/// Copies of a range share its id, so giving the same range to a plot and a `RangeTool` lets the
+/// tool move the plot's view.
+#[derive(Clone)]
+pub struct Range1d {
+    id: Id,
+    /// Start of the range
+    pub start: f64,
+    /// End of the range
+    pub end: f64,
+    /// Start to return to when the plot is reset, `start` if not set
+    pub reset_start: Option<f64>,
+    /// End to return to when the plot is reset, `end` if not set
+    pub reset_end: Option<f64>,
+    /// Limits the range can be panned or zoomed to
+    pub bounds: Option<Bounds>,
+    /// Smallest extent the range can be zoomed in to
+    pub min_interval: Option<f64>,
+    /// Largest extent the range can be zoomed out to
+    pub max_interval: Option<f64>,
+    js_callbacks: JsCallbacks,
+}
+
+impl Range1d {
+    /// Create a new range from `start` to `end`
+    pub fn new(start: f64, end: f64) -> Self {
+        Range1d::with_id(Id::new(), start, end)
+    }
+
+    /// Create a new range from `start` to `end` with a known id
+    pub fn with_id(id: Id, start: f64, end: f64) -> Self {
+        Range1d {
+            id,
+            start,
+            end,
+            reset_start: None,
+            reset_end: None,
+            bounds: None,
+            min_interval: None,
+            max_interval: None,
+            js_callbacks: JsCallbacks::default(),
+        }
+    }
+
+    /// Run `callback` in the browser whenever the property `attr` of the range changes, such as
+    /// `"start"` or `"end"`
+    pub fn js_on_change<S>(&mut self, attr: S, callback: CustomJS)
+    where
+        S: AsRef<str>,
+    {
+        self.js_callbacks.add_change(attr.as_ref(), callback);
+    }
+
+    /// Keep the property `target_attr` of `target` equal to the property `attr` of the range
+    ///
+    /// E.g. linking `"start"` to the `"start"` of another range keeps their starts together.
+    pub fn js_link<S, T, M>(&mut self, attr: S, target: &M, target_attr: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        M: ToBokeh,
+    {
+        self.js_callbacks
+            .add_link(attr.as_ref(), target, target_attr.as_ref());
+    }
+}
+
+impl From<Range1d> for Range {
+    fn from(r: Range1d) -> Range {
+        Range::Range1d(r)
+    }
+}
+
+impl ToBokeh for Range1d {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "end": self.end,
+            "start": self.start,
+        });
+        if let Some(reset_start) = self.reset_start {
+            attributes["reset_start"] = json!(reset_start);
+        }
+        if let Some(reset_end) = self.reset_end {
+            attributes["reset_end"] = json!(reset_end);
+        }
+
+        add_limits(
+            &mut attributes,
+            self.bounds,
+            self.min_interval,
+            self.max_interval,
+        );
+        self.js_callbacks.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Range1d",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.js_callbacks.references());
+        out
+    }
+}
+
+/// Limits a range can be panned or zoomed to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bounds {
+    /// Limit the range to the extent of the data when the plot is first shown
+    Auto,
+    /// Limit the range to a minimum and maximum, unlimited on either side if not set
+    Limits(Option<f64>, Option<f64>),
+}
+
+impl Bounds {
+    fn value(self) -> Value {
+        match self {
+            Bounds::Auto => json!("auto"),
+            Bounds::Limits(min, max) => json!([min, max]),
+        }
+    }
+}
+
+fn add_limits(
+    attributes: &mut Value,
+    bounds: Option<Bounds>,
+    min_interval: Option<f64>,
+    max_interval: Option<f64>,
+) {
+    if let Some(bounds) = bounds {
+        attributes["bounds"] = bounds.value();
+    }
+    if let Some(min_interval) = min_interval {
+        attributes["min_interval"] = json!(min_interval);
+    }
+    if let Some(max_interval) = max_interval {
+        attributes["max_interval"] = json!(max_interval);
+    }
+}
+
+/// Units of the padding of a `DataRange1d`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaddingUnits {
+    /// Padding is a fraction of the extent of the data
+    Percent,
+    /// Padding is in data units
+    Absolute,
+}
+
+impl PaddingUnits {
+    fn name(self) -> &'static str {
+        match self {
+            PaddingUnits::Percent => "percent",
+            PaddingUnits::Absolute => "absolute",
+        }
+    }
+}
+
+/// End of a `DataRange1d` that stays fixed when the range follows the data
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Follow {
+    /// Keep the start at the start of the data
+    Start,
+    /// Keep the end at the end of the data, as for streaming data
+    End,
+}
+
+impl Follow {
+    fn name(self) -> &'static str {
+        match self {
+            Follow::Start => "start",
+            Follow::End => "end",
+        }
+    }
+}
+
+/// Range fitted to the data of the plot
+///
+/// This is what BokehJS uses when a plot has no range. Setting `follow` and `follow_interval`
+/// keeps a window of the latest data in view, so a plot of a streaming source scrolls as data
+/// arrives.
+///
+/// `only_visible` is not supported, as it is missing from BokehJS 1.0.
+#[derive(Clone, Default)]
+pub struct DataRange1d {
+    id: Id,
+    /// Padding added around the data, 0.1 if not set
+    pub range_padding: Option<f64>,
+    /// Units of `range_padding`, percent if not set
+    pub range_padding_units: Option<PaddingUnits>,
+    /// Whether the range runs from the end of the data to the start
+    pub flipped: Option<bool>,
+    /// End of the range to keep fixed to the data
+    pub follow: Option<Follow>,
+    /// Width of the range when it follows the data
+    pub follow_interval: Option<f64>,
+    /// Limits the range can be panned or zoomed to
+    pub bounds: Option<Bounds>,
+    /// Smallest extent the range can be zoomed in to
+    pub min_interval: Option<f64>,
+    /// Largest extent the range can be zoomed out to
+    pub max_interval: Option<f64>,
+    js_callbacks: JsCallbacks,
+}
+
+impl DataRange1d {
+    /// Create a new data range
+    pub fn new() -> Self {
+        DataRange1d::default()
+    }
+
+    /// Create a new data range with a known id
+    pub fn with_id(id: Id) -> Self {
+        DataRange1d {
+            id,
+            ..DataRange1d::default()
+        }
+    }
+
+    /// Run `callback` in the browser whenever the property `attr` of the range changes, such as
+    /// `"start"` or `"end"`
+    pub fn js_on_change<S>(&mut self, attr: S, callback: CustomJS)
+    where
+        S: AsRef<str>,
+    {
+        self.js_callbacks.add_change(attr.as_ref(), callback);
+    }
+
+    /// Keep the property `target_attr` of `target` equal to the property `attr` of the range
+    ///
+    /// E.g. linking `"start"` to the `"start"` of a `Range1d` makes it follow the start computed
+    /// from the data.
+    pub fn js_link<S, T, M>(&mut self, attr: S, target: &M, target_attr: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        M: ToBokeh,
+    {
+        self.js_callbacks
+            .add_link(attr.as_ref(), target, target_attr.as_ref());
+    }
+}
+
+impl From<DataRange1d> for Range {
+    fn from(r: DataRange1d) -> Range {
+        Range::DataRange1d(r)
+    }
+}
+
+impl ToBokeh for DataRange1d {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(range_padding) = self.range_padding {
+            attributes["range_padding"] = json!(range_padding);
+        }
+        if let Some(units) = self.range_padding_units {
+            attributes["range_padding_units"] = json!(units.name());
+        }
+        if let Some(flipped) = self.flipped {
+            attributes["flipped"] = json!(flipped);
+        }
+        if let Some(follow) = self.follow {
+            attributes["follow"] = json!(follow.name());
+        }
+        if let Some(follow_interval) = self.follow_interval {
+            attributes["follow_interval"] = json!(follow_interval);
+        }
+
+        add_limits(
+            &mut attributes,
+            self.bounds,
+            self.min_interval,
+            self.max_interval,
+        );
+        self.js_callbacks.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "DataRange1d",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.js_callbacks.references());
+        out
+    }
+}
+
+/// Category of a categorical plot, optionally nested in groups
+///
+/// Nested factors are drawn grouped together on a `CategoricalAxis`, with the group names below
+/// the categories.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Factor {
+    /// Category on its own
+    Category(String),
+    /// Category within a group
+    Group(String, String),
+    /// Category within a subgroup within a group
+    Subgroup(String, String, String),
+}
+
+impl Factor {
+    // Factor as text, with nested factors joined by spaces
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Factor::Category(category) => category.clone(),
+            Factor::Group(group, category) => format!("{} {}", group, category),
+            Factor::Subgroup(group, subgroup, category) => {
+                format!("{} {} {}", group, subgroup, category)
+            }
+        }
+    }
+}
+
+impl From<&str> for Factor {
+    fn from(category: &str) -> Factor {
+        Factor::Category(category.to_string())
+    }
+}
+
+impl From<String> for Factor {
+    fn from(category: String) -> Factor {
+        Factor::Category(category)
+    }
+}
+
+impl From<(&str, &str)> for Factor {
+    fn from((group, category): (&str, &str)) -> Factor {
+        Factor::Group(group.to_string(), category.to_string())
+    }
+}
+
+impl From<(&str, &str, &str)> for Factor {
+    fn from((group, subgroup, category): (&str, &str, &str)) -> Factor {
+        Factor::Subgroup(
+            group.to_string(),
+            subgroup.to_string(),
+            category.to_string(),
+        )
+    }
+}
+
+/// Range of categories, shown in order along a `CategoricalAxis`
+///
+/// Every factor must have the same depth of nesting.
+#[derive(Clone)]
+pub struct FactorRange {
+    id: Id,
+    /// Categories of the range, in order
+    pub factors: Vec<Factor>,
+    /// Padding between categories, as a fraction of the width of a category
+    pub factor_padding: Option<f64>,
+    /// Padding between the subgroups of nested factors, 0.8 if not set
+    pub subgroup_padding: Option<f64>,
+    /// Padding between the groups of nested factors, 1.4 if not set
+    pub group_padding: Option<f64>,
+    /// Padding added at the ends of the range
+    pub range_padding: Option<f64>,
+    /// Units of `range_padding`, percent if not set
+    pub range_padding_units: Option<PaddingUnits>,
+    /// Limits the range can be panned or zoomed to, in synthetic coordinates where each category
+    /// is one unit wide
+    pub bounds: Option<Bounds>,
+    /// Smallest extent the range can be zoomed in to, in synthetic coordinates
+    pub min_interval: Option<f64>,
+    /// Largest extent the range can be zoomed out to, in synthetic coordinates
+    pub max_interval: Option<f64>,
+    js_callbacks: JsCallbacks,
+}
+
+impl FactorRange {
+    /// Create a new range of `factors`
+    pub fn new<I>(factors: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Factor>,
+    {
+        FactorRange::with_id(Id::new(), factors)
+    }
+
+    /// Create a new range of `factors` with a known id
+    pub fn with_id<I>(id: Id, factors: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Factor>,
+    {
+        FactorRange {
+            id,
+            factors: factors.into_iter().map(Into::into).collect(),
+            factor_padding: None,
+            subgroup_padding: None,
+            group_padding: None,
+            range_padding: None,
+            range_padding_units: None,
+            bounds: None,
+            min_interval: None,
+            max_interval: None,
+            js_callbacks: JsCallbacks::default(),
+        }
+    }
+
+    /// Run `callback` in the browser whenever the property `attr` of the range changes, such as
+    /// `"factors"` or `"range_padding"`
+    pub fn js_on_change<S>(&mut self, attr: S, callback: CustomJS)
+    where
+        S: AsRef<str>,
+    {
+        self.js_callbacks.add_change(attr.as_ref(), callback);
+    }
+
+    /// Keep the property `target_attr` of `target` equal to the property `attr` of the range
+    ///
+    /// E.g. linking `"factors"` to the `"factors"` of another factor range keeps both showing the
+    /// same categories. The start and end of a factor range are computed from its factors, so
+    /// link the factors rather than those.
+    pub fn js_link<S, T, M>(&mut self, attr: S, target: &M, target_attr: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        M: ToBokeh,
+    {
+        self.js_callbacks
+            .add_link(attr.as_ref(), target, target_attr.as_ref());
+    }
+}
+
+impl From<FactorRange> for Range {
+    fn from(r: FactorRange) -> Range {
+        Range::FactorRange(r)
+    }
+}
+
+impl ToBokeh for FactorRange {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "factors": self.factors,
+        });
+        if let Some(factor_padding) = self.factor_padding {
+            attributes["factor_padding"] = json!(factor_padding);
+        }
+        if let Some(subgroup_padding) = self.subgroup_padding {
+            attributes["subgroup_padding"] = json!(subgroup_padding);
+        }
+        if let Some(group_padding) = self.group_padding {
+            attributes["group_padding"] = json!(group_padding);
+        }
+        if let Some(range_padding) = self.range_padding {
+            attributes["range_padding"] = json!(range_padding);
+        }
+        if let Some(units) = self.range_padding_units {
+            attributes["range_padding_units"] = json!(units.name());
+        }
+
+        add_limits(
+            &mut attributes,
+            self.bounds,
+            self.min_interval,
+            self.max_interval,
+        );
+        self.js_callbacks.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "FactorRange",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.js_callbacks.references());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Plot;
+
+    #[test]
+    fn test_range1d() {
+        let mut range = Range1d::with_id(Id::from("1001"), 10.0, 0.0);
+        assert_eq!(
+            range.as_bokeh_value(),
+            json!({
+                "attributes": {"end": 0.0, "start": 10.0},
+                "id": "1001",
+                "type": "Range1d",
+            })
+        );
+
+        range.reset_start = Some(5.0);
+        range.reset_end = Some(-5.0);
+        let attributes = Range::from(range).as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["reset_start"], json!(5.0));
+        assert_eq!(attributes["reset_end"], json!(-5.0));
+    }
+
+    #[test]
+    fn test_linked_ranges() {
+        let range: Range = DataRange1d::with_id(Id::from("1001")).into();
+        let mut first = Plot::new();
+        first.x_range = Some(range.clone());
+        let mut second = Plot::new();
+        second.x_range = Some(range);
+        second.y_range = Some(Range1d::new(0.0, 1.0).into());
+
+        let first = first.as_bokeh_value();
+        let second = second.as_bokeh_value();
+        assert_eq!(
+            first["attributes"]["x_range"],
+            json!({"id": "1001", "type": "DataRange1d"})
+        );
+        assert_eq!(
+            first["attributes"]["x_range"],
+            second["attributes"]["x_range"]
+        );
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut range = Range1d::new(0.0, 10.0);
+        range.bounds = Some(Bounds::Limits(Some(0.0), None));
+        range.min_interval = Some(1.0);
+        range.max_interval = Some(100.0);
+        let attributes = range.as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["bounds"], json!([0.0, null]));
+        assert_eq!(attributes["min_interval"], json!(1.0));
+        assert_eq!(attributes["max_interval"], json!(100.0));
+
+        let mut range = DataRange1d::new();
+        range.bounds = Some(Bounds::Auto);
+        assert_eq!(range.as_bokeh_value()["attributes"]["bounds"], "auto");
+    }
+
+    #[test]
+    fn test_data_range1d() {
+        let mut range = DataRange1d::with_id(Id::from("1001"));
+        assert_eq!(range.as_bokeh_value()["attributes"], json!({}));
+
+        range.follow = Some(Follow::End);
+        range.follow_interval = Some(100.0);
+        range.range_padding = Some(0.0);
+        range.range_padding_units = Some(PaddingUnits::Absolute);
+        range.flipped = Some(true);
+        assert_eq!(
+            Range::from(range).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "flipped": true,
+                    "follow": "end",
+                    "follow_interval": 100.0,
+                    "range_padding": 0.0,
+                    "range_padding_units": "absolute",
+                },
+                "id": "1001",
+                "type": "DataRange1d",
+            })
+        );
+    }
+
+    #[test]
+    fn test_factor_range() {
+        let range = FactorRange::with_id(
+            Id::from("1001"),
+            vec![("fruit", "apples"), ("fruit", "pears"), ("veg", "kale")],
+        );
+        assert_eq!(
+            Range::from(range).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "factors": [["fruit", "apples"], ["fruit", "pears"], ["veg", "kale"]],
+                },
+                "id": "1001",
+                "type": "FactorRange",
+            })
+        );
+        assert_eq!(json!(Factor::from("apples")), json!("apples"));
+    }
+
+    #[test]
+    fn test_factor_range_padding() {
+        let mut range = FactorRange::new(vec!["a", "b"]);
+        range.factor_padding = Some(0.1);
+        range.group_padding = Some(2.0);
+        range.subgroup_padding = Some(1.0);
+        range.range_padding = Some(0.5);
+        range.range_padding_units = Some(PaddingUnits::Absolute);
+
+        let attributes = range.as_bokeh_value()["attributes"].clone();
+        assert_eq!(attributes["factor_padding"], json!(0.1));
+        assert_eq!(attributes["group_padding"], json!(2.0));
+        assert_eq!(attributes["subgroup_padding"], json!(1.0));
+        assert_eq!(attributes["range_padding"], json!(0.5));
+        assert_eq!(attributes["range_padding_units"], "absolute");
+    }
+}
@@ -0,0 +1,129 @@
+//! Scales, mapping data values to positions on the plot
+
+use crate::{Id, ToBokeh};
+use serde_json::{json, Value};
+
+/// Scales of the plot axes
+#[derive(Clone)]
+pub enum Scale {
+    /// Positions proportional to the values
+    LinearScale(LinearScale),
+    /// Positions proportional to the logarithm of the values
+    LogScale(LogScale),
+    /// Positions of the categories of a `FactorRange`
+    CategoricalScale(CategoricalScale),
+}
+
+impl ToBokeh for Scale {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Scale::LinearScale(s) => s.as_bokeh_value(),
+            Scale::LogScale(s) => s.as_bokeh_value(),
+            Scale::CategoricalScale(s) => s.as_bokeh_value(),
+        }
+    }
+}
+
+/// Scale with positions proportional to the values
+#[derive(Clone, Default)]
+pub struct LinearScale {
+    id: Id,
+}
+
+impl LinearScale {
+    /// Create a new linear scale
+    pub fn new() -> Self {
+        LinearScale::default()
+    }
+
+    /// Create a new linear scale with a known id
+    pub fn with_id(id: Id) -> Self {
+        LinearScale { id }
+    }
+}
+
+impl From<LinearScale> for Scale {
+    fn from(s: LinearScale) -> Scale {
+        Scale::LinearScale(s)
+    }
+}
+
+impl ToBokeh for LinearScale {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "LinearScale",
+        })
+    }
+}
+
+/// Scale with positions proportional to the logarithm of the values
+///
+/// Values must be positive.
+#[derive(Clone, Default)]
+pub struct LogScale {
+    id: Id,
+}
+
+impl LogScale {
+    /// Create a new log scale
+    pub fn new() -> Self {
+        LogScale::default()
+    }
+
+    /// Create a new log scale with a known id
+    pub fn with_id(id: Id) -> Self {
+        LogScale { id }
+    }
+}
+
+impl From<LogScale> for Scale {
+    fn from(s: LogScale) -> Scale {
+        Scale::LogScale(s)
+    }
+}
+
+impl ToBokeh for LogScale {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "LogScale",
+        })
+    }
+}
+
+/// Scale placing the categories of a `FactorRange` in order
+#[derive(Clone, Default)]
+pub struct CategoricalScale {
+    id: Id,
+}
+
+impl CategoricalScale {
+    /// Create a new categorical scale
+    pub fn new() -> Self {
+        CategoricalScale::default()
+    }
+
+    /// Create a new categorical scale with a known id
+    pub fn with_id(id: Id) -> Self {
+        CategoricalScale { id }
+    }
+}
+
+impl From<CategoricalScale> for Scale {
+    fn from(s: CategoricalScale) -> Scale {
+        Scale::CategoricalScale(s)
+    }
+}
+
+impl ToBokeh for CategoricalScale {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "CategoricalScale",
+        })
+    }
+}
@@ -0,0 +1,959 @@
+//! Embedded application server, the equivalent of `bokeh serve`
+//!
+//! Every page request creates a new session holding a fresh `Document`, built by a user supplied
+//! closure. BokehJS in the browser then connects back over a websocket, pulls the document and
+//! exchanges changes using the Bokeh server protocol. Changes made by one client of a session are
+//! forwarded to the other clients of the same session, and the periodic, timeout and next tick
+//! callbacks of each session's document are run with their changes pushed to its clients.
+//! Sessions without any clients are discarded once they have been unused for a while.
+//!
+//! Applications can also be embedded into other pages through `/autoload.js`, using the script
+//! tags made by `embed::server_document` and `embed::server_session`.
+//!
+//! Requests can be checked by an `AuthProvider`, and the document factory can be given the
+//! `SessionContext` of the request which created its session, including the authenticated user.
+
+use crate::{Document, Result, BOKEH_VERSION};
+use askama::Template;
+use failure::format_err;
+use log::{debug, warn};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::{Message as WsMessage, WebSocket};
+
+mod auth;
+mod callbacks;
+mod session;
+
+pub use self::auth::{
+    check_token_signature, generate_token, token_payload, AuthProvider, Request, User,
+};
+pub use self::session::{
+    check_session_id, generate_secret_key, generate_session_id, DocumentHandle, Session,
+    SessionContext,
+};
+use self::session::{SessionConfig, Sessions};
+
+/// Title sent with every document, matching the Python server
+const DEFAULT_TITLE: &str = "Bokeh Application";
+
+/// How long a websocket read may block before queued outgoing messages are sent
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a client may take to send the head of its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static NEXT_MSGID: AtomicUsize = AtomicUsize::new(0);
+
+// Page template
+
+#[derive(Template)]
+#[template(path = "server.html")]
+struct AppPageTemplate<'a> {
+    element_id: &'a str,
+    session_id: &'a str,
+    app_path: &'a str,
+    absolute_url: &'a str,
+}
+
+/// Script embedding a session after the script tag which loads it, with values given as JSON
+#[derive(Template)]
+#[template(path = "autoload.js")]
+struct AutoloadTemplate<'a> {
+    element_id: &'a str,
+    session_id: &'a str,
+    app_path: &'a str,
+    absolute_url: &'a str,
+}
+
+/// Render the page showing a session, with `app_path` and `absolute_url` given as JSON
+fn render_app_page(session_id: &str, app_path: &Value, absolute_url: &Value) -> Result<String> {
+    AppPageTemplate {
+        element_id: &session::random_string(),
+        session_id,
+        app_path: &app_path.to_string(),
+        absolute_url: &absolute_url.to_string(),
+    }
+    .render()
+    .map_err(|e| format_err!("rendering page: {}", e))
+}
+
+/// HTML page showing the session `session_id` of the application served at `url`
+///
+/// This lets an application be shown from pages served by another web server, as long as the
+/// browser can reach `url`. Sessions are created when the browser connects, so `session_id` can
+/// be freshly generated with `generate_session_id`.
+pub fn app_html(url: &str, session_id: &str) -> Result<String> {
+    let url = url.trim_end_matches('/');
+    render_app_page(session_id, &Value::Null, &json!(url))
+}
+
+// Protocol messages
+
+/// Message in the Bokeh server protocol
+///
+/// On the wire every message is sent as three text frames: the header, the metadata and the
+/// content.
+#[derive(Clone, Debug, PartialEq)]
+struct Message {
+    header: Value,
+    metadata: Value,
+    content: Value,
+}
+
+impl Message {
+    fn new(msgtype: &str, content: Value) -> Self {
+        let msgid = NEXT_MSGID.fetch_add(1, Ordering::SeqCst);
+        Message {
+            header: json!({
+                "msgid": msgid.to_string(),
+                "msgtype": msgtype,
+            }),
+            metadata: json!({}),
+            content,
+        }
+    }
+
+    fn reply_to(request: &Message, msgtype: &str, content: Value) -> Self {
+        let mut message = Message::new(msgtype, content);
+        message.header["reqid"] = request.header["msgid"].clone();
+        message
+    }
+
+    fn error(request: &Message, text: String) -> Self {
+        Message::reply_to(request, "ERROR", json!({ "text": text, "traceback": null }))
+    }
+
+    fn msgtype(&self) -> &str {
+        self.header["msgtype"].as_str().unwrap_or("")
+    }
+
+    fn from_frames(frames: &[String]) -> Result<Self> {
+        match frames {
+            [header, metadata, content] => Ok(Message {
+                header: serde_json::from_str(header)?,
+                metadata: serde_json::from_str(metadata)?,
+                content: serde_json::from_str(content)?,
+            }),
+            _ => Err(format_err!("expected 3 frames, got {}", frames.len())),
+        }
+    }
+
+    fn write_to<S>(&self, socket: &mut WebSocket<S>) -> Result<()>
+    where
+        S: Read + Write,
+    {
+        for part in &[&self.header, &self.metadata, &self.content] {
+            socket.write(WsMessage::Text(part.to_string()))?;
+        }
+        socket.flush()?;
+        Ok(())
+    }
+}
+
+// Server
+
+type DocumentFactory = dyn Fn(&SessionContext) -> Document + Send + Sync;
+
+struct ServerState {
+    factory: Box<DocumentFactory>,
+    auth_provider: Option<Box<dyn AuthProvider>>,
+    sessions: Sessions,
+    next_client: AtomicUsize,
+}
+
+impl ServerState {
+    fn session(&self, context: SessionContext) -> Result<Arc<Mutex<Session>>> {
+        self.sessions.get_or_create(context, &self.factory)
+    }
+
+    /// The user making `request`, or an error if the auth provider rejects it
+    fn authenticate(&self, request: &Request) -> Result<Option<User>> {
+        match &self.auth_provider {
+            Some(provider) => match provider.authenticate(request) {
+                Some(user) => Ok(Some(user)),
+                None => Err(format_err!(
+                    "request to {} was not authorized",
+                    request.path()
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// HTTP and websocket server hosting a Bokeh application
+pub struct Server {
+    listener: TcpListener,
+    factory: Box<DocumentFactory>,
+    auth_provider: Option<Box<dyn AuthProvider>>,
+    session_config: SessionConfig,
+}
+
+impl Server {
+    /// Bind a new server to `addr`
+    ///
+    /// `factory` is called to build a new document for every session.
+    pub fn bind<A, F>(addr: A, factory: F) -> Result<Server>
+    where
+        A: ToSocketAddrs,
+        F: Fn() -> Document + Send + Sync + 'static,
+    {
+        Server::bind_with_context(addr, move |_: &SessionContext| factory())
+    }
+
+    /// Bind a new server to `addr`, with a factory which is told about the request creating
+    /// each session
+    pub fn bind_with_context<A, F>(addr: A, factory: F) -> Result<Server>
+    where
+        A: ToSocketAddrs,
+        F: Fn(&SessionContext) -> Document + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind(addr)?;
+        Ok(Server {
+            listener,
+            factory: Box::new(factory),
+            auth_provider: None,
+            session_config: SessionConfig::default(),
+        })
+    }
+
+    /// Check every page and websocket request with `provider`
+    pub fn auth_provider<P>(mut self, provider: P) -> Self
+    where
+        P: AuthProvider + 'static,
+    {
+        self.auth_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// How long the session tokens handed out with pages remain valid
+    pub fn session_token_expiration(mut self, expiration: Duration) -> Self {
+        self.session_config.session_token_expiration = expiration;
+        self
+    }
+
+    /// Sign session ids with `secret_key`
+    ///
+    /// Connections using a session id which was not signed with the same key are rejected.
+    pub fn sign_sessions<S>(mut self, secret_key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.session_config.secret_key = Some(secret_key.into());
+        self
+    }
+
+    /// How long a session without any clients is kept before it is discarded
+    pub fn unused_session_lifetime(mut self, lifetime: Duration) -> Self {
+        self.session_config.unused_session_lifetime = lifetime;
+        self
+    }
+
+    /// How often to check for unused sessions
+    pub fn check_unused_sessions(mut self, interval: Duration) -> Self {
+        self.session_config.check_unused_sessions = interval;
+        self
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests forever, handling each connection on its own thread
+    pub fn run(self) -> Result<()> {
+        let state = Arc::new(ServerState {
+            factory: self.factory,
+            auth_provider: self.auth_provider,
+            sessions: Sessions::new(self.session_config),
+            next_client: AtomicUsize::new(0),
+        });
+
+        let cleanup_state = state.clone();
+        thread::spawn(move || loop {
+            thread::sleep(cleanup_state.sessions.config().check_unused_sessions);
+            cleanup_state.sessions.cleanup();
+        });
+
+        for stream in self.listener.incoming() {
+            // Failures such as running out of file descriptors or a client aborting its
+            // connection are transient, so keep accepting
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("error accepting connection: {}", e);
+                    continue;
+                }
+            };
+            let state = state.clone();
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(&state, stream) {
+                    warn!("error handling connection: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn handle_connection(state: &ServerState, stream: TcpStream) -> Result<()> {
+    let request = peek_request(&stream, REQUEST_TIMEOUT)?;
+    match request.path() {
+        "/" => serve_page(state, stream, &request),
+        "/ws" => serve_websocket(state, stream, &request),
+        "/autoload.js" => serve_autoload(state, stream, &request),
+        _ => respond(stream, "404 Not Found", "text/plain", "Not Found"),
+    }
+}
+
+/// Read the request line and headers without consuming them, so that websocket upgrades can be
+/// handed over to the websocket handshake untouched
+///
+/// Fails if the client has not sent the whole head within `timeout`.
+fn peek_request(stream: &TcpStream, timeout: Duration) -> Result<Request> {
+    let deadline = Instant::now() + timeout;
+    stream.set_read_timeout(Some(timeout))?;
+    let mut buf = [0; 8192];
+    loop {
+        let n = stream.peek(&mut buf)?;
+        if n == 0 {
+            return Err(format_err!("connection closed before request was received"));
+        }
+
+        let head = String::from_utf8_lossy(&buf[..n]);
+        if let Some(end) = head.find("\r\n\r\n") {
+            return Request::parse(&head[..end]);
+        }
+
+        if n == buf.len() {
+            return Err(format_err!("request head too long"));
+        }
+        if Instant::now() >= deadline {
+            return Err(format_err!("timed out waiting for request"));
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn respond(stream: TcpStream, status: &str, content_type: &str, body: &str) -> Result<()> {
+    // Drain the request head so the client sees an orderly response
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Create or join the session asked for by a page or autoload request
+///
+/// Returns the token to hand to BokehJS, which passes it back as the session id of its websocket
+/// connection, or the status and body of the response to send instead.
+fn session_token(
+    state: &ServerState,
+    request: &Request,
+) -> std::result::Result<String, (&'static str, String)> {
+    let user = state
+        .authenticate(request)
+        .map_err(|e| ("401 Unauthorized", e.to_string()))?;
+
+    let session_id = match request.argument("bokeh-session-id") {
+        Some(session_id) => session_id.to_string(),
+        None => state.sessions.generate_id(),
+    };
+    let context = SessionContext {
+        session_id: session_id.clone(),
+        user,
+        arguments: request.arguments(),
+        token_payload: Value::Null,
+        document: DocumentHandle::default(),
+    };
+    if state.session(context).is_err() {
+        return Err(("403 Forbidden", "Invalid session id".to_string()));
+    }
+
+    let config = state.sessions.config();
+    Ok(auth::generate_token(
+        &session_id,
+        config.secret_key.as_deref(),
+        config.session_token_expiration,
+        Value::Null,
+    ))
+}
+
+fn serve_page(state: &ServerState, stream: TcpStream, request: &Request) -> Result<()> {
+    let token = match session_token(state, request) {
+        Ok(token) => token,
+        Err((status, body)) => return respond(stream, status, "text/plain", &body),
+    };
+    let page = render_app_page(&token, &json!("/"), &Value::Null)?;
+    respond(stream, "200 OK", "text/html; charset=utf-8", &page)
+}
+
+/// Serve the script used by `embed::server_document` and `embed::server_session`, which loads
+/// BokehJS if needed and shows the session after its script tag
+fn serve_autoload(state: &ServerState, stream: TcpStream, request: &Request) -> Result<()> {
+    let element_id = match request.argument("bokeh-autoload-element") {
+        Some(element_id) => element_id,
+        None => {
+            return respond(
+                stream,
+                "400 Bad Request",
+                "text/plain",
+                "No bokeh-autoload-element query parameter",
+            )
+        }
+    };
+    let token = match session_token(state, request) {
+        Ok(token) => token,
+        Err((status, body)) => return respond(stream, status, "text/plain", &body),
+    };
+
+    let app_path = request.argument("bokeh-app-path").unwrap_or("/");
+    let absolute_url = request.argument("bokeh-absolute-url");
+    let script = AutoloadTemplate {
+        element_id: &json!(element_id).to_string(),
+        session_id: &json!(token).to_string(),
+        app_path: &json!(app_path).to_string(),
+        absolute_url: &json!(absolute_url).to_string(),
+    }
+    .render()
+    .map_err(|e| format_err!("rendering autoload script: {}", e))?;
+    respond(stream, "200 OK", "application/javascript", &script)
+}
+
+/// Context of the session a websocket request asks to join, given either a session token or a
+/// bare session id
+fn websocket_context(state: &ServerState, request: &Request) -> Result<SessionContext> {
+    let user = state.authenticate(request)?;
+    let id = request
+        .argument("bokeh-session-id")
+        .ok_or_else(|| format_err!("no session id given"))?;
+
+    let (session_id, token_payload) = if id.contains('.') {
+        let payload = auth::check_token(id, state.sessions.config().secret_key.as_deref())?;
+        let session_id = payload["session_id"]
+            .as_str()
+            .ok_or_else(|| format_err!("session token has no session id"))?
+            .to_string();
+        (session_id, payload)
+    } else {
+        (id.to_string(), Value::Null)
+    };
+
+    Ok(SessionContext {
+        session_id,
+        user,
+        arguments: request.arguments(),
+        token_payload,
+        document: DocumentHandle::default(),
+    })
+}
+
+fn serve_websocket(state: &ServerState, stream: TcpStream, request: &Request) -> Result<()> {
+    let session = match websocket_context(state, request).and_then(|c| state.session(c)) {
+        Ok(session) => session,
+        Err(e) => {
+            debug!("rejecting websocket: {}", e);
+            return respond(stream, "403 Forbidden", "text/plain", "Invalid session id");
+        }
+    };
+
+    let mut socket =
+        tungstenite::accept(stream).map_err(|e| format_err!("websocket handshake: {}", e))?;
+    socket.get_mut().set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let client = state.next_client.fetch_add(1, Ordering::SeqCst);
+    let (sender, receiver) = channel();
+    let queued = session.lock().unwrap().add_client(client, sender);
+
+    let result = Connection {
+        client,
+        session: &session,
+        socket: &mut socket,
+        outgoing: receiver,
+        queued,
+    }
+    .run();
+
+    session.lock().unwrap().remove_client(client);
+    result
+}
+
+/// A websocket client of a session
+struct Connection<'a> {
+    client: usize,
+    session: &'a Mutex<Session>,
+    socket: &'a mut WebSocket<TcpStream>,
+    outgoing: Receiver<Message>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl<'a> Connection<'a> {
+    fn run(&mut self) -> Result<()> {
+        Message::new("ACK", json!({})).write_to(self.socket)?;
+
+        let mut frames = Vec::new();
+        loop {
+            // Sent on every pass, so that a client which keeps sending is still sent changes
+            self.send_outgoing()?;
+            match self.socket.read() {
+                Ok(WsMessage::Text(text)) => {
+                    frames.push(text);
+                    if frames.len() == 3 {
+                        let message = Message::from_frames(&frames)?;
+                        frames.clear();
+                        self.handle(&message)?;
+                    }
+                }
+                // None of the handled messages carry buffers
+                Ok(WsMessage::Binary(_)) => {}
+                Ok(WsMessage::Close(_)) => return Ok(()),
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(ref e))
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::TimedOut => {}
+                Err(tungstenite::Error::ConnectionClosed)
+                | Err(tungstenite::Error::AlreadyClosed) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Send the messages queued for the client by the session
+    fn send_outgoing(&mut self) -> Result<()> {
+        while let Ok(message) = self.outgoing.try_recv() {
+            message.write_to(self.socket)?;
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, message: &Message) -> Result<()> {
+        debug!("client {} sent {}", self.client, message.msgtype());
+        self.session.lock().unwrap().touch();
+        let reply = match message.msgtype() {
+            "PULL-DOC-REQ" => {
+                let doc = self
+                    .session
+                    .lock()
+                    .unwrap()
+                    .pull_document(self.client, DEFAULT_TITLE);
+                match doc {
+                    Ok(doc) => Message::reply_to(message, "PULL-DOC-REPLY", json!({ "doc": doc })),
+                    Err(e) => Message::error(message, e.to_string()),
+                }
+            }
+            "SERVER-INFO-REQ" => Message::reply_to(
+                message,
+                "SERVER-INFO-REPLY",
+                json!({
+                    "version_info": {
+                        "bokeh": BOKEH_VERSION,
+                        "server": BOKEH_VERSION,
+                    },
+                }),
+            ),
+            "PATCH-DOC" => {
+                self.session
+                    .lock()
+                    .unwrap()
+                    .apply_client_patch(self.client, &message.content);
+                Message::reply_to(message, "OK", json!({}))
+            }
+            "EVENT" => return Ok(()),
+            other => Message::error(message, format!("unhandled message type {}", other)),
+        };
+        reply.write_to(self.socket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, ColumnDataSource, Id, Plot};
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tungstenite::client::IntoClientRequest;
+    use tungstenite::stream::MaybeTlsStream;
+
+    fn start_server() -> SocketAddr {
+        start(|server| server)
+    }
+
+    fn start<F>(configure: F) -> SocketAddr
+    where
+        F: FnOnce(Server) -> Server,
+    {
+        let server = Server::bind("127.0.0.1:0", || {
+            let mut source = ColumnDataSource::new();
+            source.add("x", &[1.0, 2.0, 3.0]);
+
+            let mut plot = Plot::new();
+            plot.add_glyph(&source, Circle::new());
+
+            let mut doc = Document::new();
+            doc.add_root(plot);
+            doc
+        })
+        .unwrap();
+        let server = configure(server);
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+        addr
+    }
+
+    fn connect(addr: SocketAddr, session_id: &str) -> WebSocket<MaybeTlsStream<TcpStream>> {
+        let url = format!(
+            "ws://{}/ws?bokeh-protocol-version=1.0&bokeh-session-id={}",
+            addr, session_id
+        );
+        let (socket, _) = tungstenite::connect(url).unwrap();
+        socket
+    }
+
+    fn receive<S>(socket: &mut WebSocket<S>) -> Message
+    where
+        S: Read + Write,
+    {
+        let mut frames = Vec::new();
+        while frames.len() < 3 {
+            if let WsMessage::Text(text) = socket.read().unwrap() {
+                frames.push(text);
+            }
+        }
+        Message::from_frames(&frames).unwrap()
+    }
+
+    /// Pull the document, so that the client is sent later changes
+    fn pull<S>(socket: &mut WebSocket<S>) -> Message
+    where
+        S: Read + Write,
+    {
+        Message::new("PULL-DOC-REQ", json!({}))
+            .write_to(socket)
+            .unwrap();
+        receive(socket)
+    }
+
+    #[test]
+    fn test_page_creates_session() {
+        let addr = start_server();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\"sessionid\":"));
+        assert!(response.contains("Bokeh.embed.embed_items"));
+    }
+
+    #[test]
+    fn test_idle_connections_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        assert!(peek_request(&stream, Duration::from_millis(10)).is_err());
+
+        // A partial request head isn't waited for forever either
+        write!(client, "GET / HTTP/1.1\r\n").unwrap();
+        assert!(peek_request(&stream, Duration::from_millis(10)).is_err());
+
+        write!(client, "Host: localhost\r\n\r\n").unwrap();
+        let request = peek_request(&stream, Duration::from_millis(100)).unwrap();
+        assert_eq!(request.path(), "/");
+    }
+
+    #[test]
+    fn test_pull_document() {
+        let addr = start_server();
+        let mut socket = connect(addr, "pull-session");
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+
+        let request = Message::new("PULL-DOC-REQ", json!({}));
+        request.write_to(&mut socket).unwrap();
+
+        let reply = receive(&mut socket);
+        assert_eq!(reply.msgtype(), "PULL-DOC-REPLY");
+        assert_eq!(reply.header["reqid"], request.header["msgid"]);
+        assert_eq!(reply.content["doc"]["version"], json!(BOKEH_VERSION));
+        assert_eq!(
+            reply.content["doc"]["roots"]["root_ids"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_patches_are_forwarded_to_other_clients() {
+        let addr = start_server();
+        let mut first = connect(addr, "shared-session");
+        let mut second = connect(addr, "shared-session");
+        assert_eq!(receive(&mut first).msgtype(), "ACK");
+        assert_eq!(receive(&mut second).msgtype(), "ACK");
+        assert_eq!(pull(&mut second).msgtype(), "PULL-DOC-REPLY");
+
+        let events = json!({ "events": [], "references": [] });
+        let patch = Message::new("PATCH-DOC", events.clone());
+        patch.write_to(&mut first).unwrap();
+
+        let reply = receive(&mut first);
+        assert_eq!(reply.msgtype(), "OK");
+        assert_eq!(reply.header["reqid"], patch.header["msgid"]);
+
+        let forwarded = receive(&mut second);
+        assert_eq!(forwarded.msgtype(), "PATCH-DOC");
+        assert_eq!(forwarded.content, events);
+    }
+
+    #[test]
+    fn test_signed_sessions() {
+        let secret_key = generate_secret_key();
+        let addr = start(|server| server.sign_sessions(secret_key.clone()));
+
+        let url = format!("ws://{}/ws?bokeh-session-id=unsigned", addr);
+        assert!(tungstenite::connect(url).is_err());
+
+        let mut socket = connect(addr, &generate_session_id(Some(&secret_key)));
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+    }
+
+    #[test]
+    fn test_callback_changes_are_pushed() {
+        let server = Server::bind("127.0.0.1:0", || {
+            let mut plot = Plot::new();
+            plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+
+            let mut doc = Document::new();
+            doc.add_root(plot);
+            doc.add_periodic_callback(
+                |doc| {
+                    let plot = &mut doc.root_mut().unwrap().plots_mut()[0];
+                    plot.min_border = Some(plot.min_border.unwrap_or(0) + 1);
+                },
+                Duration::from_millis(10),
+            );
+            doc
+        })
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let mut socket = connect(addr, "callback-session");
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+        assert_eq!(pull(&mut socket).msgtype(), "PULL-DOC-REPLY");
+
+        let patch = receive(&mut socket);
+        assert_eq!(patch.msgtype(), "PATCH-DOC");
+        let events = patch.content["events"].as_array().unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e["kind"] == "ModelChanged" && e["attr"] == "min_border"));
+    }
+
+    #[test]
+    fn test_changes_are_pushed_to_busy_clients() {
+        let server = Server::bind("127.0.0.1:0", || {
+            let mut plot = Plot::new();
+            plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+
+            let mut doc = Document::new();
+            doc.add_root(plot);
+            doc.add_periodic_callback(
+                |doc| {
+                    let plot = &mut doc.root_mut().unwrap().plots_mut()[0];
+                    plot.min_border = Some(plot.min_border.unwrap_or(0) + 1);
+                },
+                Duration::from_millis(10),
+            );
+            doc
+        })
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let mut socket = connect(addr, "busy-session");
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+        assert_eq!(pull(&mut socket).msgtype(), "PULL-DOC-REPLY");
+
+        // Keep sending patches more often than the server's poll interval, so that its reads
+        // never time out
+        let patch = json!({ "events": [], "references": [] });
+        let start = std::time::Instant::now();
+        loop {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "no changes pushed"
+            );
+            Message::new("PATCH-DOC", patch.clone())
+                .write_to(&mut socket)
+                .unwrap();
+            let message = receive(&mut socket);
+            if message.msgtype() == "PATCH-DOC" {
+                assert_eq!(message.content["events"][0]["attr"], "min_border");
+                break;
+            }
+            assert_eq!(message.msgtype(), "OK");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_streamed_rows_are_pushed() {
+        let server = Server::bind("127.0.0.1:0", || {
+            let mut source = ColumnDataSource::with_id(Id::from("source"));
+            source.add("x", &[]);
+            let mut plot = Plot::new();
+            plot.add_glyph(&source, Circle::new());
+
+            let mut doc = Document::new();
+            doc.add_root(plot);
+            let mut x = 0.0;
+            doc.add_periodic_callback(
+                move |doc| {
+                    x += 1.0;
+                    let mut data = HashMap::new();
+                    data.insert("x".to_string(), vec![x]);
+                    doc.stream(&Id::from("source"), data, Some(100)).unwrap();
+                },
+                Duration::from_millis(10),
+            );
+            doc
+        })
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let mut socket = connect(addr, "stream-session");
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+        let pulled = pull(&mut socket);
+        let source = pulled.content["doc"]["roots"]["references"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["type"] == "ColumnDataSource")
+            .unwrap()
+            .clone();
+        let mut rows = source["attributes"]["data"]["x"].as_array().unwrap().len();
+
+        let patch = receive(&mut socket);
+        let events = patch.content["events"].as_array().unwrap();
+        assert!(!events.is_empty());
+        for event in events {
+            assert_eq!(event["kind"], "ColumnsStreamed");
+            assert_eq!(event["column_source"]["id"], "source");
+            let streamed = event["data"]["x"].as_array().unwrap();
+            // Rows continue on from the pulled document, without gaps or repeats
+            assert_eq!(streamed[0], json!(rows as f64 + 1.0));
+            rows += streamed.len();
+        }
+    }
+
+    fn get(addr: SocketAddr, target: &str, headers: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n",
+            target, headers
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_auth_provider_and_context() {
+        let server = Server::bind_with_context("127.0.0.1:0", |context: &SessionContext| {
+            let mut plot = Plot::new();
+            plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+            plot.min_border = context.user.as_ref().map(|user| user.name.len() as u32);
+
+            let mut doc = Document::new();
+            doc.add_root(plot);
+            doc
+        })
+        .unwrap()
+        .sign_sessions(generate_secret_key())
+        .auth_provider(|request: &Request| request.cookie("user").map(User::new));
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        assert!(get(addr, "/", "").starts_with("HTTP/1.1 401"));
+
+        let page = get(addr, "/", "Cookie: user=alice\r\n");
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        let start = page.find("\"sessionid\":\"").unwrap() + "\"sessionid\":\"".len();
+        let token = &page[start..start + page[start..].find('"').unwrap()];
+
+        let url = format!("ws://{}/ws?bokeh-session-id={}", addr, token);
+        assert!(tungstenite::connect(url.as_str()).is_err());
+
+        let mut request = url.as_str().into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("Cookie", "user=alice".parse().unwrap());
+        let (mut socket, _) = tungstenite::connect(request).unwrap();
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+
+        let pulled = pull(&mut socket);
+        let plot = pulled.content["doc"]["roots"]["references"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["type"] == "Plot")
+            .unwrap()
+            .clone();
+        assert_eq!(plot["attributes"]["min_border"], json!(5));
+    }
+
+    #[test]
+    fn test_autoload() {
+        let addr = start_server();
+
+        let response = get(addr, "/autoload.js", "");
+        assert!(response.starts_with("HTTP/1.1 400"));
+
+        let response = get(
+            addr,
+            &format!(
+                "/autoload.js?bokeh-autoload-element=1001&bokeh-absolute-url=http://{}",
+                addr
+            ),
+            "",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("Content-Type: application/javascript"));
+        assert!(response.contains(r#"document.getElementById("1001")"#));
+        assert!(response.contains(&format!(r#""/", "http://{}""#, addr)));
+        assert!(response.contains(r#"["bokeh", "bokeh-widgets", "bokeh-tables"]"#));
+        assert!(response.contains(&format!("-{}.min.js", BOKEH_VERSION)));
+
+        let start = response.find(r#""sessionid": ""#).unwrap() + r#""sessionid": ""#.len();
+        let token = &response[start..start + response[start..].find('"').unwrap()];
+        let mut socket = connect(addr, token);
+        assert_eq!(receive(&mut socket).msgtype(), "ACK");
+    }
+}
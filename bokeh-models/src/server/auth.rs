@@ -0,0 +1,280 @@
+//! Authentication of incoming requests, and signed session tokens
+//!
+//! Every page and websocket request can be checked by an `AuthProvider` before a session is
+//! created or joined. Pages then hand BokehJS a session token in place of the bare session id.
+//! The token carries the session id and an expiry time, signed with the server's secret key if
+//! it has one, so that a websocket can only join the session it was issued for and only for a
+//! limited time.
+
+use super::session::signer;
+use crate::Result;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use failure::format_err;
+use hmac::Mac;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// HTTP request made to the server, either for a page or a websocket upgrade
+#[derive(Clone, Debug)]
+pub struct Request {
+    method: String,
+    path: String,
+    /// Query string arguments, percent-decoded
+    arguments: Vec<(String, String)>,
+    headers: HashMap<String, String>,
+}
+
+impl Request {
+    /// Parse the request line and headers of an HTTP request
+    pub(super) fn parse(head: &str) -> Result<Request> {
+        let mut lines = head.lines();
+        let request_line = lines.next().unwrap_or_default();
+        let mut parts = request_line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method, target),
+            _ => return Err(format_err!("invalid request line `{}`", request_line)),
+        };
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (target, ""),
+        };
+
+        let headers = lines
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+
+        Ok(Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            arguments: form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect(),
+            headers,
+        })
+    }
+
+    /// HTTP method, e.g. `GET`
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Path of the request, without the query string
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Value of the query string argument `name`
+    pub fn argument(&self, name: &str) -> Option<&str> {
+        self.arguments
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every query string argument
+    pub fn arguments(&self) -> HashMap<String, String> {
+        self.arguments.iter().cloned().collect()
+    }
+
+    /// Value of the header `name`, which is case insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Value of the cookie `name`
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.header("cookie")?
+            .split(';')
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// User making a request, as identified by an `AuthProvider`
+#[derive(Clone, Debug, PartialEq)]
+pub struct User {
+    /// Name of the user
+    pub name: String,
+    /// Any further details about the user needed by the application
+    pub data: Value,
+}
+
+impl User {
+    /// Create a new user with no further details
+    pub fn new<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        User {
+            name: name.into(),
+            data: Value::Null,
+        }
+    }
+}
+
+/// Decides which requests may use the server
+///
+/// Closures taking a `&Request` and returning an `Option<User>` are providers.
+pub trait AuthProvider: Send + Sync {
+    /// Identify the user making `request`, or return `None` to reject it
+    fn authenticate(&self, request: &Request) -> Option<User>;
+}
+
+impl<F> AuthProvider for F
+where
+    F: Fn(&Request) -> Option<User> + Send + Sync,
+{
+    fn authenticate(&self, request: &Request) -> Option<User> {
+        self(request)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Generate a token allowing a client to join the session `session_id` until `expiration` has
+/// passed
+///
+/// `extra_payload`, if it is an object, is included in the token for the application to read
+/// back from the `SessionContext`. Tokens are only signed if a `secret_key` is given.
+pub fn generate_token(
+    session_id: &str,
+    secret_key: Option<&str>,
+    expiration: Duration,
+    extra_payload: Value,
+) -> String {
+    let mut payload = json!({
+        "session_expiry": now() + expiration.as_secs(),
+        "session_id": session_id,
+    });
+    if let Value::Object(extra) = extra_payload {
+        for (key, value) in extra {
+            payload[key.as_str()] = value;
+        }
+    }
+
+    let payload = URL_SAFE_NO_PAD.encode(payload.to_string());
+    let signature = match secret_key {
+        Some(secret_key) => {
+            URL_SAFE_NO_PAD.encode(signer(secret_key, &payload).finalize().into_bytes())
+        }
+        None => String::new(),
+    };
+    format!("{}.{}", payload, signature)
+}
+
+/// Check a token was signed with `secret_key`, if one is given
+pub fn check_token_signature(token: &str, secret_key: Option<&str>) -> bool {
+    let (payload, signature) = match token.split_once('.') {
+        Some(pieces) => pieces,
+        None => return false,
+    };
+    match secret_key {
+        Some(secret_key) => match URL_SAFE_NO_PAD.decode(signature) {
+            Ok(signature) => signer(secret_key, payload).verify_slice(&signature).is_ok(),
+            Err(_) => false,
+        },
+        None => true,
+    }
+}
+
+/// Payload of a token, without checking its signature
+pub fn token_payload(token: &str) -> Result<Value> {
+    let payload = token
+        .split('.')
+        .next()
+        .ok_or_else(|| format_err!("invalid session token"))?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|e| format_err!("invalid session token: {}", e))?;
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+/// Payload of a token which is correctly signed and has not expired
+pub(super) fn check_token(token: &str, secret_key: Option<&str>) -> Result<Value> {
+    if !check_token_signature(token, secret_key) {
+        return Err(format_err!("invalid session token signature"));
+    }
+    let payload = token_payload(token)?;
+    match payload["session_expiry"].as_u64() {
+        Some(expiry) if expiry >= now() => Ok(payload),
+        _ => Err(format_err!("session token has expired")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let request = Request::parse(
+            "GET /ws?bokeh-session-id=abc&x=1 HTTP/1.1\r\n\
+             Host: localhost\r\n\
+             Cookie: user=alice; theme=dark\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.method(), "GET");
+        assert_eq!(request.path(), "/ws");
+        assert_eq!(request.argument("bokeh-session-id"), Some("abc"));
+        assert_eq!(request.arguments()["x"], "1");
+        assert_eq!(request.header("HOST"), Some("localhost"));
+        assert_eq!(request.cookie("theme"), Some("dark"));
+        assert_eq!(request.cookie("missing"), None);
+
+        let request = Request::parse(
+            "GET /autoload.js?bokeh-absolute-url=http%3A%2F%2Flocalhost%3A5006%2Fapp&a+b=c%26d \
+             HTTP/1.1\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            request.argument("bokeh-absolute-url"),
+            Some("http://localhost:5006/app")
+        );
+        assert_eq!(request.arguments()["a b"], "c&d");
+    }
+
+    #[test]
+    fn test_tokens() {
+        let token = generate_token(
+            "session",
+            Some("key"),
+            Duration::from_secs(60),
+            json!({"role": "admin"}),
+        );
+        let payload = check_token(&token, Some("key")).unwrap();
+        assert_eq!(payload["session_id"], "session");
+        assert_eq!(payload["role"], "admin");
+
+        assert!(check_token(&token, Some("another key")).is_err());
+
+        let unsigned = generate_token("session", None, Duration::from_secs(60), Value::Null);
+        assert!(check_token(&unsigned, None).is_ok());
+        assert!(check_token(&unsigned, Some("key")).is_err());
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let mut payload = token_payload(&generate_token(
+            "session",
+            None,
+            Duration::from_secs(0),
+            Value::Null,
+        ))
+        .unwrap();
+        payload["session_expiry"] = json!(now() - 1);
+        let token = format!("{}.", URL_SAFE_NO_PAD.encode(payload.to_string()));
+        assert!(check_token(&token, None).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! Running the periodic, timeout and next tick callbacks of session documents
+
+use super::session::Session;
+use crate::{CallbackId, CallbackSchedule};
+use std::collections::HashMap;
+use std::sync::{Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often changes held back for slow clients are retried
+const HELD_BACK_RETRY: Duration = Duration::from_millis(20);
+
+/// Run the callbacks of a session's document until the session is discarded
+///
+/// Between passes the scheduler waits until the next callback is due, or, when the document has
+/// no callbacks, until one is added.
+pub(super) fn spawn_scheduler(session: Weak<Mutex<Session>>) {
+    thread::spawn(move || {
+        let mut due = HashMap::new();
+        while let Some(session) = session.upgrade() {
+            let (next, signal) = {
+                let mut session = session.lock().unwrap();
+                let next = run_due_callbacks(&mut session, &mut due);
+                (next, session.document().callback_signal().clone())
+            };
+            drop(session);
+
+            signal.wait(next);
+        }
+    });
+}
+
+/// When a periodic callback which was due at `due_at` next runs, given that it finished at `now`
+///
+/// Like Tornado's `PeriodicCallback`, runs which were missed because the callback or the session
+/// was busy are skipped rather than made up back to back.
+fn next_run(due_at: Instant, period: Duration, now: Instant) -> Instant {
+    let next = due_at + period;
+    if next > now || period == Duration::from_secs(0) {
+        return next.max(now);
+    }
+    let missed = (now - next).as_nanos() / period.as_nanos() + 1;
+    next + period * missed as u32
+}
+
+/// Run every callback which is due, and return when the scheduler next has work to do
+///
+/// Callbacks are scheduled from the first time they are seen, so a callback added by another
+/// callback is timed from the next pass. Returns `None` when there is nothing to do until a
+/// callback is added.
+fn run_due_callbacks(
+    session: &mut Session,
+    due: &mut HashMap<CallbackId, Instant>,
+) -> Option<Instant> {
+    let now = Instant::now();
+    let callbacks = session.document().callbacks().to_vec();
+    due.retain(|id, _| callbacks.iter().any(|c| c.id() == *id));
+
+    let mut changed = false;
+    for callback in callbacks {
+        let due_at = *due
+            .entry(callback.id())
+            .or_insert_with(|| match callback.schedule() {
+                CallbackSchedule::Periodic(delay) | CallbackSchedule::Timeout(delay) => now + delay,
+                CallbackSchedule::NextTick => now,
+            });
+        if due_at > now {
+            continue;
+        }
+
+        callback.run(session.document_mut());
+        changed = true;
+
+        match callback.schedule() {
+            CallbackSchedule::Periodic(period) => {
+                due.insert(callback.id(), next_run(due_at, period, Instant::now()));
+            }
+            CallbackSchedule::Timeout(_) | CallbackSchedule::NextTick => {
+                session.document_mut().remove_callback(callback.id());
+                due.remove(&callback.id());
+            }
+        }
+    }
+
+    if changed || session.has_held_back_changes() {
+        session.push_changes();
+    }
+
+    let next = due.values().min().cloned();
+    if session.has_held_back_changes() {
+        let retry = Instant::now() + HELD_BACK_RETRY;
+        return Some(next.map_or(retry, |next| next.min(retry)));
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::session::SessionContext;
+    use crate::{Circle, ColumnDataSource, Document, Plot};
+
+    #[test]
+    fn test_next_run() {
+        let start = Instant::now();
+        let period = Duration::from_millis(10);
+        assert_eq!(next_run(start, period, start), start + period);
+        assert_eq!(
+            next_run(start, period, start + Duration::from_millis(25)),
+            start + Duration::from_millis(30)
+        );
+        assert_eq!(
+            next_run(start, period, start + Duration::from_millis(30)),
+            start + Duration::from_millis(40)
+        );
+    }
+
+    #[test]
+    fn test_slow_periodic_callback() {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+        let mut document = Document::new();
+        document.add_root(plot);
+        document.add_periodic_callback(
+            |_| thread::sleep(Duration::from_millis(30)),
+            Duration::from_millis(10),
+        );
+        let mut session = Session::new(SessionContext::new("session"), document);
+
+        let mut due = HashMap::new();
+        run_due_callbacks(&mut session, &mut due);
+        thread::sleep(Duration::from_millis(10));
+        let next = run_due_callbacks(&mut session, &mut due).unwrap();
+
+        // The callback takes longer than its period, but isn't due again straight away
+        assert!(next > Instant::now());
+    }
+}
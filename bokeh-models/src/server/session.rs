@@ -0,0 +1,892 @@
+//! Server sessions: session ids, per-session documents and expiry of unused sessions
+
+use super::auth::User;
+use super::{callbacks, Message};
+use crate::events::{self, ChangeTracker, DocumentEvent, Patch};
+use crate::{to_bokeh_json, Document, Result, ValidatedDocument};
+use failure::format_err;
+use hmac::{Hmac, Mac};
+use log::{debug, warn};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+/// Length of generated session ids and secret keys, matching the Python server
+const RANDOM_STRING_LENGTH: usize = 44;
+
+pub(super) fn random_string() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RANDOM_STRING_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+pub(super) fn signer(secret_key: &str, base_id: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(base_id.as_bytes());
+    mac
+}
+
+/// Generate a random secret key suitable for signing session ids
+pub fn generate_secret_key() -> String {
+    random_string()
+}
+
+/// Generate a new session id
+///
+/// If a `secret_key` is given the id is signed with it, in the same format as the Python server
+/// so that ids can be shared between the two.
+pub fn generate_session_id(secret_key: Option<&str>) -> String {
+    let base_id = random_string();
+    match secret_key {
+        Some(secret_key) => {
+            let signature = signer(secret_key, &base_id).finalize().into_bytes();
+            format!("{}-{}", base_id, URL_SAFE_NO_PAD.encode(signature))
+        }
+        None => base_id,
+    }
+}
+
+/// Check a session id is well formed, and correctly signed if a `secret_key` is given
+pub fn check_session_id(session_id: &str, secret_key: Option<&str>) -> bool {
+    match secret_key {
+        Some(secret_key) => {
+            let (base_id, signature) = match session_id.split_once('-') {
+                Some(pieces) => pieces,
+                None => return false,
+            };
+            match URL_SAFE_NO_PAD.decode(signature) {
+                Ok(signature) => signer(secret_key, base_id).verify_slice(&signature).is_ok(),
+                Err(_) => false,
+            }
+        }
+        None => {
+            !session_id.is_empty()
+                && session_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        }
+    }
+}
+
+/// Details of the request which created a session, for use by the document factory
+#[derive(Clone, Debug, Default)]
+pub struct SessionContext {
+    /// Id of the session
+    pub session_id: String,
+    /// User identified by the server's `AuthProvider`, if it has one
+    pub user: Option<User>,
+    /// Query string arguments of the request
+    pub arguments: HashMap<String, String>,
+    /// Payload of the session token used to connect, or null if a bare session id was used
+    pub token_payload: Value,
+    /// Handle for changing the document of the session from other threads
+    pub document: DocumentHandle,
+}
+
+impl SessionContext {
+    /// Create a context for a session with no further details
+    pub fn new<S>(session_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        SessionContext {
+            session_id: session_id.into(),
+            ..SessionContext::default()
+        }
+    }
+}
+
+/// Handle through which the document of a session can be changed from any thread
+///
+/// This is the counterpart of Python's `without_document_lock`: slow work, such as fetching new
+/// data, can be done on another thread without holding up the session, and the document is only
+/// locked while the results are applied with `with_document`.
+#[derive(Clone, Default)]
+pub struct DocumentHandle {
+    session: Arc<OnceLock<Weak<Mutex<Session>>>>,
+}
+
+impl DocumentHandle {
+    /// Lock the document and run `f` against it
+    ///
+    /// The changes made by `f` are sent to the clients before the lock is released, so every
+    /// client sees changes in the order they were made, whichever thread made them. Fails if the
+    /// session has been discarded.
+    pub fn with_document<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Document) -> R,
+    {
+        let session = self
+            .session
+            .get()
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| format_err!("session has been discarded"))?;
+        let mut session = session.lock().unwrap();
+        let result = f(session.document_mut());
+        session.push_changes();
+        Ok(result)
+    }
+}
+
+impl fmt::Debug for DocumentHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DocumentHandle").finish()
+    }
+}
+
+/// Attribute value set by a client, which the server's model knows nothing about
+struct ClientChange {
+    value: Value,
+    /// The server's value when the change was made. Once the server's value differs from this
+    /// it has been changed on the server, and the server's change takes precedence.
+    base: Value,
+}
+
+/// Number of messages a client may have waiting before stream and patch events are held back
+const MAX_QUEUED_MESSAGES: usize = 8;
+
+/// Websocket client of a session
+struct Client {
+    sender: Sender<Message>,
+    /// Messages sent to the client which it has not yet written to its websocket
+    queued: Arc<AtomicUsize>,
+    /// Whether the client has pulled the document, and so should be sent changes to it
+    subscribed: bool,
+}
+
+/// A document and the websocket clients currently viewing it
+pub struct Session {
+    context: SessionContext,
+    document: Document,
+    clients: HashMap<usize, Client>,
+    last_active: Instant,
+    tracker: ChangeTracker,
+    held_back: bool,
+    client_changes: HashMap<(String, String), ClientChange>,
+}
+
+impl Session {
+    pub(super) fn new(context: SessionContext, document: Document) -> Self {
+        let tracker = match document.clone().validate() {
+            Ok(doc) => ChangeTracker::from_document(&doc),
+            Err(_) => ChangeTracker::new(),
+        };
+        Session {
+            context,
+            document,
+            clients: HashMap::new(),
+            last_active: Instant::now(),
+            tracker,
+            held_back: false,
+            client_changes: HashMap::new(),
+        }
+    }
+
+    /// Id of the session
+    pub fn id(&self) -> &str {
+        &self.context.session_id
+    }
+
+    /// Details of the request which created the session
+    pub fn context(&self) -> &SessionContext {
+        &self.context
+    }
+
+    /// The document of the session
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Mutable access to the document of the session
+    pub fn document_mut(&mut self) -> &mut Document {
+        &mut self.document
+    }
+
+    /// Number of websocket clients connected to the session
+    pub fn connection_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Time since a client last connected, disconnected or sent a message
+    pub fn idle_time(&self) -> Duration {
+        self.last_active.elapsed()
+    }
+
+    pub(super) fn touch(&mut self) {
+        self.last_active = Instant::now();
+    }
+
+    /// Add a client, returning the count of messages queued for it
+    ///
+    /// The client must decrement the count as it sends each message on.
+    pub(super) fn add_client(
+        &mut self,
+        client: usize,
+        sender: Sender<Message>,
+    ) -> Arc<AtomicUsize> {
+        let queued = Arc::new(AtomicUsize::new(0));
+        self.clients.insert(
+            client,
+            Client {
+                sender,
+                queued: queued.clone(),
+                subscribed: false,
+            },
+        );
+        self.touch();
+        queued
+    }
+
+    pub(super) fn remove_client(&mut self, client: usize) {
+        self.clients.remove(&client);
+        self.touch();
+    }
+
+    /// Send a message to every subscribed client of the session, except `from` if given
+    pub(super) fn broadcast(&mut self, from: Option<usize>, message: &Message) {
+        // Clients which have gone away have dropped their receiver
+        self.clients.retain(|&id, client| {
+            if Some(id) == from || !client.subscribed {
+                return true;
+            }
+            client.queued.fetch_add(1, Ordering::SeqCst);
+            client.sender.send(message.clone()).is_ok()
+        });
+    }
+
+    /// Serialize the document for `client`, which is then sent all later changes
+    ///
+    /// Pending changes are sent to the other clients first, so that none of them are sent to
+    /// `client` twice.
+    pub(super) fn pull_document(&mut self, client: usize, title: &str) -> Result<Value> {
+        self.send_changes();
+        let validated = self.document.clone().validate()?;
+        let mut doc = to_bokeh_json(&validated, title)?;
+        doc["roots"]["references"] = json!(self.references(&validated));
+        if let Some(client) = self.clients.get_mut(&client) {
+            client.subscribed = true;
+        }
+        Ok(doc)
+    }
+
+    /// Send the changes made to the document since they were last sent to every client
+    ///
+    /// While any client is falling behind, the changes are held back. Rows streamed in the
+    /// meantime are combined, so slow clients catch up with fewer, larger messages.
+    pub(super) fn push_changes(&mut self) {
+        let congested = self.clients.values().any(|client| {
+            client.subscribed && client.queued.load(Ordering::SeqCst) >= MAX_QUEUED_MESSAGES
+        });
+        if congested {
+            debug!("holding back changes of session {}", self.id());
+        } else {
+            self.send_changes();
+        }
+        self.held_back = congested;
+    }
+
+    /// Apply a PATCH-DOC sent by `client`, and forward it to the other clients
+    ///
+    /// The server's models cannot be updated from JSON, so changed attributes are remembered and
+    /// applied whenever the document is serialized, until the server changes them itself. That
+    /// way clients which pull the document later see the same state as the others, and the
+    /// server does not send the old values back. Rows streamed or patched into a data source are
+    /// remembered as a change to its whole `data`.
+    pub(super) fn apply_client_patch(&mut self, client: usize, content: &Value) {
+        // Anything changed on the server so far happened first
+        self.send_changes();
+
+        if let Ok(doc) = self.document.clone().validate() {
+            let server_references = doc.references();
+            let mut references = self.references(&doc);
+            for event in content["events"].as_array().into_iter().flatten() {
+                let (model, attr, value) = match event["kind"].as_str() {
+                    Some("ModelChanged") => (
+                        &event["model"],
+                        event["attr"].as_str().unwrap_or_default(),
+                        event["new"].clone(),
+                    ),
+                    _ => {
+                        let source = &event["column_source"];
+                        let mut data = match references.iter().find(|r| r["id"] == source["id"]) {
+                            Some(model) => model["attributes"]["data"].clone(),
+                            None => continue,
+                        };
+                        if !events::apply_column_event(&mut data, event) {
+                            continue;
+                        }
+                        (source, "data", data)
+                    }
+                };
+                let base = match server_references.iter().find(|r| r["id"] == model["id"]) {
+                    Some(model) => model["attributes"][attr].clone(),
+                    None => continue,
+                };
+                if let Some(current) = references.iter_mut().find(|r| r["id"] == model["id"]) {
+                    current["attributes"][attr] = value.clone();
+                }
+
+                self.tracker.apply(&DocumentEvent::ModelChanged {
+                    model: model.clone(),
+                    attr: attr.to_string(),
+                    new: value.clone(),
+                });
+                self.client_changes.insert(
+                    (model["id"].to_string(), attr.to_string()),
+                    ClientChange { value, base },
+                );
+            }
+        }
+
+        self.broadcast(Some(client), &Message::new("PATCH-DOC", content.clone()));
+    }
+
+    /// Serialized models of `doc`, with the changes made by clients applied
+    fn references(&mut self, doc: &ValidatedDocument) -> Vec<Value> {
+        let mut references = doc.references();
+        let index: HashMap<String, usize> = references
+            .iter()
+            .enumerate()
+            .map(|(i, model)| (model["id"].to_string(), i))
+            .collect();
+
+        self.client_changes.retain(|(id, attr), change| {
+            let model = match index.get(id) {
+                Some(&i) => &mut references[i],
+                None => return false,
+            };
+            let current = model["attributes"].get(attr.as_str()).cloned();
+            if current.unwrap_or(Value::Null) != change.base {
+                return false;
+            }
+            model["attributes"][attr.as_str()] = change.value.clone();
+            true
+        });
+        references
+    }
+
+    /// Whether the last changes were held back, and still need to be pushed
+    pub(super) fn has_held_back_changes(&self) -> bool {
+        self.held_back
+    }
+
+    fn send_changes(&mut self) {
+        let doc = match self.document.clone().validate() {
+            Ok(doc) => doc,
+            Err(e) => {
+                warn!("cannot send changes of session {}: {}", self.id(), e);
+                return;
+            }
+        };
+
+        let mut patch = Patch::default();
+        for event in self.document.take_events().events {
+            if self.tracker.apply(&event) {
+                patch.events.push(event);
+            }
+        }
+        let references = self.references(&doc);
+        let changes = self.tracker.changes_in(references, doc.root_ids());
+        patch.events.extend(changes.events);
+        patch.references = changes.references;
+
+        if !patch.is_empty() {
+            self.broadcast(None, &Message::new("PATCH-DOC", patch.to_json()));
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Let the callback scheduler notice the session is gone
+        self.document.callback_signal().close();
+    }
+}
+
+/// Configuration of session ids and session expiry
+#[derive(Clone, Debug)]
+pub(super) struct SessionConfig {
+    pub(super) secret_key: Option<String>,
+    pub(super) unused_session_lifetime: Duration,
+    pub(super) check_unused_sessions: Duration,
+    pub(super) session_token_expiration: Duration,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        // Same defaults as `bokeh serve`
+        SessionConfig {
+            secret_key: None,
+            unused_session_lifetime: Duration::from_millis(15000),
+            check_unused_sessions: Duration::from_millis(17000),
+            session_token_expiration: Duration::from_secs(300),
+        }
+    }
+}
+
+/// All of the live sessions of a server
+pub(super) struct Sessions {
+    config: SessionConfig,
+    sessions: Mutex<HashMap<String, Arc<Mutex<Session>>>>,
+}
+
+impl Sessions {
+    pub(super) fn new(config: SessionConfig) -> Self {
+        Sessions {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(super) fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    pub(super) fn generate_id(&self) -> String {
+        generate_session_id(self.config.secret_key.as_deref())
+    }
+
+    pub(super) fn check_id(&self, session_id: &str) -> bool {
+        check_session_id(session_id, self.config.secret_key.as_deref())
+    }
+
+    /// Look up the session of `context`, creating it with the document from `factory` if it does
+    /// not exist
+    pub(super) fn get_or_create<F>(
+        &self,
+        context: SessionContext,
+        factory: F,
+    ) -> Result<Arc<Mutex<Session>>>
+    where
+        F: FnOnce(&SessionContext) -> Document,
+    {
+        let session_id = context.session_id.clone();
+        if !self.check_id(&session_id) {
+            return Err(format_err!("invalid session id `{}`", session_id));
+        }
+
+        if let Some(session) = self.sessions.lock().unwrap().get(&session_id) {
+            return Ok(session.clone());
+        }
+
+        // The factory runs user code, so build the document without holding the sessions lock
+        debug!("creating session {}", session_id);
+        let document = factory(&context);
+
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.get(&session_id) {
+            debug!("session {} was created concurrently", session_id);
+            return Ok(session.clone());
+        }
+        let handle = context.document.clone();
+        let session = Arc::new(Mutex::new(Session::new(context, document)));
+        let _ = handle.session.set(Arc::downgrade(&session));
+        callbacks::spawn_scheduler(Arc::downgrade(&session));
+        sessions.insert(session_id, session.clone());
+        Ok(session)
+    }
+
+    /// Discard sessions which have had no clients for longer than the unused session lifetime
+    pub(super) fn cleanup(&self) {
+        let lifetime = self.config.unused_session_lifetime;
+        self.sessions.lock().unwrap().retain(|id, session| {
+            let session = session.lock().unwrap();
+            let keep = session.connection_count() > 0 || session.idle_time() < lifetime;
+            if !keep {
+                debug!("discarding unused session {}", id);
+            }
+            keep
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_unsigned_session_ids() {
+        let session_id = generate_session_id(None);
+        assert_eq!(session_id.len(), RANDOM_STRING_LENGTH);
+        assert!(check_session_id(&session_id, None));
+        assert!(!check_session_id("", None));
+        assert!(!check_session_id("not/valid", None));
+    }
+
+    #[test]
+    fn test_signed_session_ids() {
+        let secret_key = generate_secret_key();
+        let session_id = generate_session_id(Some(&secret_key));
+
+        assert!(check_session_id(&session_id, Some(&secret_key)));
+        assert!(!check_session_id(&session_id, Some("another key")));
+        assert!(!check_session_id(&random_string(), Some(&secret_key)));
+
+        let forged = format!(
+            "{}-{}",
+            random_string(),
+            session_id.split_once('-').unwrap().1
+        );
+        assert!(!check_session_id(&forged, Some(&secret_key)));
+    }
+
+    #[test]
+    fn test_cleanup_unused_sessions() {
+        let sessions = Sessions::new(SessionConfig {
+            unused_session_lifetime: Duration::from_millis(10),
+            ..SessionConfig::default()
+        });
+
+        let unused = sessions.generate_id();
+        sessions
+            .get_or_create(SessionContext::new(unused), |_| Document::new())
+            .unwrap();
+
+        let used = sessions.generate_id();
+        let (sender, _receiver) = std::sync::mpsc::channel();
+        sessions
+            .get_or_create(SessionContext::new(used.clone()), |_| Document::new())
+            .unwrap()
+            .lock()
+            .unwrap()
+            .add_client(0, sender);
+
+        thread::sleep(Duration::from_millis(20));
+        sessions.cleanup();
+        let remaining = sessions.sessions.lock().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(remaining.contains_key(&used));
+    }
+
+    #[test]
+    fn test_factory_runs_without_lock() {
+        let sessions = Arc::new(Sessions::new(SessionConfig::default()));
+        let existing = sessions.generate_id();
+        sessions
+            .get_or_create(SessionContext::new(existing.clone()), |_| Document::new())
+            .unwrap();
+
+        let (started, wait_started) = std::sync::mpsc::channel();
+        let (release, wait_release) = std::sync::mpsc::channel::<()>();
+        let slow = sessions.generate_id();
+        let worker_sessions = sessions.clone();
+        let worker = thread::spawn(move || {
+            worker_sessions
+                .get_or_create(SessionContext::new(slow), |_| {
+                    started.send(()).unwrap();
+                    wait_release.recv().unwrap();
+                    Document::new()
+                })
+                .unwrap()
+        });
+
+        wait_started.recv().unwrap();
+        let session = sessions
+            .get_or_create(SessionContext::new(existing.clone()), |_| unreachable!())
+            .unwrap();
+        assert_eq!(session.lock().unwrap().id(), existing);
+        sessions.cleanup();
+
+        release.send(()).unwrap();
+        worker.join().unwrap();
+        assert_eq!(sessions.sessions.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_creation_keeps_first_session() {
+        let sessions = Sessions::new(SessionConfig::default());
+        let session_id = sessions.generate_id();
+        let first = sessions
+            .get_or_create(SessionContext::new(session_id.clone()), |_| Document::new())
+            .unwrap();
+
+        // Simulate losing the race: the factory runs, but another thread has inserted meanwhile
+        let context = SessionContext::new(session_id.clone());
+        let document = Document::new();
+        let second = {
+            let mut guard = sessions.sessions.lock().unwrap();
+            let existing = guard.remove(&session_id).unwrap();
+            drop(guard);
+            sessions
+                .get_or_create(context, |_| {
+                    sessions
+                        .sessions
+                        .lock()
+                        .unwrap()
+                        .insert(session_id.clone(), existing);
+                    document
+                })
+                .unwrap()
+        };
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_rejects_invalid_ids() {
+        let sessions = Sessions::new(SessionConfig {
+            secret_key: Some(generate_secret_key()),
+            ..SessionConfig::default()
+        });
+        assert!(sessions
+            .get_or_create(SessionContext::new("unsigned"), |_| Document::new())
+            .is_err());
+
+        let session_id = sessions.generate_id();
+        let session = sessions
+            .get_or_create(SessionContext::new(session_id.clone()), |_| Document::new())
+            .unwrap();
+        assert_eq!(session.lock().unwrap().id(), session_id);
+    }
+
+    #[test]
+    fn test_push_changes_sends_deltas() {
+        let mut plot = crate::Plot::new();
+        plot.add_glyph(&crate::ColumnDataSource::new(), crate::Circle::new());
+        let mut document = Document::new();
+        document.add_root(plot);
+
+        let mut session = Session::new(SessionContext::new(generate_session_id(None)), document);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        session.add_client(0, sender);
+        session.pull_document(0, "Test").unwrap();
+
+        session.push_changes();
+        assert!(receiver.try_recv().is_err());
+
+        session.document_mut().root_mut().unwrap().plots_mut()[0].min_border = Some(10);
+        session.push_changes();
+        let message = receiver.try_recv().unwrap();
+        assert_eq!(message.msgtype(), "PATCH-DOC");
+        assert_eq!(message.content["events"].as_array().unwrap().len(), 1);
+        assert_eq!(message.content["events"][0]["attr"], "min_border");
+        assert_eq!(message.content["references"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_changes_are_held_back_for_slow_clients() {
+        let mut source = crate::ColumnDataSource::with_id(crate::Id::from("source"));
+        source.add("x", &[]);
+        let mut plot = crate::Plot::new();
+        plot.add_glyph(&source, crate::Circle::new());
+        let mut document = Document::new();
+        document.add_root(plot);
+
+        let mut session = Session::new(SessionContext::new(generate_session_id(None)), document);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let queued = session.add_client(0, sender);
+        session.pull_document(0, "Test").unwrap();
+        queued.store(MAX_QUEUED_MESSAGES, Ordering::SeqCst);
+
+        for x in 0..3 {
+            let mut data = HashMap::new();
+            data.insert("x".to_string(), vec![f64::from(x)]);
+            session
+                .document_mut()
+                .stream(&crate::Id::from("source"), data, None)
+                .unwrap();
+            session.push_changes();
+        }
+        assert!(receiver.try_recv().is_err());
+        assert!(session.has_held_back_changes());
+
+        queued.store(0, Ordering::SeqCst);
+        session.push_changes();
+        let message = receiver.try_recv().unwrap();
+        assert_eq!(
+            message.content["events"],
+            serde_json::json!([{
+                "column_source": {"id": "source", "type": "ColumnDataSource"},
+                "data": {"x": [0.0, 1.0, 2.0]},
+                "kind": "ColumnsStreamed",
+                "rollover": null,
+            }])
+        );
+    }
+
+    fn plot_document(plot_id: &str) -> Document {
+        let mut plot = crate::Plot::with_id(crate::Id::from(plot_id));
+        plot.add_glyph(&crate::ColumnDataSource::new(), crate::Circle::new());
+        let mut document = Document::new();
+        document.add_root(plot);
+        document
+    }
+
+    fn min_border(doc: &Value) -> Value {
+        doc["roots"]["references"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["type"] == "Plot")
+            .unwrap()["attributes"]["min_border"]
+            .clone()
+    }
+
+    #[test]
+    fn test_client_changes_are_kept() {
+        let mut session = Session::new(
+            SessionContext::new(generate_session_id(None)),
+            plot_document("plot"),
+        );
+        let (first, first_receiver) = std::sync::mpsc::channel();
+        let (second, second_receiver) = std::sync::mpsc::channel();
+        session.add_client(0, first);
+        session.add_client(1, second);
+        session.pull_document(0, "Test").unwrap();
+        session.pull_document(1, "Test").unwrap();
+
+        let content = json!({
+            "events": [{
+                "attr": "min_border",
+                "kind": "ModelChanged",
+                "model": {"id": "plot", "type": "Plot"},
+                "new": 20,
+            }],
+            "references": [],
+        });
+        session.apply_client_patch(0, &content);
+        assert!(first_receiver.try_recv().is_err());
+        assert_eq!(second_receiver.try_recv().unwrap().content, content);
+
+        // The server does not undo the change, and later clients see it
+        session.push_changes();
+        assert!(second_receiver.try_recv().is_err());
+        let (third, _third_receiver) = std::sync::mpsc::channel();
+        session.add_client(2, third);
+        assert_eq!(
+            min_border(&session.pull_document(2, "Test").unwrap()),
+            json!(20)
+        );
+
+        // Until the server changes the attribute itself
+        session.document_mut().root_mut().unwrap().plots_mut()[0].min_border = Some(30);
+        session.push_changes();
+        let message = first_receiver.try_recv().unwrap();
+        assert_eq!(message.content["events"][0]["new"], json!(30));
+        let (fourth, _fourth_receiver) = std::sync::mpsc::channel();
+        session.add_client(3, fourth);
+        assert_eq!(
+            min_border(&session.pull_document(3, "Test").unwrap()),
+            json!(30)
+        );
+    }
+
+    #[test]
+    fn test_client_data_changes_are_kept() {
+        let mut source = crate::ColumnDataSource::with_id(crate::Id::from("source"));
+        source.add("x", &[1.0, 2.0, 3.0]);
+        let mut plot = crate::Plot::new();
+        plot.add_glyph(&source, crate::Circle::new());
+        let mut document = Document::new();
+        document.add_root(plot);
+
+        let mut session = Session::new(SessionContext::new(generate_session_id(None)), document);
+        let (first, _first_receiver) = std::sync::mpsc::channel();
+        session.add_client(0, first);
+        session.pull_document(0, "Test").unwrap();
+
+        let source_ref = json!({ "id": "source", "type": "ColumnDataSource" });
+        session.apply_client_patch(
+            0,
+            &json!({
+                "events": [
+                    {
+                        "kind": "ColumnsPatched",
+                        "column_source": source_ref,
+                        "patches": { "x": [[1, 20.0]] },
+                    },
+                    {
+                        "kind": "ColumnsStreamed",
+                        "column_source": source_ref,
+                        "data": { "x": [4.0] },
+                        "rollover": null,
+                    },
+                ],
+                "references": [],
+            }),
+        );
+
+        let (second, second_receiver) = std::sync::mpsc::channel();
+        session.add_client(1, second);
+        let pulled = session.pull_document(1, "Test").unwrap();
+        let source = pulled["roots"]["references"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["id"] == "source")
+            .unwrap()
+            .clone();
+        assert_eq!(
+            source["attributes"]["data"]["x"],
+            json!([1.0, 20.0, 3.0, 4.0])
+        );
+
+        // The server does not send the old data back
+        session.push_changes();
+        assert!(second_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_callbacks_added_later_are_run() {
+        let sessions = Sessions::new(SessionConfig::default());
+        let context = SessionContext::new(sessions.generate_id());
+        let handle = context.document.clone();
+        let _session = sessions
+            .get_or_create(context, |_| plot_document("plot"))
+            .unwrap();
+
+        // Give the scheduler time to park, as the document has no callbacks yet
+        thread::sleep(Duration::from_millis(20));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        handle
+            .with_document(|doc| {
+                doc.add_next_tick_callback(move |_| sender.send(()).unwrap());
+            })
+            .unwrap();
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn test_document_handle() {
+        let sessions = Sessions::new(SessionConfig::default());
+        let context = SessionContext::new(sessions.generate_id());
+        let handle = context.document.clone();
+        let session = sessions
+            .get_or_create(context, |_| plot_document("plot"))
+            .unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        session.lock().unwrap().add_client(0, sender);
+        session.lock().unwrap().pull_document(0, "Test").unwrap();
+
+        let worker_handle = handle.clone();
+        thread::spawn(move || {
+            worker_handle
+                .with_document(|doc| doc.root_mut().unwrap().plots_mut()[0].min_border = Some(15))
+                .unwrap()
+        })
+        .join()
+        .unwrap();
+
+        let message = receiver.try_recv().unwrap();
+        assert_eq!(message.content["events"][0]["attr"], "min_border");
+        assert_eq!(message.content["events"][0]["new"], json!(15));
+
+        drop(session);
+        sessions.sessions.lock().unwrap().clear();
+        assert!(handle.with_document(|_| ()).is_err());
+    }
+}
@@ -0,0 +1,946 @@
+//! Tables showing the rows of a data source, with formatters and editors for their cells
+
+use crate::layouts::LayoutDom;
+use crate::{
+    CDSView, ColumnDataSource, FontStyle, Id, Rounding, TextAlign, ToBokeh, Widget,
+    WidgetProperties,
+};
+use serde_json::{json, Value};
+
+// DataTable
+
+/// Table of the rows of a data source
+///
+/// A table given the same source as the glyphs of a plot shows the same data; rows selected in
+/// either of them are selected in both, and edits made in the table are shown by the plot.
+#[derive(Clone)]
+pub struct DataTable {
+    id: Id,
+    pub(crate) source: ColumnDataSource,
+    view: CDSView,
+    /// Columns of the table, from left to right
+    pub columns: Vec<TableColumn>,
+    /// Whether cells can be edited with the editors of their columns, false if not set
+    pub editable: Option<bool>,
+    /// Whether the columns are resized to fit the width of the table, true if not set
+    pub fit_columns: Option<bool>,
+    /// Height of each row in pixels, 25 if not set
+    pub row_height: Option<u32>,
+    /// Whether rows can be sorted by clicking the column headers, true if not set
+    pub sortable: Option<bool>,
+    /// Whether rows can be selected, true if not set
+    pub selectable: Option<bool>,
+    /// Whether a column of row indices is shown on the left, true if not set
+    pub show_index: Option<bool>,
+    /// Size and state of the widget
+    pub properties: WidgetProperties,
+}
+
+impl DataTable {
+    /// Create a new table of `columns` of the rows of `source`
+    pub fn new<I>(source: &ColumnDataSource, columns: I) -> DataTable
+    where
+        I: IntoIterator<Item = TableColumn>,
+    {
+        DataTable::with_id(Id::new(), source, columns)
+    }
+
+    /// Create a new table of `columns` of the rows of `source` with a known id
+    pub fn with_id<I>(id: Id, source: &ColumnDataSource, columns: I) -> DataTable
+    where
+        I: IntoIterator<Item = TableColumn>,
+    {
+        DataTable {
+            id,
+            source: source.clone(),
+            view: CDSView::new(source),
+            columns: columns.into_iter().collect(),
+            editable: None,
+            fit_columns: None,
+            row_height: None,
+            sortable: None,
+            selectable: None,
+            show_index: None,
+            properties: WidgetProperties::default(),
+        }
+    }
+}
+
+impl From<DataTable> for Widget {
+    fn from(w: DataTable) -> Widget {
+        Widget::DataTable(w)
+    }
+}
+
+impl From<DataTable> for LayoutDom {
+    fn from(w: DataTable) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for DataTable {
+    fn as_bokeh_value(&self) -> Value {
+        let columns: Vec<Value> = self.columns.iter().map(|c| c.as_bokeh_ref()).collect();
+        let mut attributes = json!({
+            "columns": columns,
+            "source": self.source.as_bokeh_ref(),
+            "view": self.view.as_bokeh_ref(),
+        });
+        if let Some(editable) = self.editable {
+            attributes["editable"] = json!(editable);
+        }
+        if let Some(fit_columns) = self.fit_columns {
+            attributes["fit_columns"] = json!(fit_columns);
+        }
+        if let Some(row_height) = self.row_height {
+            attributes["row_height"] = json!(row_height);
+        }
+        if let Some(sortable) = self.sortable {
+            attributes["sortable"] = json!(sortable);
+        }
+        if let Some(selectable) = self.selectable {
+            attributes["selectable"] = json!(selectable);
+        }
+        if self.show_index == Some(false) {
+            attributes["index_position"] = Value::Null;
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "DataTable",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value(), self.view.as_bokeh_value()];
+        out.extend(self.source.references());
+        for column in &self.columns {
+            out.extend(column.references());
+        }
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// TableColumn
+
+/// Order rows are first sorted in when a column header is clicked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest value first
+    Ascending,
+    /// Largest value first
+    Descending,
+}
+
+impl SortOrder {
+    fn name(self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "ascending",
+            SortOrder::Descending => "descending",
+        }
+    }
+}
+
+/// Column of a `DataTable`, showing a column of its data source
+#[derive(Clone)]
+pub struct TableColumn {
+    id: Id,
+    /// Name of the column of the data source shown
+    pub field: String,
+    /// Heading of the column
+    pub title: String,
+    /// Width of the column in pixels, 300 if not set
+    pub width: Option<u32>,
+    /// How values are shown, as plain text if not set
+    pub formatter: Option<CellFormatter>,
+    /// How values are edited in an editable table, as free text if not set
+    pub editor: Option<CellEditor>,
+    /// Whether rows can be sorted by this column, true if not set
+    pub sortable: Option<bool>,
+    /// Order of the first sort by this column, ascending if not set
+    pub default_sort: Option<SortOrder>,
+}
+
+impl TableColumn {
+    /// Create a new column showing `field` of the data source headed by `title`
+    pub fn new<S, T>(field: S, title: T) -> TableColumn
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        TableColumn::with_id(Id::new(), field, title)
+    }
+
+    /// Create a new column showing `field` of the data source with a known id
+    pub fn with_id<S, T>(id: Id, field: S, title: T) -> TableColumn
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        TableColumn {
+            id,
+            field: field.into(),
+            title: title.into(),
+            width: None,
+            formatter: None,
+            editor: None,
+            sortable: None,
+            default_sort: None,
+        }
+    }
+}
+
+impl ToBokeh for TableColumn {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "field": self.field,
+            "title": self.title,
+        });
+        if let Some(width) = self.width {
+            attributes["width"] = json!(width);
+        }
+        if let Some(formatter) = &self.formatter {
+            attributes["formatter"] = formatter.as_bokeh_ref();
+        }
+        if let Some(editor) = &self.editor {
+            attributes["editor"] = editor.as_bokeh_ref();
+        }
+        if let Some(sortable) = self.sortable {
+            attributes["sortable"] = json!(sortable);
+        }
+        if let Some(default_sort) = self.default_sort {
+            attributes["default_sort"] = json!(default_sort.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "TableColumn",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        if let Some(formatter) = &self.formatter {
+            out.push(formatter.as_bokeh_value());
+        }
+        if let Some(editor) = &self.editor {
+            out.push(editor.as_bokeh_value());
+        }
+        out
+    }
+}
+
+// Formatters
+
+/// All of the ways cells of a table can be shown
+#[derive(Clone)]
+pub enum CellFormatter {
+    /// Values shown as styled text
+    StringFormatter(StringFormatter),
+    /// Numbers formatted with a Numbro format string
+    NumberFormatter(NumberFormatter),
+    /// Dates formatted with a date format string
+    DateFormatter(DateFormatter),
+    /// Values substituted into an HTML template
+    HTMLTemplateFormatter(HTMLTemplateFormatter),
+}
+
+impl ToBokeh for CellFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            CellFormatter::StringFormatter(f) => f.as_bokeh_value(),
+            CellFormatter::NumberFormatter(f) => f.as_bokeh_value(),
+            CellFormatter::DateFormatter(f) => f.as_bokeh_value(),
+            CellFormatter::HTMLTemplateFormatter(f) => f.as_bokeh_value(),
+        }
+    }
+}
+
+/// Style of the text of formatted cells
+#[derive(Clone, Debug, Default)]
+pub struct CellTextProperties {
+    /// Style of the font, normal if not set
+    pub font_style: Option<FontStyle>,
+    /// Alignment of the text in the cell, left if not set
+    pub text_align: Option<TextAlign>,
+    /// Colour of the text
+    pub text_color: Option<String>,
+}
+
+impl CellTextProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(font_style) = self.font_style {
+            attributes["font_style"] = json!(font_style.name());
+        }
+        if let Some(text_align) = self.text_align {
+            attributes["text_align"] = json!(text_align.name());
+        }
+        if let Some(text_color) = &self.text_color {
+            attributes["text_color"] = json!(text_color);
+        }
+    }
+}
+
+/// Shows values as text, styled the same way in every cell
+#[derive(Clone, Default)]
+pub struct StringFormatter {
+    id: Id,
+    /// Style of the text
+    pub text_properties: CellTextProperties,
+}
+
+impl StringFormatter {
+    /// Create a new StringFormatter
+    pub fn new() -> StringFormatter {
+        StringFormatter::default()
+    }
+
+    /// Create a new StringFormatter with a known id
+    pub fn with_id(id: Id) -> StringFormatter {
+        StringFormatter {
+            id,
+            ..StringFormatter::default()
+        }
+    }
+}
+
+impl From<StringFormatter> for CellFormatter {
+    fn from(f: StringFormatter) -> CellFormatter {
+        CellFormatter::StringFormatter(f)
+    }
+}
+
+impl ToBokeh for StringFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        self.text_properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "StringFormatter",
+        })
+    }
+}
+
+/// Shows numbers with a [Numbro](http://numbrojs.com/format.html) format string
+#[derive(Clone)]
+pub struct NumberFormatter {
+    id: Id,
+    /// Format of the numbers
+    pub format: String,
+    /// Language to format the numbers in, such as `"fr"`, English if not set
+    pub language: Option<String>,
+    /// How numbers are rounded, to the nearest value if not set
+    pub rounding: Option<Rounding>,
+    /// Style of the text
+    pub text_properties: CellTextProperties,
+}
+
+impl NumberFormatter {
+    /// Create a new NumberFormatter with the given format, such as `"0,0.00"`
+    pub fn new<S>(format: S) -> NumberFormatter
+    where
+        S: Into<String>,
+    {
+        NumberFormatter::with_id(Id::new(), format)
+    }
+
+    /// Create a new NumberFormatter with a known id
+    pub fn with_id<S>(id: Id, format: S) -> NumberFormatter
+    where
+        S: Into<String>,
+    {
+        NumberFormatter {
+            id,
+            format: format.into(),
+            language: None,
+            rounding: None,
+            text_properties: CellTextProperties::default(),
+        }
+    }
+}
+
+impl From<NumberFormatter> for CellFormatter {
+    fn from(f: NumberFormatter) -> CellFormatter {
+        CellFormatter::NumberFormatter(f)
+    }
+}
+
+impl ToBokeh for NumberFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "format": self.format });
+        if let Some(language) = &self.language {
+            attributes["language"] = json!(language);
+        }
+        if let Some(rounding) = self.rounding {
+            attributes["rounding"] = json!(rounding.name());
+        }
+        self.text_properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "NumberFormatter",
+        })
+    }
+}
+
+/// Shows dates, given as milliseconds since the epoch, with a date format string
+#[derive(Clone)]
+pub struct DateFormatter {
+    id: Id,
+    /// Format of the dates, either a strftime format such as `"%d %b %Y"` or a named format such
+    /// as `"ISO-8601"` or `"RFC-2822"`
+    pub format: String,
+}
+
+impl DateFormatter {
+    /// Create a new DateFormatter with the given format
+    pub fn new<S>(format: S) -> DateFormatter
+    where
+        S: Into<String>,
+    {
+        DateFormatter::with_id(Id::new(), format)
+    }
+
+    /// Create a new DateFormatter with a known id
+    pub fn with_id<S>(id: Id, format: S) -> DateFormatter
+    where
+        S: Into<String>,
+    {
+        DateFormatter {
+            id,
+            format: format.into(),
+        }
+    }
+}
+
+impl From<DateFormatter> for CellFormatter {
+    fn from(f: DateFormatter) -> CellFormatter {
+        CellFormatter::DateFormatter(f)
+    }
+}
+
+impl ToBokeh for DateFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "format": self.format,
+            },
+            "id": self.id,
+            "type": "DateFormatter",
+        })
+    }
+}
+
+/// Shows cells with an [Underscore](https://underscorejs.org/#template) HTML template
+///
+/// The template is given the value of the cell as `value` and the other columns of the row by
+/// name, e.g. `"<a href=\"<%= url %>\"><%= value %></a>"`.
+#[derive(Clone)]
+pub struct HTMLTemplateFormatter {
+    id: Id,
+    /// Template of the HTML of each cell
+    pub template: String,
+}
+
+impl HTMLTemplateFormatter {
+    /// Create a new HTMLTemplateFormatter with the given template
+    pub fn new<S>(template: S) -> HTMLTemplateFormatter
+    where
+        S: Into<String>,
+    {
+        HTMLTemplateFormatter::with_id(Id::new(), template)
+    }
+
+    /// Create a new HTMLTemplateFormatter with a known id
+    pub fn with_id<S>(id: Id, template: S) -> HTMLTemplateFormatter
+    where
+        S: Into<String>,
+    {
+        HTMLTemplateFormatter {
+            id,
+            template: template.into(),
+        }
+    }
+}
+
+impl From<HTMLTemplateFormatter> for CellFormatter {
+    fn from(f: HTMLTemplateFormatter) -> CellFormatter {
+        CellFormatter::HTMLTemplateFormatter(f)
+    }
+}
+
+impl ToBokeh for HTMLTemplateFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "template": self.template,
+            },
+            "id": self.id,
+            "type": "HTMLTemplateFormatter",
+        })
+    }
+}
+
+// Editors
+
+/// All of the ways cells of an editable table can be edited
+#[derive(Clone)]
+pub enum CellEditor {
+    /// Single line of text, with optional completions
+    StringEditor(StringEditor),
+    /// Several lines of text
+    TextEditor(TextEditor),
+    /// Drop-down list of options
+    SelectEditor(SelectEditor),
+    /// Whole numbers
+    IntEditor(IntEditor),
+    /// Numbers
+    NumberEditor(NumberEditor),
+    /// Checkbox for true or false values
+    CheckboxEditor(CheckboxEditor),
+    /// Calendar choosing a date
+    DateEditor(DateEditor),
+}
+
+impl ToBokeh for CellEditor {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            CellEditor::StringEditor(e) => e.as_bokeh_value(),
+            CellEditor::TextEditor(e) => e.as_bokeh_value(),
+            CellEditor::SelectEditor(e) => e.as_bokeh_value(),
+            CellEditor::IntEditor(e) => e.as_bokeh_value(),
+            CellEditor::NumberEditor(e) => e.as_bokeh_value(),
+            CellEditor::CheckboxEditor(e) => e.as_bokeh_value(),
+            CellEditor::DateEditor(e) => e.as_bokeh_value(),
+        }
+    }
+}
+
+/// Edits cells as a single line of text
+#[derive(Clone, Default)]
+pub struct StringEditor {
+    id: Id,
+    /// Values suggested while typing
+    pub completions: Vec<String>,
+}
+
+impl StringEditor {
+    /// Create a new StringEditor
+    pub fn new() -> StringEditor {
+        StringEditor::default()
+    }
+
+    /// Create a new StringEditor with a known id
+    pub fn with_id(id: Id) -> StringEditor {
+        StringEditor {
+            id,
+            ..StringEditor::default()
+        }
+    }
+}
+
+impl From<StringEditor> for CellEditor {
+    fn from(e: StringEditor) -> CellEditor {
+        CellEditor::StringEditor(e)
+    }
+}
+
+impl ToBokeh for StringEditor {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.completions.is_empty() {
+            attributes["completions"] = json!(self.completions);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "StringEditor",
+        })
+    }
+}
+
+/// Edits cells as several lines of text
+#[derive(Clone, Default)]
+pub struct TextEditor {
+    id: Id,
+}
+
+impl TextEditor {
+    /// Create a new TextEditor
+    pub fn new() -> TextEditor {
+        TextEditor::default()
+    }
+
+    /// Create a new TextEditor with a known id
+    pub fn with_id(id: Id) -> TextEditor {
+        TextEditor { id }
+    }
+}
+
+impl From<TextEditor> for CellEditor {
+    fn from(e: TextEditor) -> CellEditor {
+        CellEditor::TextEditor(e)
+    }
+}
+
+impl ToBokeh for TextEditor {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "TextEditor",
+        })
+    }
+}
+
+/// Edits cells by choosing one of a list of options
+#[derive(Clone)]
+pub struct SelectEditor {
+    id: Id,
+    /// Values which can be chosen
+    pub options: Vec<String>,
+}
+
+impl SelectEditor {
+    /// Create a new SelectEditor choosing one of `options`
+    pub fn new<I, S>(options: I) -> SelectEditor
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        SelectEditor::with_id(Id::new(), options)
+    }
+
+    /// Create a new SelectEditor choosing one of `options` with a known id
+    pub fn with_id<I, S>(id: Id, options: I) -> SelectEditor
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        SelectEditor {
+            id,
+            options: options.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<SelectEditor> for CellEditor {
+    fn from(e: SelectEditor) -> CellEditor {
+        CellEditor::SelectEditor(e)
+    }
+}
+
+impl ToBokeh for SelectEditor {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "options": self.options,
+            },
+            "id": self.id,
+            "type": "SelectEditor",
+        })
+    }
+}
+
+/// Edits cells as whole numbers
+#[derive(Clone, Default)]
+pub struct IntEditor {
+    id: Id,
+    /// Change made by the arrow keys, 1 if not set
+    pub step: Option<i64>,
+}
+
+impl IntEditor {
+    /// Create a new IntEditor
+    pub fn new() -> IntEditor {
+        IntEditor::default()
+    }
+
+    /// Create a new IntEditor with a known id
+    pub fn with_id(id: Id) -> IntEditor {
+        IntEditor {
+            id,
+            ..IntEditor::default()
+        }
+    }
+}
+
+impl From<IntEditor> for CellEditor {
+    fn from(e: IntEditor) -> CellEditor {
+        CellEditor::IntEditor(e)
+    }
+}
+
+impl ToBokeh for IntEditor {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(step) = self.step {
+            attributes["step"] = json!(step);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "IntEditor",
+        })
+    }
+}
+
+/// Edits cells as numbers
+#[derive(Clone, Default)]
+pub struct NumberEditor {
+    id: Id,
+    /// Change made by the arrow keys, 0.01 if not set
+    pub step: Option<f64>,
+}
+
+impl NumberEditor {
+    /// Create a new NumberEditor
+    pub fn new() -> NumberEditor {
+        NumberEditor::default()
+    }
+
+    /// Create a new NumberEditor with a known id
+    pub fn with_id(id: Id) -> NumberEditor {
+        NumberEditor {
+            id,
+            ..NumberEditor::default()
+        }
+    }
+}
+
+impl From<NumberEditor> for CellEditor {
+    fn from(e: NumberEditor) -> CellEditor {
+        CellEditor::NumberEditor(e)
+    }
+}
+
+impl ToBokeh for NumberEditor {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(step) = self.step {
+            attributes["step"] = json!(step);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "NumberEditor",
+        })
+    }
+}
+
+/// Edits cells holding true or false with a checkbox
+#[derive(Clone, Default)]
+pub struct CheckboxEditor {
+    id: Id,
+}
+
+impl CheckboxEditor {
+    /// Create a new CheckboxEditor
+    pub fn new() -> CheckboxEditor {
+        CheckboxEditor::default()
+    }
+
+    /// Create a new CheckboxEditor with a known id
+    pub fn with_id(id: Id) -> CheckboxEditor {
+        CheckboxEditor { id }
+    }
+}
+
+impl From<CheckboxEditor> for CellEditor {
+    fn from(e: CheckboxEditor) -> CellEditor {
+        CellEditor::CheckboxEditor(e)
+    }
+}
+
+impl ToBokeh for CheckboxEditor {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "CheckboxEditor",
+        })
+    }
+}
+
+/// Edits cells holding dates with a calendar
+#[derive(Clone, Default)]
+pub struct DateEditor {
+    id: Id,
+}
+
+impl DateEditor {
+    /// Create a new DateEditor
+    pub fn new() -> DateEditor {
+        DateEditor::default()
+    }
+
+    /// Create a new DateEditor with a known id
+    pub fn with_id(id: Id) -> DateEditor {
+        DateEditor { id }
+    }
+}
+
+impl From<DateEditor> for CellEditor {
+    fn from(e: DateEditor) -> CellEditor {
+        CellEditor::DateEditor(e)
+    }
+}
+
+impl ToBokeh for DateEditor {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "DateEditor",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{file_html, Circle, Document, Plot, BOKEH_VERSION};
+    use std::collections::HashMap;
+
+    fn table() -> (ColumnDataSource, DataTable) {
+        let mut source = ColumnDataSource::with_id(Id::from("1001"));
+        source.add("price", &[1.5, 2.25]);
+
+        let mut price = TableColumn::with_id(Id::from("1003"), "price", "Price");
+        price.formatter = Some(NumberFormatter::with_id(Id::from("1004"), "$0.00").into());
+        price.editor = Some(NumberEditor::with_id(Id::from("1005")).into());
+        price.default_sort = Some(SortOrder::Descending);
+
+        let mut table = DataTable::with_id(Id::from("1002"), &source, vec![price]);
+        table.editable = Some(true);
+        table.show_index = Some(false);
+        (source, table)
+    }
+
+    #[test]
+    fn test_data_table() {
+        let (_, table) = table();
+        let value = table.as_bokeh_value();
+        assert_eq!(value["type"], "DataTable");
+        assert_eq!(
+            value["attributes"]["columns"],
+            json!([{"id": "1003", "type": "TableColumn"}])
+        );
+        assert_eq!(
+            value["attributes"]["source"],
+            json!({"id": "1001", "type": "ColumnDataSource"})
+        );
+        assert_eq!(value["attributes"]["editable"], true);
+        assert_eq!(value["attributes"]["index_position"], Value::Null);
+        assert!(value["attributes"]
+            .as_object()
+            .unwrap()
+            .contains_key("index_position"));
+
+        let references = table.references();
+        let column = references
+            .iter()
+            .find(|r| r["type"] == "TableColumn")
+            .unwrap();
+        assert_eq!(
+            column["attributes"],
+            json!({
+                "default_sort": "descending",
+                "editor": {"id": "1005", "type": "NumberEditor"},
+                "field": "price",
+                "formatter": {"id": "1004", "type": "NumberFormatter"},
+                "title": "Price",
+            })
+        );
+        for kind in &[
+            "CDSView",
+            "ColumnDataSource",
+            "NumberFormatter",
+            "NumberEditor",
+        ] {
+            assert!(references.iter().any(|r| r["type"] == *kind));
+        }
+    }
+
+    #[test]
+    fn test_formatters() {
+        let mut string = StringFormatter::with_id(Id::from("1001"));
+        string.text_properties.font_style = Some(FontStyle::Bold);
+        string.text_properties.text_align = Some(TextAlign::Right);
+        assert_eq!(
+            CellFormatter::from(string).as_bokeh_value(),
+            json!({
+                "attributes": {"font_style": "bold", "text_align": "right"},
+                "id": "1001",
+                "type": "StringFormatter",
+            })
+        );
+
+        let date = DateFormatter::with_id(Id::from("1002"), "%d %b %Y");
+        assert_eq!(
+            CellFormatter::from(date).as_bokeh_value()["attributes"],
+            json!({"format": "%d %b %Y"})
+        );
+        let html = HTMLTemplateFormatter::with_id(Id::from("1003"), "<b><%= value %></b>");
+        assert_eq!(
+            CellFormatter::from(html).as_bokeh_value()["attributes"],
+            json!({"template": "<b><%= value %></b>"})
+        );
+
+        let select = SelectEditor::with_id(Id::from("1004"), vec!["a", "b"]);
+        assert_eq!(
+            CellEditor::from(select).as_bokeh_value()["attributes"],
+            json!({"options": ["a", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_data_table_file_html() {
+        let (_, table) = table();
+        let mut doc = Document::new();
+        doc.add_root(table);
+
+        let html = file_html(&doc.validate().unwrap(), "Prices").unwrap();
+        let script = format!(
+            r#"<script type="text/javascript" src="https://cdn.pydata.org/bokeh/release/bokeh-tables-{}.min.js"></script>"#,
+            BOKEH_VERSION
+        );
+        assert!(html.contains(&script));
+        assert!(html.contains(r#""type":"DataTable""#));
+    }
+
+    #[test]
+    fn test_data_table_shares_plot_source() {
+        let (source, table) = table();
+        let mut plot = Plot::new();
+        plot.add_glyph(&source, Circle::new());
+
+        let mut doc = Document::new();
+        doc.add_root(crate::layouts::row(vec![
+            LayoutDom::from(plot),
+            table.into(),
+        ]));
+        let mut data = HashMap::new();
+        data.insert("price".to_string(), vec![3.0]);
+        doc.stream(&Id::from("1001"), data, None).unwrap();
+
+        let references: Vec<Value> = doc.root().unwrap().references();
+        let sources: Vec<&Value> = references
+            .iter()
+            .filter(|r| r["type"] == "ColumnDataSource")
+            .collect();
+        assert!(sources
+            .iter()
+            .all(|s| s["attributes"]["data"]["price"] == json!([1.5, 2.25, 3.0])));
+    }
+}
@@ -0,0 +1,254 @@
+//! Themes, and resolution of the effective value of model properties
+//!
+//! A theme set on a document with `Document::set_theme` is applied as the document is
+//! serialized, so a property of a model ends up with a value from one of three places: it was set
+//! explicitly on the model, it was provided by the theme, or BokehJS falls back on its built-in
+//! default. `Theme::resolve` reports which, to help track down why a plot looks different to its
+//! Python counterpart.
+
+use crate::defaults::builtin_defaults;
+use crate::{Result, ToBokeh};
+use failure::format_err;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Where the effective value of a property comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertySource {
+    /// Set on the model itself
+    Explicit,
+    /// Provided by the theme
+    Theme,
+    /// Built-in BokehJS default
+    Default,
+}
+
+/// Effective value of a single property
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedProperty {
+    /// Name of the property
+    pub name: String,
+    /// Value used when rendering
+    pub value: Value,
+    /// Where the value comes from
+    pub source: PropertySource,
+}
+
+/// Effective properties of a single model
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelSnapshot {
+    /// Id of the model
+    pub id: Value,
+    /// BokehJS type of the model
+    pub model_type: String,
+    /// Effective value of every known property, sorted by name
+    pub properties: Vec<ResolvedProperty>,
+}
+
+/// Default property values per model type, like Python's JSON themes
+///
+/// Values are given in their serialized form, as they appear in the attributes of the document
+/// JSON, e.g. `{"value": "red"}` for a fill color.
+#[derive(Clone, Debug, Default)]
+pub struct Theme {
+    attrs: HashMap<String, Map<String, Value>>,
+}
+
+impl Theme {
+    /// Create a new empty theme
+    pub fn new() -> Self {
+        Theme::default()
+    }
+
+    /// Build a theme from JSON of the form `{"attrs": {"Circle": {"size": ...}}}`
+    pub fn from_json(json: &Value) -> Result<Self> {
+        let attrs = json["attrs"]
+            .as_object()
+            .ok_or_else(|| format_err!("theme requires an `attrs` object"))?;
+
+        let mut theme = Theme::new();
+        for (model_type, properties) in attrs {
+            let properties = properties.as_object().ok_or_else(|| {
+                format_err!("theme attributes for `{}` must be an object", model_type)
+            })?;
+            theme.attrs.insert(model_type.clone(), properties.clone());
+        }
+        Ok(theme)
+    }
+
+    /// Set the themed value of `attr` for models of type `model_type`
+    pub fn set<M, A>(&mut self, model_type: M, attr: A, value: Value)
+    where
+        M: Into<String>,
+        A: Into<String>,
+    {
+        self.attrs
+            .entry(model_type.into())
+            .or_default()
+            .insert(attr.into(), value);
+    }
+
+    /// Fill in the themed properties of a serialized model which it does not set itself
+    pub(crate) fn apply(&self, value: &mut Value) {
+        let themed = match value["type"].as_str().and_then(|t| self.attrs.get(t)) {
+            Some(themed) => themed,
+            None => return,
+        };
+        if let Some(attributes) = value["attributes"].as_object_mut() {
+            for (name, themed_value) in themed {
+                attributes
+                    .entry(name.as_str())
+                    .or_insert_with(|| themed_value.clone());
+            }
+        }
+    }
+
+    /// Effective value of every known property of `model`, as rendered in a document with this
+    /// theme
+    pub fn resolve<M>(&self, model: &M) -> Vec<ResolvedProperty>
+    where
+        M: ToBokeh + ?Sized,
+    {
+        self.resolve_value(&model.as_bokeh_value()).properties
+    }
+
+    /// Effective properties of `model` and of every model it refers to
+    pub fn snapshot<M>(&self, model: &M) -> Vec<ModelSnapshot>
+    where
+        M: ToBokeh + ?Sized,
+    {
+        model
+            .references()
+            .iter()
+            .map(|value| self.resolve_value(value))
+            .collect()
+    }
+
+    fn resolve_value(&self, value: &Value) -> ModelSnapshot {
+        let model_type = value["type"].as_str().unwrap_or_default().to_string();
+
+        let mut properties = BTreeMap::new();
+        if let Some(defaults) = builtin_defaults(&model_type) {
+            for (name, value) in defaults {
+                properties.insert(name.clone(), (value.clone(), PropertySource::Default));
+            }
+        }
+        if let Some(themed) = self.attrs.get(&model_type) {
+            for (name, value) in themed {
+                properties.insert(name.clone(), (value.clone(), PropertySource::Theme));
+            }
+        }
+        if let Some(explicit) = value["attributes"].as_object() {
+            for (name, value) in explicit {
+                properties.insert(name.clone(), (value.clone(), PropertySource::Explicit));
+            }
+        }
+
+        ModelSnapshot {
+            id: value["id"].clone(),
+            model_type,
+            properties: properties
+                .into_iter()
+                .map(|(name, (value, source))| ResolvedProperty {
+                    name,
+                    value,
+                    source,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, ColumnDataSource, Document, Plot, Slider};
+    use serde_json::json;
+
+    fn property<'a>(properties: &'a [ResolvedProperty], name: &str) -> &'a ResolvedProperty {
+        properties.iter().find(|p| p.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_sources() {
+        let theme = Theme::from_json(&json!({
+            "attrs": {
+                "Circle": {
+                    "fill_color": {"value": "blue"},
+                    "line_color": {"value": "white"},
+                },
+            },
+        }))
+        .unwrap();
+
+        let mut circle = Circle::new();
+        circle.line_color = Some("red".to_string());
+
+        let properties = theme.resolve(&circle);
+
+        let line_color = property(&properties, "line_color");
+        assert_eq!(line_color.value, json!({"value": "red"}));
+        assert_eq!(line_color.source, PropertySource::Explicit);
+
+        let fill_color = property(&properties, "fill_color");
+        assert_eq!(fill_color.value, json!({"value": "blue"}));
+        assert_eq!(fill_color.source, PropertySource::Theme);
+
+        let size = property(&properties, "size");
+        assert_eq!(size.value, json!({"units": "screen", "value": 4}));
+        assert_eq!(size.source, PropertySource::Default);
+    }
+
+    #[test]
+    fn test_snapshot_covers_references() {
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), Circle::new());
+
+        let snapshot = Theme::new().snapshot(&plot);
+        let types: Vec<&str> = snapshot.iter().map(|s| s.model_type.as_str()).collect();
+        assert!(types.contains(&"Plot"));
+        assert!(types.contains(&"Circle"));
+
+        let plot_snapshot = snapshot.iter().find(|s| s.model_type == "Plot").unwrap();
+        let min_border = property(&plot_snapshot.properties, "min_border");
+        assert_eq!(min_border.value, json!(5));
+        assert_eq!(min_border.source, PropertySource::Default);
+    }
+
+    #[test]
+    fn test_theme_applied_to_document() {
+        let mut theme = Theme::new();
+        theme.set("Circle", "fill_color", json!({"value": "blue"}));
+        theme.set("Circle", "line_color", json!({"value": "white"}));
+
+        let mut circle = Circle::new();
+        circle.line_color = Some("red".to_string());
+        let mut plot = Plot::new();
+        plot.add_glyph(&ColumnDataSource::new(), circle);
+        let mut doc = Document::new();
+        doc.add_root(plot);
+        doc.set_theme(theme);
+
+        let references = doc.validate().unwrap().references();
+        let circle = references.iter().find(|r| r["type"] == "Circle").unwrap();
+        assert_eq!(circle["attributes"]["fill_color"], json!({"value": "blue"}));
+        assert_eq!(circle["attributes"]["line_color"], json!({"value": "red"}));
+    }
+
+    #[test]
+    fn test_defaults_of_other_models() {
+        let properties = Theme::new().resolve(&Slider::new(0.0, 1.0, 0.5));
+        let show_value = property(&properties, "show_value");
+        assert_eq!(show_value.value, json!(true));
+        assert_eq!(show_value.source, PropertySource::Default);
+        assert_eq!(
+            property(&properties, "value").source,
+            PropertySource::Explicit
+        );
+    }
+
+    #[test]
+    fn test_invalid_theme() {
+        assert!(Theme::from_json(&json!({"Circle": {}})).is_err());
+    }
+}
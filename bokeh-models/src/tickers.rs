@@ -0,0 +1,645 @@
+//! Tickers, choosing where the ticks of an axis go
+
+use crate::{Id, ToBokeh};
+use serde_json::{json, Value};
+
+/// Tickers for axes
+#[derive(Clone)]
+pub enum Ticker {
+    /// Ticks at round numbers
+    BasicTicker(BasicTicker),
+    /// Ticks at round numbers, with configurable rounding
+    AdaptiveTicker(AdaptiveTicker),
+    /// Ticks at powers of the base
+    LogTicker(LogTicker),
+    /// Ticks at round dates and times
+    DatetimeTicker(DatetimeTicker),
+    /// Ticks at the start of given months
+    MonthsTicker(MonthsTicker),
+    /// Ticks at given days of the month
+    DaysTicker(DaysTicker),
+    /// Ticks at the start of round years
+    YearsTicker(YearsTicker),
+    /// Ticks at each category
+    CategoricalTicker(CategoricalTicker),
+    /// Ticks at round latitudes or longitudes
+    MercatorTicker(MercatorTicker),
+    /// Ticks at given locations
+    FixedTicker(FixedTicker),
+    /// Ticks at a fixed spacing
+    SingleIntervalTicker(SingleIntervalTicker),
+}
+
+impl ToBokeh for Ticker {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Ticker::BasicTicker(t) => t.as_bokeh_value(),
+            Ticker::AdaptiveTicker(t) => t.as_bokeh_value(),
+            Ticker::LogTicker(t) => t.as_bokeh_value(),
+            Ticker::DatetimeTicker(t) => t.as_bokeh_value(),
+            Ticker::MonthsTicker(t) => t.as_bokeh_value(),
+            Ticker::DaysTicker(t) => t.as_bokeh_value(),
+            Ticker::YearsTicker(t) => t.as_bokeh_value(),
+            Ticker::CategoricalTicker(t) => t.as_bokeh_value(),
+            Ticker::MercatorTicker(t) => t.as_bokeh_value(),
+            Ticker::FixedTicker(t) => t.as_bokeh_value(),
+            Ticker::SingleIntervalTicker(t) => t.as_bokeh_value(),
+        }
+    }
+}
+
+// BasicTicker
+
+/// Struct representing ticks
+#[derive(Clone, Default)]
+pub struct BasicTicker {
+    id: Id,
+}
+
+impl BasicTicker {
+    /// Create a new BasicTicker
+    pub fn new() -> BasicTicker {
+        BasicTicker::default()
+    }
+
+    /// Create a new BasicTicker with a known id
+    pub fn with_id(id: Id) -> BasicTicker {
+        BasicTicker { id }
+    }
+}
+
+impl From<BasicTicker> for Ticker {
+    fn from(t: BasicTicker) -> Ticker {
+        Ticker::BasicTicker(t)
+    }
+}
+
+impl ToBokeh for BasicTicker {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "BasicTicker",
+        })
+    }
+}
+
+// AdaptiveTicker
+
+/// How an `AdaptiveTicker` or `LogTicker` chooses the spacing of its ticks
+#[derive(Clone, Debug, Default)]
+pub struct AdaptiveProperties {
+    /// Multiples of powers of the base that the spacing is chosen from, `[1, 2, 5]` if not set
+    pub mantissas: Option<Vec<f64>>,
+    /// Base of the powers the spacing is a multiple of, 10 if not set
+    pub base: Option<f64>,
+    /// Number of major ticks to aim for, 6 if not set
+    pub desired_num_ticks: Option<u32>,
+    /// Number of minor ticks between each pair of major ticks, 5 if not set
+    pub num_minor_ticks: Option<u32>,
+    /// Smallest spacing between major ticks
+    pub min_interval: Option<f64>,
+    /// Largest spacing between major ticks
+    pub max_interval: Option<f64>,
+}
+
+impl AdaptiveProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(mantissas) = &self.mantissas {
+            attributes["mantissas"] = json!(mantissas);
+        }
+        if let Some(base) = self.base {
+            attributes["base"] = json!(base);
+        }
+        if let Some(desired_num_ticks) = self.desired_num_ticks {
+            attributes["desired_num_ticks"] = json!(desired_num_ticks);
+        }
+        if let Some(num_minor_ticks) = self.num_minor_ticks {
+            attributes["num_minor_ticks"] = json!(num_minor_ticks);
+        }
+        if let Some(min_interval) = self.min_interval {
+            attributes["min_interval"] = json!(min_interval);
+        }
+        if let Some(max_interval) = self.max_interval {
+            attributes["max_interval"] = json!(max_interval);
+        }
+    }
+}
+
+/// Ticks at round numbers, with the rounding given by its properties
+///
+/// Use this in place of a `BasicTicker` to tune the number and spacing of ticks.
+#[derive(Clone, Default)]
+pub struct AdaptiveTicker {
+    id: Id,
+    /// How the spacing of the ticks is chosen
+    pub properties: AdaptiveProperties,
+}
+
+impl AdaptiveTicker {
+    /// Create a new AdaptiveTicker
+    pub fn new() -> AdaptiveTicker {
+        AdaptiveTicker::default()
+    }
+
+    /// Create a new AdaptiveTicker with a known id
+    pub fn with_id(id: Id) -> AdaptiveTicker {
+        AdaptiveTicker {
+            id,
+            ..AdaptiveTicker::default()
+        }
+    }
+}
+
+impl From<AdaptiveTicker> for Ticker {
+    fn from(t: AdaptiveTicker) -> Ticker {
+        Ticker::AdaptiveTicker(t)
+    }
+}
+
+impl ToBokeh for AdaptiveTicker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "AdaptiveTicker",
+        })
+    }
+}
+
+// LogTicker
+
+/// Ticks at powers of ten, for log axes
+///
+/// The mantissas default to `[1, 5]` rather than `[1, 2, 5]`.
+#[derive(Clone, Default)]
+pub struct LogTicker {
+    id: Id,
+    /// How the spacing of the ticks is chosen
+    pub properties: AdaptiveProperties,
+}
+
+impl LogTicker {
+    /// Create a new LogTicker
+    pub fn new() -> LogTicker {
+        LogTicker::default()
+    }
+
+    /// Create a new LogTicker with a known id
+    pub fn with_id(id: Id) -> LogTicker {
+        LogTicker {
+            id,
+            ..LogTicker::default()
+        }
+    }
+}
+
+impl From<LogTicker> for Ticker {
+    fn from(t: LogTicker) -> Ticker {
+        Ticker::LogTicker(t)
+    }
+}
+
+impl ToBokeh for LogTicker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "LogTicker",
+        })
+    }
+}
+
+// DatetimeTicker
+
+/// Ticks at round dates and times, from milliseconds to years, depending on the zoom level
+#[derive(Clone, Default)]
+pub struct DatetimeTicker {
+    id: Id,
+}
+
+impl DatetimeTicker {
+    /// Create a new DatetimeTicker
+    pub fn new() -> DatetimeTicker {
+        DatetimeTicker::default()
+    }
+
+    /// Create a new DatetimeTicker with a known id
+    pub fn with_id(id: Id) -> DatetimeTicker {
+        DatetimeTicker { id }
+    }
+}
+
+impl From<DatetimeTicker> for Ticker {
+    fn from(t: DatetimeTicker) -> Ticker {
+        Ticker::DatetimeTicker(t)
+    }
+}
+
+impl ToBokeh for DatetimeTicker {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "DatetimeTicker",
+        })
+    }
+}
+
+// MonthsTicker
+
+/// Ticks at the start of given months of each year, for datetime axes
+#[derive(Clone, Default)]
+pub struct MonthsTicker {
+    id: Id,
+    /// Months to tick at, counting January as 0
+    pub months: Vec<u32>,
+}
+
+impl MonthsTicker {
+    /// Create a new MonthsTicker ticking at `months`, counting January as 0
+    pub fn new(months: Vec<u32>) -> MonthsTicker {
+        MonthsTicker::with_id(Id::new(), months)
+    }
+
+    /// Create a new MonthsTicker ticking at `months` with a known id
+    pub fn with_id(id: Id, months: Vec<u32>) -> MonthsTicker {
+        MonthsTicker { id, months }
+    }
+}
+
+impl From<MonthsTicker> for Ticker {
+    fn from(t: MonthsTicker) -> Ticker {
+        Ticker::MonthsTicker(t)
+    }
+}
+
+impl ToBokeh for MonthsTicker {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "months": self.months,
+            },
+            "id": self.id,
+            "type": "MonthsTicker",
+        })
+    }
+}
+
+// DaysTicker
+
+/// Ticks at given days of each month, for datetime axes
+#[derive(Clone, Default)]
+pub struct DaysTicker {
+    id: Id,
+    /// Days of the month to tick at, counting from 1
+    pub days: Vec<u32>,
+}
+
+impl DaysTicker {
+    /// Create a new DaysTicker ticking at `days` of the month
+    pub fn new(days: Vec<u32>) -> DaysTicker {
+        DaysTicker::with_id(Id::new(), days)
+    }
+
+    /// Create a new DaysTicker ticking at `days` of the month with a known id
+    pub fn with_id(id: Id, days: Vec<u32>) -> DaysTicker {
+        DaysTicker { id, days }
+    }
+}
+
+impl From<DaysTicker> for Ticker {
+    fn from(t: DaysTicker) -> Ticker {
+        Ticker::DaysTicker(t)
+    }
+}
+
+impl ToBokeh for DaysTicker {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "days": self.days,
+            },
+            "id": self.id,
+            "type": "DaysTicker",
+        })
+    }
+}
+
+// YearsTicker
+
+/// Ticks at the start of round years, for datetime axes
+#[derive(Clone, Default)]
+pub struct YearsTicker {
+    id: Id,
+}
+
+impl YearsTicker {
+    /// Create a new YearsTicker
+    pub fn new() -> YearsTicker {
+        YearsTicker::default()
+    }
+
+    /// Create a new YearsTicker with a known id
+    pub fn with_id(id: Id) -> YearsTicker {
+        YearsTicker { id }
+    }
+}
+
+impl From<YearsTicker> for Ticker {
+    fn from(t: YearsTicker) -> Ticker {
+        Ticker::YearsTicker(t)
+    }
+}
+
+impl ToBokeh for YearsTicker {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "YearsTicker",
+        })
+    }
+}
+
+// CategoricalTicker
+
+/// Ticks at each category of a `FactorRange`
+#[derive(Clone, Default)]
+pub struct CategoricalTicker {
+    id: Id,
+}
+
+impl CategoricalTicker {
+    /// Create a new CategoricalTicker
+    pub fn new() -> CategoricalTicker {
+        CategoricalTicker::default()
+    }
+
+    /// Create a new CategoricalTicker with a known id
+    pub fn with_id(id: Id) -> CategoricalTicker {
+        CategoricalTicker { id }
+    }
+}
+
+impl From<CategoricalTicker> for Ticker {
+    fn from(t: CategoricalTicker) -> Ticker {
+        Ticker::CategoricalTicker(t)
+    }
+}
+
+impl ToBokeh for CategoricalTicker {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "CategoricalTicker",
+        })
+    }
+}
+
+// MercatorTicker
+
+/// Whether Web Mercator coordinates are labelled with latitudes or longitudes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LatLon {
+    /// Latitudes, for the y axis
+    Lat,
+    /// Longitudes, for the x axis
+    Lon,
+}
+
+impl LatLon {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            LatLon::Lat => "lat",
+            LatLon::Lon => "lon",
+        }
+    }
+}
+
+/// Ticks at round latitudes or longitudes, for axes of Web Mercator coordinates
+#[derive(Clone, Default)]
+pub struct MercatorTicker {
+    id: Id,
+    /// Whether the ticks are at round latitudes or longitudes
+    pub dimension: Option<LatLon>,
+}
+
+impl MercatorTicker {
+    /// Create a new MercatorTicker
+    pub fn new() -> MercatorTicker {
+        MercatorTicker::default()
+    }
+
+    /// Create a new MercatorTicker with a known id
+    pub fn with_id(id: Id) -> MercatorTicker {
+        MercatorTicker {
+            id,
+            ..MercatorTicker::default()
+        }
+    }
+}
+
+impl From<MercatorTicker> for Ticker {
+    fn from(t: MercatorTicker) -> Ticker {
+        Ticker::MercatorTicker(t)
+    }
+}
+
+impl ToBokeh for MercatorTicker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimension) = self.dimension {
+            attributes["dimension"] = json!(dimension.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "MercatorTicker",
+        })
+    }
+}
+
+// FixedTicker
+
+/// Ticks at given locations, whatever the zoom level
+#[derive(Clone, Default)]
+pub struct FixedTicker {
+    id: Id,
+    /// Locations of the major ticks
+    pub ticks: Vec<f64>,
+    /// Locations of the minor ticks
+    pub minor_ticks: Vec<f64>,
+}
+
+impl FixedTicker {
+    /// Create a new FixedTicker with major ticks at `ticks`
+    pub fn new(ticks: Vec<f64>) -> FixedTicker {
+        FixedTicker::with_id(Id::new(), ticks)
+    }
+
+    /// Create a new FixedTicker with major ticks at `ticks` and a known id
+    pub fn with_id(id: Id, ticks: Vec<f64>) -> FixedTicker {
+        FixedTicker {
+            id,
+            ticks,
+            minor_ticks: Vec::new(),
+        }
+    }
+}
+
+impl From<FixedTicker> for Ticker {
+    fn from(t: FixedTicker) -> Ticker {
+        Ticker::FixedTicker(t)
+    }
+}
+
+impl ToBokeh for FixedTicker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "ticks": self.ticks,
+        });
+        if !self.minor_ticks.is_empty() {
+            attributes["minor_ticks"] = json!(self.minor_ticks);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "FixedTicker",
+        })
+    }
+}
+
+// SingleIntervalTicker
+
+/// Ticks at multiples of a fixed interval, whatever the zoom level
+#[derive(Clone)]
+pub struct SingleIntervalTicker {
+    id: Id,
+    /// Spacing between major ticks
+    pub interval: f64,
+    /// Number of minor ticks between each pair of major ticks, 5 if not set
+    pub num_minor_ticks: Option<u32>,
+}
+
+impl SingleIntervalTicker {
+    /// Create a new SingleIntervalTicker ticking every `interval`
+    pub fn new(interval: f64) -> SingleIntervalTicker {
+        SingleIntervalTicker::with_id(Id::new(), interval)
+    }
+
+    /// Create a new SingleIntervalTicker ticking every `interval` with a known id
+    pub fn with_id(id: Id, interval: f64) -> SingleIntervalTicker {
+        SingleIntervalTicker {
+            id,
+            interval,
+            num_minor_ticks: None,
+        }
+    }
+}
+
+impl From<SingleIntervalTicker> for Ticker {
+    fn from(t: SingleIntervalTicker) -> Ticker {
+        Ticker::SingleIntervalTicker(t)
+    }
+}
+
+impl ToBokeh for SingleIntervalTicker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "interval": self.interval,
+        });
+        if let Some(num_minor_ticks) = self.num_minor_ticks {
+            attributes["num_minor_ticks"] = json!(num_minor_ticks);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "SingleIntervalTicker",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_ticker() {
+        assert_eq!(
+            Ticker::from(LogTicker::with_id(Id::from("1001"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "LogTicker"})
+        );
+    }
+
+    #[test]
+    fn test_fixed_ticker() {
+        let mut ticker = FixedTicker::with_id(Id::from("1001"), vec![1.0, 5.0, 20.0]);
+        ticker.minor_ticks = vec![2.0, 3.0];
+        assert_eq!(
+            Ticker::from(ticker).as_bokeh_value(),
+            json!({
+                "attributes": {"minor_ticks": [2.0, 3.0], "ticks": [1.0, 5.0, 20.0]},
+                "id": "1001",
+                "type": "FixedTicker",
+            })
+        );
+    }
+
+    #[test]
+    fn test_adaptive_ticker() {
+        let mut ticker = AdaptiveTicker::with_id(Id::from("1001"));
+        ticker.properties.mantissas = Some(vec![1.0, 2.5]);
+        ticker.properties.base = Some(2.0);
+        ticker.properties.desired_num_ticks = Some(10);
+        assert_eq!(
+            Ticker::from(ticker).as_bokeh_value(),
+            json!({
+                "attributes": {"base": 2.0, "desired_num_ticks": 10, "mantissas": [1.0, 2.5]},
+                "id": "1001",
+                "type": "AdaptiveTicker",
+            })
+        );
+
+        let mut ticker = LogTicker::new();
+        ticker.properties.num_minor_ticks = Some(9);
+        assert_eq!(ticker.as_bokeh_value()["attributes"]["num_minor_ticks"], 9);
+    }
+
+    #[test]
+    fn test_calendar_tickers() {
+        assert_eq!(
+            Ticker::from(MonthsTicker::with_id(Id::from("1001"), vec![0, 6])).as_bokeh_value(),
+            json!({"attributes": {"months": [0, 6]}, "id": "1001", "type": "MonthsTicker"})
+        );
+        assert_eq!(
+            DaysTicker::new(vec![1, 15]).as_bokeh_value()["attributes"],
+            json!({"days": [1, 15]})
+        );
+        assert_eq!(YearsTicker::new().as_bokeh_value()["type"], "YearsTicker");
+    }
+
+    #[test]
+    fn test_single_interval_ticker() {
+        let mut ticker = SingleIntervalTicker::with_id(Id::from("1001"), 10.0);
+        ticker.num_minor_ticks = Some(2);
+        assert_eq!(
+            Ticker::from(ticker).as_bokeh_value(),
+            json!({
+                "attributes": {"interval": 10.0, "num_minor_ticks": 2},
+                "id": "1001",
+                "type": "SingleIntervalTicker",
+            })
+        );
+    }
+}
@@ -0,0 +1,2743 @@
+//! Tools for interacting with plots in the browser
+
+use crate::layouts::SizingMode;
+use crate::{Callback, Id, Plot, Range1d, Result, ToBokeh};
+use failure::format_err;
+use serde_json::{json, Value};
+
+/// Tools for the plot, by the kind of interaction they act on
+#[derive(Clone)]
+pub enum Tool {
+    /// Tools acting on mouse drags
+    Drag(Drag),
+    /// Tools acting on the mouse wheel
+    Scroll(Scroll),
+    /// Tools acting on clicks
+    Tap(Tap),
+    /// Tools inspecting the glyphs under the mouse
+    Inspection(Inspection),
+    /// Tools acting once each time they are clicked on the toolbar
+    Action(Action),
+}
+
+impl ToBokeh for Tool {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Tool::Drag(t) => t.as_bokeh_value(),
+            Tool::Scroll(t) => t.as_bokeh_value(),
+            Tool::Tap(t) => t.as_bokeh_value(),
+            Tool::Inspection(t) => t.as_bokeh_value(),
+            Tool::Action(t) => t.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Tool::Drag(t) => t.references(),
+            Tool::Scroll(t) => t.references(),
+            Tool::Tap(t) => t.references(),
+            Tool::Inspection(t) => t.references(),
+            Tool::Action(t) => t.references(),
+        }
+    }
+}
+
+/// Tools acting on mouse drags
+#[derive(Clone)]
+pub enum Drag {
+    /// Allow the plot to pan
+    PanTool(PanTool),
+    /// Zoom to a box drawn by dragging the mouse
+    BoxZoomTool(BoxZoomTool),
+    /// Select the glyphs inside a box drawn by dragging the mouse
+    BoxSelectTool(BoxSelectTool),
+    /// Select the glyphs inside a freeform region drawn by dragging the mouse
+    LassoSelectTool(LassoSelectTool),
+    /// Add, move and delete points
+    PointDrawTool(PointDrawTool),
+    /// Draw, move and delete boxes
+    BoxEditTool(BoxEditTool),
+    /// Draw, move and delete polygons and lines
+    PolyDrawTool(PolyDrawTool),
+    /// Draw freehand polygons and lines
+    FreehandDrawTool(FreehandDrawTool),
+    /// Drag a box on an overview plot to set the ranges shown by another plot
+    RangeTool(Box<RangeTool>),
+}
+
+impl From<Drag> for Tool {
+    fn from(t: Drag) -> Tool {
+        Tool::Drag(t)
+    }
+}
+
+impl ToBokeh for Drag {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Drag::PanTool(t) => t.as_bokeh_value(),
+            Drag::BoxZoomTool(t) => t.as_bokeh_value(),
+            Drag::BoxSelectTool(t) => t.as_bokeh_value(),
+            Drag::LassoSelectTool(t) => t.as_bokeh_value(),
+            Drag::PointDrawTool(t) => t.as_bokeh_value(),
+            Drag::BoxEditTool(t) => t.as_bokeh_value(),
+            Drag::PolyDrawTool(t) => t.as_bokeh_value(),
+            Drag::FreehandDrawTool(t) => t.as_bokeh_value(),
+            Drag::RangeTool(t) => t.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Drag::PanTool(t) => t.references(),
+            Drag::BoxZoomTool(t) => t.references(),
+            Drag::BoxSelectTool(t) => t.references(),
+            Drag::LassoSelectTool(t) => t.references(),
+            Drag::PointDrawTool(t) => t.references(),
+            Drag::BoxEditTool(t) => t.references(),
+            Drag::PolyDrawTool(t) => t.references(),
+            Drag::FreehandDrawTool(t) => t.references(),
+            Drag::RangeTool(t) => t.references(),
+        }
+    }
+}
+
+/// Tools acting on the mouse wheel
+#[derive(Clone)]
+pub enum Scroll {
+    /// Zoom in and out with the mouse wheel
+    WheelZoomTool(WheelZoomTool),
+    /// Pan the plot along one dimension with the mouse wheel
+    WheelPanTool(WheelPanTool),
+}
+
+impl From<Scroll> for Tool {
+    fn from(t: Scroll) -> Tool {
+        Tool::Scroll(t)
+    }
+}
+
+impl ToBokeh for Scroll {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Scroll::WheelZoomTool(t) => t.as_bokeh_value(),
+            Scroll::WheelPanTool(t) => t.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Scroll::WheelZoomTool(t) => t.references(),
+            Scroll::WheelPanTool(t) => t.references(),
+        }
+    }
+}
+
+/// Tools acting on clicks
+#[derive(Clone)]
+pub enum Tap {
+    /// Select or inspect glyphs by clicking on them
+    TapTool(TapTool),
+    /// Select the glyphs inside a polygon drawn by clicking its corners
+    PolySelectTool(PolySelectTool),
+}
+
+impl From<Tap> for Tool {
+    fn from(t: Tap) -> Tool {
+        Tool::Tap(t)
+    }
+}
+
+impl ToBokeh for Tap {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Tap::TapTool(t) => t.as_bokeh_value(),
+            Tap::PolySelectTool(t) => t.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Tap::TapTool(t) => t.references(),
+            Tap::PolySelectTool(t) => t.references(),
+        }
+    }
+}
+
+/// Tools inspecting the glyphs under the mouse
+#[derive(Clone)]
+pub enum Inspection {
+    /// Show tooltips for the glyphs under the mouse
+    HoverTool(HoverTool),
+    /// Draw lines through the mouse position
+    CrosshairTool(CrosshairTool),
+}
+
+impl From<Inspection> for Tool {
+    fn from(t: Inspection) -> Tool {
+        Tool::Inspection(t)
+    }
+}
+
+impl ToBokeh for Inspection {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Inspection::HoverTool(t) => t.as_bokeh_value(),
+            Inspection::CrosshairTool(t) => t.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Inspection::HoverTool(t) => t.references(),
+            Inspection::CrosshairTool(t) => t.references(),
+        }
+    }
+}
+
+/// Tools acting once each time they are clicked on the toolbar
+#[derive(Clone)]
+pub enum Action {
+    /// Download the plot as a PNG image
+    SaveTool(SaveTool),
+    /// Reset the ranges of the plot to their initial values
+    ResetTool(ResetTool),
+    /// Undo the last change to the ranges of the plot
+    UndoTool(UndoTool),
+    /// Redo the last change to the ranges of the plot undone by an undo tool
+    RedoTool(RedoTool),
+    /// Zoom into the plot by a fixed amount on every click
+    ZoomInTool(ZoomInTool),
+    /// Zoom out of the plot by a fixed amount on every click
+    ZoomOutTool(ZoomOutTool),
+    /// Open a page of documentation about the plot
+    HelpTool(HelpTool),
+    /// Run a callback of its own
+    CustomAction(CustomAction),
+}
+
+impl From<Action> for Tool {
+    fn from(t: Action) -> Tool {
+        Tool::Action(t)
+    }
+}
+
+impl ToBokeh for Action {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Action::SaveTool(t) => t.as_bokeh_value(),
+            Action::ResetTool(t) => t.as_bokeh_value(),
+            Action::UndoTool(t) => t.as_bokeh_value(),
+            Action::RedoTool(t) => t.as_bokeh_value(),
+            Action::ZoomInTool(t) => t.as_bokeh_value(),
+            Action::ZoomOutTool(t) => t.as_bokeh_value(),
+            Action::HelpTool(t) => t.as_bokeh_value(),
+            Action::CustomAction(t) => t.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Action::SaveTool(t) => t.references(),
+            Action::ResetTool(t) => t.references(),
+            Action::UndoTool(t) => t.references(),
+            Action::RedoTool(t) => t.references(),
+            Action::ZoomInTool(t) => t.references(),
+            Action::ZoomOutTool(t) => t.references(),
+            Action::HelpTool(t) => t.references(),
+            Action::CustomAction(t) => t.references(),
+        }
+    }
+}
+
+/// Allow the plot to pan
+#[derive(Clone, Default)]
+pub struct PanTool {
+    id: Id,
+    /// Dimensions the plot can be panned along
+    pub dimensions: Option<Dimensions>,
+}
+
+impl PanTool {
+    /// Create a new pan tool
+    pub fn new() -> Self {
+        PanTool::default()
+    }
+
+    /// Create a new pan tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        PanTool {
+            id,
+            ..PanTool::default()
+        }
+    }
+}
+
+impl From<PanTool> for Drag {
+    fn from(t: PanTool) -> Drag {
+        Drag::PanTool(t)
+    }
+}
+
+impl From<PanTool> for Tool {
+    fn from(t: PanTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for PanTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "PanTool",
+        })
+    }
+}
+
+/// Zoom in and out with the mouse wheel
+#[derive(Clone, Default)]
+pub struct WheelZoomTool {
+    id: Id,
+    /// Dimensions to zoom along
+    pub dimensions: Option<Dimensions>,
+    /// Whether to keep the point under the mouse fixed while zooming
+    pub maintain_focus: Option<bool>,
+    /// Fraction of the ranges to zoom by per pixel scrolled
+    pub speed: Option<f64>,
+}
+
+impl WheelZoomTool {
+    /// Create a new wheel zoom tool
+    pub fn new() -> Self {
+        WheelZoomTool::default()
+    }
+
+    /// Create a new wheel zoom tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        WheelZoomTool {
+            id,
+            ..WheelZoomTool::default()
+        }
+    }
+}
+
+impl From<WheelZoomTool> for Scroll {
+    fn from(t: WheelZoomTool) -> Scroll {
+        Scroll::WheelZoomTool(t)
+    }
+}
+
+impl From<WheelZoomTool> for Tool {
+    fn from(t: WheelZoomTool) -> Tool {
+        Tool::Scroll(t.into())
+    }
+}
+
+impl ToBokeh for WheelZoomTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+        if let Some(maintain_focus) = self.maintain_focus {
+            attributes["maintain_focus"] = json!(maintain_focus);
+        }
+        if let Some(speed) = self.speed {
+            attributes["speed"] = json!(speed);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "WheelZoomTool",
+        })
+    }
+}
+
+/// Which glyphs the hover tool inspects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoverMode {
+    /// Glyphs under the mouse
+    Mouse,
+    /// Glyphs crossing a vertical line through the mouse
+    VLine,
+    /// Glyphs crossing a horizontal line through the mouse
+    HLine,
+}
+
+impl HoverMode {
+    fn name(self) -> &'static str {
+        match self {
+            HoverMode::Mouse => "mouse",
+            HoverMode::VLine => "vline",
+            HoverMode::HLine => "hline",
+        }
+    }
+}
+
+/// How the value of a field is formatted in a tooltip
+#[derive(Clone)]
+pub enum TooltipFormatter {
+    /// Numbers formatted with a Numbro format such as `0.00`
+    Numeral,
+    /// Timestamps formatted with a strftime format such as `%F %T`
+    Datetime,
+    /// Numbers formatted with a printf format such as `%.2f`
+    Printf,
+    /// Values formatted by JavaScript code
+    CustomJS(CustomJSHover),
+}
+
+impl TooltipFormatter {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            TooltipFormatter::Numeral => json!("numeral"),
+            TooltipFormatter::Datetime => json!("datetime"),
+            TooltipFormatter::Printf => json!("printf"),
+            TooltipFormatter::CustomJS(formatter) => formatter.as_bokeh_ref(),
+        }
+    }
+}
+
+impl From<CustomJSHover> for TooltipFormatter {
+    fn from(formatter: CustomJSHover) -> TooltipFormatter {
+        TooltipFormatter::CustomJS(formatter)
+    }
+}
+
+/// Tooltip field formatter implemented in JavaScript
+///
+/// The code is the body of a function given `value`, `format` and `special_vars`, and returns
+/// the text to show.
+#[derive(Clone, Default)]
+pub struct CustomJSHover {
+    id: Id,
+    /// Body of the formatting function
+    pub code: String,
+}
+
+impl CustomJSHover {
+    /// Create a new formatter running `code`
+    pub fn new<S>(code: S) -> Self
+    where
+        S: Into<String>,
+    {
+        CustomJSHover {
+            code: code.into(),
+            ..CustomJSHover::default()
+        }
+    }
+
+    /// Create a new formatter running `code`, with a known id
+    pub fn with_id<S>(id: Id, code: S) -> Self
+    where
+        S: Into<String>,
+    {
+        CustomJSHover {
+            id,
+            code: code.into(),
+        }
+    }
+}
+
+impl ToBokeh for CustomJSHover {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {
+                "code": self.code,
+            },
+            "id": self.id,
+            "type": "CustomJSHover",
+        })
+    }
+}
+
+#[derive(Clone)]
+enum Tooltips {
+    Fields(Vec<(String, String)>),
+    Html(String),
+}
+
+impl Default for Tooltips {
+    fn default() -> Self {
+        Tooltips::Fields(Vec::new())
+    }
+}
+
+/// Show tooltips for the glyphs under the mouse
+#[derive(Clone, Default)]
+pub struct HoverTool {
+    id: Id,
+    renderers: Vec<Id>,
+    tooltips: Tooltips,
+    formatters: Vec<(String, TooltipFormatter)>,
+    /// Which glyphs to inspect
+    pub mode: Option<HoverMode>,
+}
+
+impl HoverTool {
+    /// Create a new hover tool, showing the BokehJS default tooltips
+    pub fn new() -> Self {
+        HoverTool::default()
+    }
+
+    /// Create a new hover tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        HoverTool {
+            id,
+            ..HoverTool::default()
+        }
+    }
+
+    /// Only show tooltips for the glyphs drawn by `renderer`
+    ///
+    /// Without any renderers the tool acts on every glyph of the plot. `renderer` is the id
+    /// returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+
+    /// Add a row to the tooltip
+    ///
+    /// `value` may refer to columns of the data source as `@column`, or to special variables
+    /// such as `$index` or `$x`.
+    ///
+    /// Replaces any HTML template set with `set_tooltip_html`.
+    pub fn add_tooltip<L, V>(&mut self, label: L, value: V)
+    where
+        L: Into<String>,
+        V: Into<String>,
+    {
+        if let Tooltips::Html(_) = self.tooltips {
+            self.tooltips = Tooltips::default();
+        }
+        if let Tooltips::Fields(fields) = &mut self.tooltips {
+            fields.push((label.into(), value.into()));
+        }
+    }
+
+    /// Show the tooltip as an HTML template instead of label and value rows
+    ///
+    /// The template may use the same `@column` and `$variable` fields as the rows.
+    pub fn set_tooltip_html<S>(&mut self, html: S)
+    where
+        S: Into<String>,
+    {
+        self.tooltips = Tooltips::Html(html.into());
+    }
+
+    /// Format the values of `field`, e.g. `@date`, with `formatter`
+    ///
+    /// The format itself follows the field in the tooltip, as in `@date{%F}`.
+    pub fn add_formatter<S, F>(&mut self, field: S, formatter: F)
+    where
+        S: Into<String>,
+        F: Into<TooltipFormatter>,
+    {
+        let field = field.into();
+        self.formatters.retain(|(f, _)| *f != field);
+        self.formatters.push((field, formatter.into()));
+    }
+}
+
+impl From<HoverTool> for Inspection {
+    fn from(t: HoverTool) -> Inspection {
+        Inspection::HoverTool(t)
+    }
+}
+
+impl From<HoverTool> for Tool {
+    fn from(t: HoverTool) -> Tool {
+        Tool::Inspection(t.into())
+    }
+}
+
+impl ToBokeh for HoverTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        match &self.tooltips {
+            Tooltips::Fields(fields) if fields.is_empty() => {}
+            Tooltips::Fields(fields) => attributes["tooltips"] = json!(fields),
+            Tooltips::Html(html) => attributes["tooltips"] = json!(html),
+        }
+        if !self.formatters.is_empty() {
+            let formatters: serde_json::Map<String, Value> = self
+                .formatters
+                .iter()
+                .map(|(field, formatter)| (field.clone(), formatter.as_bokeh_value()))
+                .collect();
+            attributes["formatters"] = Value::Object(formatters);
+        }
+        if let Some(mode) = self.mode {
+            attributes["mode"] = json!(mode.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "HoverTool",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for (_, formatter) in &self.formatters {
+            if let TooltipFormatter::CustomJS(formatter) = formatter {
+                out.push(formatter.as_bokeh_value());
+            }
+        }
+        out
+    }
+}
+
+/// Dimensions a tool acts along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimensions {
+    /// Horizontally only
+    Width,
+    /// Vertically only
+    Height,
+    /// Both horizontally and vertically
+    Both,
+}
+
+impl Dimensions {
+    fn name(self) -> &'static str {
+        match self {
+            Dimensions::Width => "width",
+            Dimensions::Height => "height",
+            Dimensions::Both => "both",
+        }
+    }
+}
+
+/// Zoom to a box drawn by dragging the mouse
+#[derive(Clone, Default)]
+pub struct BoxZoomTool {
+    id: Id,
+    /// Which dimensions the box spans
+    pub dimensions: Option<Dimensions>,
+    /// Whether the box keeps the aspect ratio of the plot
+    pub match_aspect: Option<bool>,
+}
+
+impl BoxZoomTool {
+    /// Create a new box zoom tool
+    pub fn new() -> Self {
+        BoxZoomTool::default()
+    }
+
+    /// Create a new box zoom tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        BoxZoomTool {
+            id,
+            ..BoxZoomTool::default()
+        }
+    }
+}
+
+impl From<BoxZoomTool> for Drag {
+    fn from(t: BoxZoomTool) -> Drag {
+        Drag::BoxZoomTool(t)
+    }
+}
+
+impl From<BoxZoomTool> for Tool {
+    fn from(t: BoxZoomTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for BoxZoomTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+        if let Some(match_aspect) = self.match_aspect {
+            attributes["match_aspect"] = json!(match_aspect);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "BoxZoomTool",
+        })
+    }
+}
+
+/// Select the glyphs inside a box drawn by dragging the mouse
+///
+/// Selected rows are set on the `Selection` of each glyph's data source.
+#[derive(Clone, Default)]
+pub struct BoxSelectTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Which dimensions the box spans
+    pub dimensions: Option<Dimensions>,
+    /// Whether to update the selection while dragging, rather than when the drag ends
+    pub select_every_mousemove: Option<bool>,
+}
+
+impl BoxSelectTool {
+    /// Create a new box select tool
+    pub fn new() -> Self {
+        BoxSelectTool::default()
+    }
+
+    /// Create a new box select tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        BoxSelectTool {
+            id,
+            ..BoxSelectTool::default()
+        }
+    }
+
+    /// Only select the glyphs drawn by `renderer`
+    ///
+    /// Without any renderers the tool acts on every glyph of the plot. `renderer` is the id
+    /// returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<BoxSelectTool> for Drag {
+    fn from(t: BoxSelectTool) -> Drag {
+        Drag::BoxSelectTool(t)
+    }
+}
+
+impl From<BoxSelectTool> for Tool {
+    fn from(t: BoxSelectTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for BoxSelectTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+        if let Some(select_every_mousemove) = self.select_every_mousemove {
+            attributes["select_every_mousemove"] = json!(select_every_mousemove);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "BoxSelectTool",
+        })
+    }
+}
+
+/// Select the glyphs inside a freeform region drawn by dragging the mouse
+#[derive(Clone, Default)]
+pub struct LassoSelectTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Whether to update the selection while drawing, rather than when the drag ends
+    pub continuous: Option<bool>,
+}
+
+impl LassoSelectTool {
+    /// Create a new lasso select tool
+    pub fn new() -> Self {
+        LassoSelectTool::default()
+    }
+
+    /// Create a new lasso select tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        LassoSelectTool {
+            id,
+            ..LassoSelectTool::default()
+        }
+    }
+
+    /// Only select the glyphs drawn by `renderer`
+    ///
+    /// Without any renderers the tool acts on every glyph of the plot. `renderer` is the id
+    /// returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<LassoSelectTool> for Drag {
+    fn from(t: LassoSelectTool) -> Drag {
+        Drag::LassoSelectTool(t)
+    }
+}
+
+impl From<LassoSelectTool> for Tool {
+    fn from(t: LassoSelectTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for LassoSelectTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        // Called `select_every_mousemove` in BokehJS 1.0
+        if let Some(continuous) = self.continuous {
+            attributes["select_every_mousemove"] = json!(continuous);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "LassoSelectTool",
+        })
+    }
+}
+
+/// Select the glyphs inside a polygon drawn by clicking its corners
+///
+/// Double click to close the polygon and make the selection.
+#[derive(Clone, Default)]
+pub struct PolySelectTool {
+    id: Id,
+    renderers: Vec<Id>,
+}
+
+impl PolySelectTool {
+    /// Create a new poly select tool
+    pub fn new() -> Self {
+        PolySelectTool::default()
+    }
+
+    /// Create a new poly select tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        PolySelectTool {
+            id,
+            ..PolySelectTool::default()
+        }
+    }
+
+    /// Only select the glyphs drawn by `renderer`
+    ///
+    /// Without any renderers the tool acts on every glyph of the plot. `renderer` is the id
+    /// returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<PolySelectTool> for Tap {
+    fn from(t: PolySelectTool) -> Tap {
+        Tap::PolySelectTool(t)
+    }
+}
+
+impl From<PolySelectTool> for Tool {
+    fn from(t: PolySelectTool) -> Tool {
+        Tool::Tap(t.into())
+    }
+}
+
+impl ToBokeh for PolySelectTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "PolySelectTool",
+        })
+    }
+}
+
+/// What tapping a glyph does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapBehavior {
+    /// Select the tapped glyphs
+    Select,
+    /// Only inspect the tapped glyphs, leaving the selection alone
+    Inspect,
+}
+
+impl TapBehavior {
+    fn name(self) -> &'static str {
+        match self {
+            TapBehavior::Select => "select",
+            TapBehavior::Inspect => "inspect",
+        }
+    }
+}
+
+/// Select or inspect glyphs by clicking on them
+///
+/// The callback, if any, is run on every tap, e.g. to open a page about the tapped item.
+#[derive(Clone, Default)]
+pub struct TapTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Whether a tap selects or only inspects the glyphs
+    pub behavior: Option<TapBehavior>,
+    /// Callback run when glyphs are tapped
+    pub callback: Option<Callback>,
+}
+
+impl TapTool {
+    /// Create a new tap tool
+    pub fn new() -> Self {
+        TapTool::default()
+    }
+
+    /// Create a new tap tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        TapTool {
+            id,
+            ..TapTool::default()
+        }
+    }
+
+    /// Only tap the glyphs drawn by `renderer`
+    ///
+    /// Without any renderers the tool acts on every glyph of the plot. `renderer` is the id
+    /// returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<TapTool> for Tap {
+    fn from(t: TapTool) -> Tap {
+        Tap::TapTool(t)
+    }
+}
+
+impl From<TapTool> for Tool {
+    fn from(t: TapTool) -> Tool {
+        Tool::Tap(t.into())
+    }
+}
+
+impl ToBokeh for TapTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        if let Some(behavior) = self.behavior {
+            attributes["behavior"] = json!(behavior.name());
+        }
+        if let Some(callback) = &self.callback {
+            attributes["callback"] = callback.as_bokeh_ref();
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "TapTool",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        if let Some(callback) = &self.callback {
+            out.extend(callback.references());
+        }
+        out
+    }
+}
+
+/// Draw lines through the mouse position
+///
+/// BokehJS keeps the spans drawing the crosshair on the tool itself, so adding the same tool, or a
+/// clone of it, to several plots links their crosshairs: moving the mouse over one plot moves the
+/// crosshair on every plot sharing the tool.
+#[derive(Clone, Default)]
+pub struct CrosshairTool {
+    id: Id,
+    /// Dimensions to draw lines along
+    pub dimensions: Option<Dimensions>,
+    /// Colour of the lines
+    pub line_color: Option<String>,
+    /// Opacity of the lines
+    pub line_alpha: Option<f64>,
+    /// Width of the lines in pixels
+    pub line_width: Option<f64>,
+}
+
+impl CrosshairTool {
+    /// Create a new crosshair tool
+    pub fn new() -> Self {
+        CrosshairTool::default()
+    }
+
+    /// Create a new crosshair tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        CrosshairTool {
+            id,
+            ..CrosshairTool::default()
+        }
+    }
+}
+
+impl From<CrosshairTool> for Inspection {
+    fn from(t: CrosshairTool) -> Inspection {
+        Inspection::CrosshairTool(t)
+    }
+}
+
+impl From<CrosshairTool> for Tool {
+    fn from(t: CrosshairTool) -> Tool {
+        Tool::Inspection(t.into())
+    }
+}
+
+impl ToBokeh for CrosshairTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+        if let Some(line_color) = &self.line_color {
+            attributes["line_color"] = json!(line_color);
+        }
+        if let Some(line_alpha) = self.line_alpha {
+            attributes["line_alpha"] = json!(line_alpha);
+        }
+        if let Some(line_width) = self.line_width {
+            attributes["line_width"] = json!(line_width);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "CrosshairTool",
+        })
+    }
+}
+
+/// Download the plot as a PNG image
+///
+/// BokehJS 1.0 always names the image `bokeh_plot.png`; it has no option to change it.
+#[derive(Clone, Default)]
+pub struct SaveTool {
+    id: Id,
+}
+
+impl SaveTool {
+    /// Create a new save tool
+    pub fn new() -> Self {
+        SaveTool::default()
+    }
+
+    /// Create a new save tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        SaveTool { id }
+    }
+}
+
+impl From<SaveTool> for Action {
+    fn from(t: SaveTool) -> Action {
+        Action::SaveTool(t)
+    }
+}
+
+impl From<SaveTool> for Tool {
+    fn from(t: SaveTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for SaveTool {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "SaveTool",
+        })
+    }
+}
+
+/// Reset the ranges of the plot to their initial values
+#[derive(Clone, Default)]
+pub struct ResetTool {
+    id: Id,
+}
+
+impl ResetTool {
+    /// Create a new reset tool
+    pub fn new() -> Self {
+        ResetTool::default()
+    }
+
+    /// Create a new reset tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        ResetTool { id }
+    }
+}
+
+impl From<ResetTool> for Action {
+    fn from(t: ResetTool) -> Action {
+        Action::ResetTool(t)
+    }
+}
+
+impl From<ResetTool> for Tool {
+    fn from(t: ResetTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for ResetTool {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "ResetTool",
+        })
+    }
+}
+
+/// Undo the last change to the ranges of the plot
+#[derive(Clone, Default)]
+pub struct UndoTool {
+    id: Id,
+}
+
+impl UndoTool {
+    /// Create a new undo tool
+    pub fn new() -> Self {
+        UndoTool::default()
+    }
+
+    /// Create a new undo tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        UndoTool { id }
+    }
+}
+
+impl From<UndoTool> for Action {
+    fn from(t: UndoTool) -> Action {
+        Action::UndoTool(t)
+    }
+}
+
+impl From<UndoTool> for Tool {
+    fn from(t: UndoTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for UndoTool {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "UndoTool",
+        })
+    }
+}
+
+/// Redo the last change to the ranges of the plot undone by an undo tool
+#[derive(Clone, Default)]
+pub struct RedoTool {
+    id: Id,
+}
+
+impl RedoTool {
+    /// Create a new redo tool
+    pub fn new() -> Self {
+        RedoTool::default()
+    }
+
+    /// Create a new redo tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        RedoTool { id }
+    }
+}
+
+impl From<RedoTool> for Action {
+    fn from(t: RedoTool) -> Action {
+        Action::RedoTool(t)
+    }
+}
+
+impl From<RedoTool> for Tool {
+    fn from(t: RedoTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for RedoTool {
+    fn as_bokeh_value(&self) -> Value {
+        json!({
+            "attributes": {},
+            "id": self.id,
+            "type": "RedoTool",
+        })
+    }
+}
+
+/// Zoom into the plot by a fixed amount on every click
+#[derive(Clone, Default)]
+pub struct ZoomInTool {
+    id: Id,
+    /// Fraction of the current ranges to zoom in by
+    pub factor: Option<f64>,
+    /// Dimensions to zoom along
+    pub dimensions: Option<Dimensions>,
+}
+
+impl ZoomInTool {
+    /// Create a new zoom in tool
+    pub fn new() -> Self {
+        ZoomInTool::default()
+    }
+
+    /// Create a new zoom in tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        ZoomInTool {
+            id,
+            ..ZoomInTool::default()
+        }
+    }
+}
+
+impl From<ZoomInTool> for Action {
+    fn from(t: ZoomInTool) -> Action {
+        Action::ZoomInTool(t)
+    }
+}
+
+impl From<ZoomInTool> for Tool {
+    fn from(t: ZoomInTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for ZoomInTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(factor) = self.factor {
+            attributes["factor"] = json!(factor);
+        }
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "ZoomInTool",
+        })
+    }
+}
+
+/// Zoom out of the plot by a fixed amount on every click
+#[derive(Clone, Default)]
+pub struct ZoomOutTool {
+    id: Id,
+    /// Fraction of the current ranges to zoom out by
+    pub factor: Option<f64>,
+    /// Dimensions to zoom along
+    pub dimensions: Option<Dimensions>,
+}
+
+impl ZoomOutTool {
+    /// Create a new zoom out tool
+    pub fn new() -> Self {
+        ZoomOutTool::default()
+    }
+
+    /// Create a new zoom out tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        ZoomOutTool {
+            id,
+            ..ZoomOutTool::default()
+        }
+    }
+}
+
+impl From<ZoomOutTool> for Action {
+    fn from(t: ZoomOutTool) -> Action {
+        Action::ZoomOutTool(t)
+    }
+}
+
+impl From<ZoomOutTool> for Tool {
+    fn from(t: ZoomOutTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for ZoomOutTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(factor) = self.factor {
+            attributes["factor"] = json!(factor);
+        }
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "ZoomOutTool",
+        })
+    }
+}
+
+/// Single dimension a tool acts along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    /// Horizontally
+    Width,
+    /// Vertically
+    Height,
+}
+
+impl Dimension {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Dimension::Width => "width",
+            Dimension::Height => "height",
+        }
+    }
+}
+
+/// Pan the plot along one dimension with the mouse wheel
+#[derive(Clone, Default)]
+pub struct WheelPanTool {
+    id: Id,
+    /// Dimension to pan along
+    pub dimension: Option<Dimension>,
+}
+
+impl WheelPanTool {
+    /// Create a new wheel pan tool
+    pub fn new() -> Self {
+        WheelPanTool::default()
+    }
+
+    /// Create a new wheel pan tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        WheelPanTool {
+            id,
+            ..WheelPanTool::default()
+        }
+    }
+}
+
+impl From<WheelPanTool> for Scroll {
+    fn from(t: WheelPanTool) -> Scroll {
+        Scroll::WheelPanTool(t)
+    }
+}
+
+impl From<WheelPanTool> for Tool {
+    fn from(t: WheelPanTool) -> Tool {
+        Tool::Scroll(t.into())
+    }
+}
+
+impl ToBokeh for WheelPanTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(dimension) = self.dimension {
+            attributes["dimension"] = json!(dimension.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "WheelPanTool",
+        })
+    }
+}
+
+// References to the glyph renderers with the given ids
+pub(crate) fn renderer_refs(renderers: &[Id]) -> Value {
+    renderers
+        .iter()
+        .map(|id| json!({"id": id, "type": "GlyphRenderer"}))
+        .collect()
+}
+
+/// Add, move and delete points
+///
+/// Clicking adds a point, dragging moves one and backspace deletes the selected points. The tool
+/// edits the data sources of its renderers, which should draw single points, e.g. `Circle`.
+#[derive(Clone, Default)]
+pub struct PointDrawTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Whether clicking adds new points
+    pub add: Option<bool>,
+    /// Whether points can be dragged
+    pub drag: Option<bool>,
+    /// Value of the columns not set by the tool in new rows
+    pub empty_value: Option<f64>,
+}
+
+impl PointDrawTool {
+    /// Create a new point draw tool
+    pub fn new() -> Self {
+        PointDrawTool::default()
+    }
+
+    /// Create a new point draw tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        PointDrawTool {
+            id,
+            ..PointDrawTool::default()
+        }
+    }
+
+    /// Let the tool edit the glyphs drawn by `renderer`, and its data source
+    ///
+    /// `renderer` is the id returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<PointDrawTool> for Drag {
+    fn from(t: PointDrawTool) -> Drag {
+        Drag::PointDrawTool(t)
+    }
+}
+
+impl From<PointDrawTool> for Tool {
+    fn from(t: PointDrawTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for PointDrawTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        if let Some(add) = self.add {
+            attributes["add"] = json!(add);
+        }
+        if let Some(drag) = self.drag {
+            attributes["drag"] = json!(drag);
+        }
+        if let Some(empty_value) = self.empty_value {
+            attributes["empty_value"] = json!(empty_value);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "PointDrawTool",
+        })
+    }
+}
+
+/// Draw, move and delete boxes
+///
+/// Dragging with shift held draws a new box, dragging moves one and backspace deletes the
+/// selected boxes. The tool edits the data sources of its renderers, which should draw `Rect`
+/// glyphs.
+#[derive(Clone, Default)]
+pub struct BoxEditTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Dimensions boxes can be drawn and moved along
+    pub dimensions: Option<Dimensions>,
+    /// Value of the columns not set by the tool in new rows
+    pub empty_value: Option<f64>,
+}
+
+impl BoxEditTool {
+    /// Create a new box edit tool
+    pub fn new() -> Self {
+        BoxEditTool::default()
+    }
+
+    /// Create a new box edit tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        BoxEditTool {
+            id,
+            ..BoxEditTool::default()
+        }
+    }
+
+    /// Let the tool edit the glyphs drawn by `renderer`, and its data source
+    ///
+    /// `renderer` is the id returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<BoxEditTool> for Drag {
+    fn from(t: BoxEditTool) -> Drag {
+        Drag::BoxEditTool(t)
+    }
+}
+
+impl From<BoxEditTool> for Tool {
+    fn from(t: BoxEditTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for BoxEditTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        if let Some(dimensions) = self.dimensions {
+            attributes["dimensions"] = json!(dimensions.name());
+        }
+        if let Some(empty_value) = self.empty_value {
+            attributes["empty_value"] = json!(empty_value);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "BoxEditTool",
+        })
+    }
+}
+
+/// Draw, move and delete polygons and lines
+///
+/// Double clicking starts a new shape, each click adds a vertex and a double click finishes it.
+/// The tool edits the data sources of its renderers, which should draw `Patches` or `MultiLine`
+/// glyphs.
+#[derive(Clone, Default)]
+pub struct PolyDrawTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Whether shapes can be dragged
+    pub drag: Option<bool>,
+    /// Value of the columns not set by the tool in new rows
+    pub empty_value: Option<f64>,
+}
+
+impl PolyDrawTool {
+    /// Create a new poly draw tool
+    pub fn new() -> Self {
+        PolyDrawTool::default()
+    }
+
+    /// Create a new poly draw tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        PolyDrawTool {
+            id,
+            ..PolyDrawTool::default()
+        }
+    }
+
+    /// Let the tool edit the glyphs drawn by `renderer`, and its data source
+    ///
+    /// `renderer` is the id returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<PolyDrawTool> for Drag {
+    fn from(t: PolyDrawTool) -> Drag {
+        Drag::PolyDrawTool(t)
+    }
+}
+
+impl From<PolyDrawTool> for Tool {
+    fn from(t: PolyDrawTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for PolyDrawTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        if let Some(drag) = self.drag {
+            attributes["drag"] = json!(drag);
+        }
+        if let Some(empty_value) = self.empty_value {
+            attributes["empty_value"] = json!(empty_value);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "PolyDrawTool",
+        })
+    }
+}
+
+/// Draw freehand polygons and lines
+///
+/// Each drag draws a new shape following the mouse. The tool edits the data sources of its
+/// renderers, which should draw `Patches` or `MultiLine` glyphs.
+#[derive(Clone, Default)]
+pub struct FreehandDrawTool {
+    id: Id,
+    renderers: Vec<Id>,
+    /// Value of the columns not set by the tool in new rows
+    pub empty_value: Option<f64>,
+}
+
+impl FreehandDrawTool {
+    /// Create a new freehand draw tool
+    pub fn new() -> Self {
+        FreehandDrawTool::default()
+    }
+
+    /// Create a new freehand draw tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        FreehandDrawTool {
+            id,
+            ..FreehandDrawTool::default()
+        }
+    }
+
+    /// Let the tool edit the glyphs drawn by `renderer`, and its data source
+    ///
+    /// `renderer` is the id returned by `Plot::add_glyph`.
+    pub fn add_renderer(&mut self, renderer: &Id) {
+        self.renderers.push(renderer.clone());
+    }
+}
+
+impl From<FreehandDrawTool> for Drag {
+    fn from(t: FreehandDrawTool) -> Drag {
+        Drag::FreehandDrawTool(t)
+    }
+}
+
+impl From<FreehandDrawTool> for Tool {
+    fn from(t: FreehandDrawTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for FreehandDrawTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if !self.renderers.is_empty() {
+            attributes["renderers"] = renderer_refs(&self.renderers);
+        }
+        if let Some(empty_value) = self.empty_value {
+            attributes["empty_value"] = json!(empty_value);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "FreehandDrawTool",
+        })
+    }
+}
+
+/// Drag a box on an overview plot to set the ranges shown by another plot
+///
+/// Give the tool the same `Range1d` as the `x_range` or `y_range` of the detail plot, and add it to
+/// an overview plot of the same data: dragging or resizing the box on the overview then changes
+/// the range, and so the view of the detail plot.
+#[derive(Clone, Default)]
+pub struct RangeTool {
+    id: Id,
+    /// Horizontal range controlled by the tool
+    pub x_range: Option<Range1d>,
+    /// Vertical range controlled by the tool
+    pub y_range: Option<Range1d>,
+    /// Whether the box can be dragged horizontally
+    pub x_interaction: Option<bool>,
+    /// Whether the box can be dragged vertically
+    pub y_interaction: Option<bool>,
+}
+
+impl RangeTool {
+    /// Create a new range tool
+    pub fn new() -> Self {
+        RangeTool::default()
+    }
+
+    /// Create a new range tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        RangeTool {
+            id,
+            ..RangeTool::default()
+        }
+    }
+}
+
+impl From<RangeTool> for Drag {
+    fn from(t: RangeTool) -> Drag {
+        Drag::RangeTool(Box::new(t))
+    }
+}
+
+impl From<RangeTool> for Tool {
+    fn from(t: RangeTool) -> Tool {
+        Tool::Drag(t.into())
+    }
+}
+
+impl ToBokeh for RangeTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(x_range) = &self.x_range {
+            attributes["x_range"] = x_range.as_bokeh_ref();
+        }
+        if let Some(y_range) = &self.y_range {
+            attributes["y_range"] = y_range.as_bokeh_ref();
+        }
+        if let Some(x_interaction) = self.x_interaction {
+            attributes["x_interaction"] = json!(x_interaction);
+        }
+        if let Some(y_interaction) = self.y_interaction {
+            attributes["y_interaction"] = json!(y_interaction);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "RangeTool",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        for range in self.x_range.iter().chain(&self.y_range) {
+            out.extend(range.references());
+        }
+        out
+    }
+}
+
+/// Where a plot's toolbar is placed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToolbarLocation {
+    /// Above the plot
+    Above,
+    /// Below the plot
+    Below,
+    /// Left of the plot
+    Left,
+    /// Right of the plot
+    Right,
+    /// Not shown at all
+    Hidden,
+}
+
+impl ToolbarLocation {
+    pub(crate) fn value(self) -> Value {
+        match self {
+            ToolbarLocation::Above => json!("above"),
+            ToolbarLocation::Below => json!("below"),
+            ToolbarLocation::Left => json!("left"),
+            ToolbarLocation::Right => json!("right"),
+            ToolbarLocation::Hidden => Value::Null,
+        }
+    }
+}
+
+/// Style of the Bokeh logo on a toolbar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Logo {
+    /// Coloured logo
+    Normal,
+    /// Greyscale logo
+    Grey,
+    /// No logo
+    Hidden,
+}
+
+impl Logo {
+    fn value(self) -> Value {
+        match self {
+            Logo::Normal => json!("normal"),
+            Logo::Grey => json!("grey"),
+            Logo::Hidden => Value::Null,
+        }
+    }
+}
+
+/// Open a page of documentation about the plot
+#[derive(Clone, Default)]
+pub struct HelpTool {
+    id: Id,
+    /// Description shown when hovering over the tool
+    pub help_tooltip: Option<String>,
+    /// Address of the page opened by clicking the tool
+    pub redirect: Option<String>,
+}
+
+impl HelpTool {
+    /// Create a new help tool
+    pub fn new() -> Self {
+        HelpTool::default()
+    }
+
+    /// Create a new help tool with a known id
+    pub fn with_id(id: Id) -> Self {
+        HelpTool {
+            id,
+            ..HelpTool::default()
+        }
+    }
+}
+
+impl From<HelpTool> for Action {
+    fn from(t: HelpTool) -> Action {
+        Action::HelpTool(t)
+    }
+}
+
+impl From<HelpTool> for Tool {
+    fn from(t: HelpTool) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for HelpTool {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(help_tooltip) = &self.help_tooltip {
+            attributes["help_tooltip"] = json!(help_tooltip);
+        }
+        if let Some(redirect) = &self.redirect {
+            attributes["redirect"] = json!(redirect);
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "HelpTool",
+        })
+    }
+}
+
+/// Button on the toolbar running its callback when clicked
+///
+/// Dashboards use these for actions of their own alongside the built in tools, such as a
+/// `CustomJS` callback clearing a selection.
+#[derive(Clone, Default)]
+pub struct CustomAction {
+    id: Id,
+    /// Description shown when hovering over the tool
+    pub action_tooltip: Option<String>,
+    /// Icon of the button, as the data URL of an image, e.g. `data:image/png;base64,...`
+    pub icon: Option<String>,
+    /// Callback run when the button is clicked
+    pub callback: Option<Callback>,
+}
+
+impl CustomAction {
+    /// Create a new custom action running `callback`
+    pub fn new<C>(callback: C) -> Self
+    where
+        C: Into<Callback>,
+    {
+        CustomAction {
+            callback: Some(callback.into()),
+            ..CustomAction::default()
+        }
+    }
+
+    /// Create a new custom action running `callback`, with a known id
+    pub fn with_id<C>(id: Id, callback: C) -> Self
+    where
+        C: Into<Callback>,
+    {
+        CustomAction {
+            id,
+            callback: Some(callback.into()),
+            ..CustomAction::default()
+        }
+    }
+}
+
+impl From<CustomAction> for Action {
+    fn from(t: CustomAction) -> Action {
+        Action::CustomAction(t)
+    }
+}
+
+impl From<CustomAction> for Tool {
+    fn from(t: CustomAction) -> Tool {
+        Tool::Action(t.into())
+    }
+}
+
+impl ToBokeh for CustomAction {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({});
+        if let Some(action_tooltip) = &self.action_tooltip {
+            attributes["action_tooltip"] = json!(action_tooltip);
+        }
+        if let Some(icon) = &self.icon {
+            attributes["icon"] = json!(icon);
+        }
+        if let Some(callback) = &self.callback {
+            attributes["callback"] = callback.as_bokeh_ref();
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "CustomAction",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        if let Some(callback) = &self.callback {
+            out.extend(callback.references());
+        }
+        out
+    }
+}
+
+/// Tool of a category which is active when the plot is shown
+///
+/// The category is part of the type, so only a drag tool can be the active drag tool.
+#[derive(Clone)]
+pub enum ActiveTool<T> {
+    /// The first tool of the category on the toolbar
+    Auto,
+    /// The given tool, which must also be on the toolbar
+    Tool(T),
+    /// No tool, until one is picked on the toolbar
+    Disabled,
+}
+
+impl<T> ActiveTool<T>
+where
+    T: ToBokeh,
+{
+    fn value(&self) -> Value {
+        match self {
+            ActiveTool::Auto => json!("auto"),
+            ActiveTool::Tool(tool) => tool.as_bokeh_ref(),
+            ActiveTool::Disabled => Value::Null,
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            ActiveTool::Tool(tool) => tool.references(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl<T> From<T> for ActiveTool<Drag>
+where
+    T: Into<Drag>,
+{
+    fn from(tool: T) -> Self {
+        ActiveTool::Tool(tool.into())
+    }
+}
+
+impl<T> From<T> for ActiveTool<Scroll>
+where
+    T: Into<Scroll>,
+{
+    fn from(tool: T) -> Self {
+        ActiveTool::Tool(tool.into())
+    }
+}
+
+impl<T> From<T> for ActiveTool<Tap>
+where
+    T: Into<Tap>,
+{
+    fn from(tool: T) -> Self {
+        ActiveTool::Tool(tool.into())
+    }
+}
+
+impl<T> From<T> for ActiveTool<Inspection>
+where
+    T: Into<Inspection>,
+{
+    fn from(tool: T) -> Self {
+        ActiveTool::Tool(tool.into())
+    }
+}
+
+/// Holds the tools of a plot
+///
+/// The active tool settings refer to tools by id, so set them to copies of tools added to the
+/// toolbar, e.g. `toolbar.active_scroll = Some(wheel_zoom.clone().into())`.
+#[derive(Clone, Default)]
+pub struct Toolbar {
+    id: Id,
+    tools: Vec<Tool>,
+    /// Whether to only show the toolbar while the mouse is over the plot
+    pub autohide: Option<bool>,
+    /// Style of the Bokeh logo
+    pub logo: Option<Logo>,
+    /// Active drag tool, e.g. a pan or box zoom tool
+    pub active_drag: Option<ActiveTool<Drag>>,
+    /// Active scroll tool, e.g. a wheel zoom tool
+    pub active_scroll: Option<ActiveTool<Scroll>>,
+    /// Active inspection tool, e.g. a hover tool
+    pub active_inspect: Option<ActiveTool<Inspection>>,
+    /// Active tap tool
+    pub active_tap: Option<ActiveTool<Tap>>,
+}
+
+impl Toolbar {
+    /// Create a new empty toolbar
+    pub fn new() -> Self {
+        Toolbar::default()
+    }
+
+    /// Create a new empty toolbar with a known id
+    pub fn with_id(id: Id) -> Self {
+        Toolbar {
+            id,
+            ..Toolbar::default()
+        }
+    }
+
+    /// Add a tool to the toolbar
+    pub fn add_tool<T>(&mut self, tool: T)
+    where
+        T: Into<Tool>,
+    {
+        self.tools.push(tool.into());
+    }
+
+    /// Tools on the toolbar
+    pub fn tools(&self) -> &[Tool] {
+        &self.tools
+    }
+
+    /// Check that every active tool is on the toolbar
+    pub(crate) fn validate(&self) -> Result<()> {
+        let tools: Vec<Value> = self.tools.iter().map(|t| t.as_bokeh_ref()).collect();
+        for (name, value, _) in self.active_tools() {
+            if value.is_object() && !tools.contains(&value) {
+                return Err(format_err!("{} tool is not on the toolbar", name));
+            }
+        }
+        Ok(())
+    }
+
+    // Active tool settings which are set, with their attribute names, values and references
+    fn active_tools(&self) -> Vec<(&'static str, Value, Vec<Value>)> {
+        let active = vec![
+            (
+                "active_drag",
+                self.active_drag
+                    .as_ref()
+                    .map(|a| (a.value(), a.references())),
+            ),
+            (
+                "active_inspect",
+                self.active_inspect
+                    .as_ref()
+                    .map(|a| (a.value(), a.references())),
+            ),
+            (
+                "active_scroll",
+                self.active_scroll
+                    .as_ref()
+                    .map(|a| (a.value(), a.references())),
+            ),
+            (
+                "active_tap",
+                self.active_tap
+                    .as_ref()
+                    .map(|a| (a.value(), a.references())),
+            ),
+        ];
+        active
+            .into_iter()
+            .filter_map(|(name, active)| {
+                active.map(|(value, references)| (name, value, references))
+            })
+            .collect()
+    }
+}
+
+impl ToBokeh for Toolbar {
+    fn as_bokeh_value(&self) -> Value {
+        let tools: Vec<Value> = self.tools.iter().map(|t| t.as_bokeh_ref()).collect();
+        let mut attributes = json!({
+            "tools": tools,
+        });
+        if let Some(autohide) = self.autohide {
+            attributes["autohide"] = json!(autohide);
+        }
+        if let Some(logo) = self.logo {
+            attributes["logo"] = logo.value();
+        }
+        for (name, value, _) in self.active_tools() {
+            attributes[name] = value;
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Toolbar",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.tools.iter().flat_map(|t| t.references()));
+        for (_, _, references) in self.active_tools() {
+            out.extend(references);
+        }
+        out
+    }
+}
+
+/// Toolbar controlling the tools of several plots at once
+///
+/// BokehJS merges tools of the same kind and settings from the different plots, so a single pan
+/// tool pans every plot.
+#[derive(Clone, Default)]
+pub struct ProxyToolbar {
+    id: Id,
+    tools: Vec<Tool>,
+    /// Whether to only show the toolbar while the mouse is over it
+    pub autohide: Option<bool>,
+    /// Style of the Bokeh logo
+    pub logo: Option<Logo>,
+}
+
+impl ProxyToolbar {
+    /// Create a new empty toolbar
+    pub fn new() -> Self {
+        ProxyToolbar::default()
+    }
+
+    /// Create a new empty toolbar with a known id
+    pub fn with_id(id: Id) -> Self {
+        ProxyToolbar {
+            id,
+            ..ProxyToolbar::default()
+        }
+    }
+
+    /// Add a tool to the toolbar
+    pub fn add_tool<T>(&mut self, tool: T)
+    where
+        T: Into<Tool>,
+    {
+        self.tools.push(tool.into());
+    }
+
+    /// Tools on the toolbar
+    pub fn tools(&self) -> &[Tool] {
+        &self.tools
+    }
+}
+
+impl ToBokeh for ProxyToolbar {
+    fn as_bokeh_value(&self) -> Value {
+        let tools: Vec<Value> = self.tools.iter().map(|t| t.as_bokeh_ref()).collect();
+        let mut attributes = json!({
+            "tools": tools,
+        });
+        if let Some(autohide) = self.autohide {
+            attributes["autohide"] = json!(autohide);
+        }
+        if let Some(logo) = self.logo {
+            attributes["logo"] = logo.value();
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "ProxyToolbar",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.tools.iter().flat_map(|t| t.references()));
+        out
+    }
+}
+
+/// Layout item showing a toolbar shared by several plots, e.g. the plots of a grid
+#[derive(Clone, Default)]
+pub struct ToolbarBox {
+    id: Id,
+    /// Toolbar shown
+    pub toolbar: ProxyToolbar,
+    /// Side of the plots the toolbar is placed on, to the right if not set
+    pub toolbar_location: Option<ToolbarLocation>,
+    /// How the toolbar box is sized, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+}
+
+impl ToolbarBox {
+    /// Create a new toolbar box with an empty toolbar
+    pub fn new() -> Self {
+        ToolbarBox::default()
+    }
+
+    /// Create a new toolbar box with an empty toolbar and a known id
+    pub fn with_id(id: Id) -> Self {
+        ToolbarBox {
+            id,
+            ..ToolbarBox::default()
+        }
+    }
+}
+
+impl ToBokeh for ToolbarBox {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "toolbar": self.toolbar.as_bokeh_ref(),
+        });
+        if let Some(toolbar_location) = self.toolbar_location {
+            attributes["toolbar_location"] = toolbar_location.value();
+        }
+        if let Some(sizing_mode) = self.sizing_mode {
+            attributes["sizing_mode"] = json!(sizing_mode.name());
+        }
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "ToolbarBox",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.toolbar.references());
+        out
+    }
+}
+
+/// Move the tools of `plots` onto one toolbar, hiding the plots' own toolbars
+///
+/// This matches `gridplot(..., merge_tools=True)` from Python. Place the returned toolbar box
+/// alongside the plots in their layout.
+pub fn merge_tools<'a, I>(plots: I) -> ToolbarBox
+where
+    I: IntoIterator<Item = &'a mut Plot>,
+{
+    let mut toolbar_box = ToolbarBox::new();
+    for plot in plots {
+        toolbar_box
+            .toolbar
+            .tools
+            .extend(plot.toolbar.tools().iter().cloned());
+        plot.toolbar_location = Some(ToolbarLocation::Hidden);
+    }
+    toolbar_box
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hover_tool() {
+        let mut tool = HoverTool::with_id(Id::from("1001"));
+        assert_eq!(
+            tool.as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "HoverTool"})
+        );
+
+        tool.add_tooltip("index", "$index");
+        tool.add_tooltip("(x, y)", "(@x, @y)");
+        tool.mode = Some(HoverMode::VLine);
+        assert_eq!(
+            tool.as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "mode": "vline",
+                    "tooltips": [["index", "$index"], ["(x, y)", "(@x, @y)"]],
+                },
+                "id": "1001",
+                "type": "HoverTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_hover_tool_html_and_formatters() {
+        let mut tool = HoverTool::with_id(Id::from("1001"));
+        tool.set_tooltip_html("<b>@date{%F}</b>: @price{$0.00}");
+        tool.add_formatter("@date", TooltipFormatter::Datetime);
+        tool.add_formatter("@price", TooltipFormatter::Numeral);
+        tool.add_formatter(
+            "@name",
+            CustomJSHover::with_id(Id::from("1002"), "return value.toUpperCase()"),
+        );
+
+        let references = Tool::from(tool).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "formatters": {
+                        "@date": "datetime",
+                        "@name": {"id": "1002", "type": "CustomJSHover"},
+                        "@price": "numeral",
+                    },
+                    "tooltips": "<b>@date{%F}</b>: @price{$0.00}",
+                },
+                "id": "1001",
+                "type": "HoverTool",
+            })
+        );
+        assert_eq!(
+            references[1],
+            json!({
+                "attributes": {"code": "return value.toUpperCase()"},
+                "id": "1002",
+                "type": "CustomJSHover",
+            })
+        );
+    }
+
+    #[test]
+    fn test_box_zoom_tool() {
+        let mut tool = BoxZoomTool::with_id(Id::from("1001"));
+        tool.dimensions = Some(Dimensions::Width);
+        tool.match_aspect = Some(true);
+        assert_eq!(
+            tool.as_bokeh_value(),
+            json!({
+                "attributes": {"dimensions": "width", "match_aspect": true},
+                "id": "1001",
+                "type": "BoxZoomTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_box_select_tool() {
+        let mut tool = BoxSelectTool::with_id(Id::from("1001"));
+        tool.dimensions = Some(Dimensions::Both);
+        tool.select_every_mousemove = Some(true);
+        assert_eq!(
+            tool.as_bokeh_value(),
+            json!({
+                "attributes": {"dimensions": "both", "select_every_mousemove": true},
+                "id": "1001",
+                "type": "BoxSelectTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_lasso_select_tool() {
+        let mut tool = LassoSelectTool::with_id(Id::from("1001"));
+        tool.continuous = Some(false);
+        assert_eq!(
+            tool.as_bokeh_value(),
+            json!({
+                "attributes": {"select_every_mousemove": false},
+                "id": "1001",
+                "type": "LassoSelectTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_poly_select_tool() {
+        let tool = PolySelectTool::with_id(Id::from("1001"));
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "PolySelectTool"})
+        );
+    }
+
+    #[test]
+    fn test_tap_tool() {
+        let mut tool = TapTool::with_id(Id::from("1001"));
+        tool.behavior = Some(TapBehavior::Inspect);
+        tool.callback = Some(crate::OpenURL::with_id(Id::from("1002"), "/items/@id").into());
+
+        let references = Tool::from(tool).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "behavior": "inspect",
+                    "callback": {"id": "1002", "type": "OpenURL"},
+                },
+                "id": "1001",
+                "type": "TapTool",
+            })
+        );
+        assert_eq!(references[1]["type"], "OpenURL");
+    }
+
+    #[test]
+    fn test_crosshair_tool() {
+        let mut tool = CrosshairTool::with_id(Id::from("1001"));
+        tool.dimensions = Some(Dimensions::Height);
+        tool.line_color = Some("red".to_string());
+        tool.line_alpha = Some(0.5);
+        tool.line_width = Some(2.0);
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "dimensions": "height",
+                    "line_alpha": 0.5,
+                    "line_color": "red",
+                    "line_width": 2.0,
+                },
+                "id": "1001",
+                "type": "CrosshairTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_save_tool() {
+        assert_eq!(
+            Tool::from(SaveTool::with_id(Id::from("1001"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "SaveTool"})
+        );
+    }
+
+    #[test]
+    fn test_reset_tool() {
+        assert_eq!(
+            Tool::from(ResetTool::with_id(Id::from("1001"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "ResetTool"})
+        );
+    }
+
+    #[test]
+    fn test_undo_redo_tools() {
+        assert_eq!(
+            Tool::from(UndoTool::with_id(Id::from("1001"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1001", "type": "UndoTool"})
+        );
+        assert_eq!(
+            Tool::from(RedoTool::with_id(Id::from("1002"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1002", "type": "RedoTool"})
+        );
+    }
+
+    #[test]
+    fn test_zoom_in_out_tools() {
+        let mut zoom_in = ZoomInTool::with_id(Id::from("1001"));
+        zoom_in.factor = Some(0.25);
+        zoom_in.dimensions = Some(Dimensions::Width);
+        assert_eq!(
+            Tool::from(zoom_in).as_bokeh_value(),
+            json!({
+                "attributes": {"dimensions": "width", "factor": 0.25},
+                "id": "1001",
+                "type": "ZoomInTool",
+            })
+        );
+
+        assert_eq!(
+            Tool::from(ZoomOutTool::with_id(Id::from("1002"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1002", "type": "ZoomOutTool"})
+        );
+    }
+
+    #[test]
+    fn test_wheel_zoom_tool() {
+        let mut tool = WheelZoomTool::with_id(Id::from("1001"));
+        tool.dimensions = Some(Dimensions::Height);
+        tool.maintain_focus = Some(false);
+        tool.speed = Some(0.005);
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({
+                "attributes": {"dimensions": "height", "maintain_focus": false, "speed": 0.005},
+                "id": "1001",
+                "type": "WheelZoomTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_wheel_pan_tool() {
+        let mut tool = WheelPanTool::with_id(Id::from("1001"));
+        tool.dimension = Some(Dimension::Height);
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({
+                "attributes": {"dimension": "height"},
+                "id": "1001",
+                "type": "WheelPanTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_point_draw_tool() {
+        let mut plot = crate::Plot::new();
+        let renderer = plot.add_glyph(&crate::ColumnDataSource::new(), crate::Circle::new());
+
+        let mut tool = PointDrawTool::with_id(Id::from("1001"));
+        tool.add_renderer(&renderer);
+        tool.drag = Some(false);
+        tool.empty_value = Some(0.0);
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "drag": false,
+                    "empty_value": 0.0,
+                    "renderers": [{"id": renderer, "type": "GlyphRenderer"}],
+                },
+                "id": "1001",
+                "type": "PointDrawTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_shape_edit_tools() {
+        let renderer = Id::from("1002");
+
+        let mut box_edit = BoxEditTool::with_id(Id::from("1001"));
+        box_edit.add_renderer(&renderer);
+        box_edit.dimensions = Some(Dimensions::Width);
+        assert_eq!(
+            Tool::from(box_edit).as_bokeh_value()["attributes"],
+            json!({
+                "dimensions": "width",
+                "renderers": [{"id": "1002", "type": "GlyphRenderer"}],
+            })
+        );
+
+        let mut poly_draw = PolyDrawTool::with_id(Id::from("1003"));
+        poly_draw.add_renderer(&renderer);
+        assert_eq!(
+            Tool::from(poly_draw).as_bokeh_value()["attributes"]["renderers"],
+            json!([{"id": "1002", "type": "GlyphRenderer"}])
+        );
+
+        assert_eq!(
+            Tool::from(FreehandDrawTool::with_id(Id::from("1004"))).as_bokeh_value(),
+            json!({"attributes": {}, "id": "1004", "type": "FreehandDrawTool"})
+        );
+    }
+
+    #[test]
+    fn test_range_tool() {
+        let mut tool = RangeTool::with_id(Id::from("1001"));
+        tool.x_range = Some(Range1d::with_id(Id::from("1002"), 0.0, 10.0));
+        tool.y_interaction = Some(false);
+
+        let references = Tool::from(tool).references();
+        assert_eq!(
+            references,
+            vec![
+                json!({
+                    "attributes": {
+                        "x_range": {"id": "1002", "type": "Range1d"},
+                        "y_interaction": false,
+                    },
+                    "id": "1001",
+                    "type": "RangeTool",
+                }),
+                json!({
+                    "attributes": {"end": 10.0, "start": 0.0},
+                    "id": "1002",
+                    "type": "Range1d",
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toolbar() {
+        let mut toolbar = Toolbar::with_id(Id::from("1001"));
+        toolbar.add_tool(PanTool::with_id(Id::from("1002")));
+        toolbar.autohide = Some(true);
+        toolbar.logo = Some(Logo::Hidden);
+        assert_eq!(
+            toolbar.as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "autohide": true,
+                    "logo": null,
+                    "tools": [{"id": "1002", "type": "PanTool"}],
+                },
+                "id": "1001",
+                "type": "Toolbar",
+            })
+        );
+        assert_eq!(toolbar.references().len(), 2);
+    }
+
+    #[test]
+    fn test_toolbar_active_tools() {
+        let wheel_zoom = WheelZoomTool::with_id(Id::from("1002"));
+        let mut toolbar = Toolbar::with_id(Id::from("1001"));
+        toolbar.add_tool(wheel_zoom.clone());
+        toolbar.active_scroll = Some(wheel_zoom.into());
+        toolbar.active_drag = Some(ActiveTool::Disabled);
+        toolbar.active_inspect = Some(ActiveTool::Auto);
+        assert_eq!(
+            toolbar.as_bokeh_value()["attributes"],
+            json!({
+                "active_drag": null,
+                "active_inspect": "auto",
+                "active_scroll": {"id": "1002", "type": "WheelZoomTool"},
+                "tools": [{"id": "1002", "type": "WheelZoomTool"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_pan_tool() {
+        let mut tool = PanTool::with_id(Id::from("1001"));
+        tool.dimensions = Some(Dimensions::Width);
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({
+                "attributes": {"dimensions": "width"},
+                "id": "1001",
+                "type": "PanTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_tool_renderers() {
+        let source = crate::ColumnDataSource::new();
+        let mut plot = crate::Plot::new();
+        let points = plot.add_glyph(&source, crate::Circle::new());
+        plot.add_glyph(&source, crate::Circle::new());
+
+        let mut hover = HoverTool::with_id(Id::from("1001"));
+        hover.add_renderer(&points);
+        assert_eq!(
+            Tool::from(hover).as_bokeh_value()["attributes"],
+            json!({"renderers": [{"id": points, "type": "GlyphRenderer"}]})
+        );
+
+        let mut tap = TapTool::new();
+        tap.add_renderer(&points);
+        assert_eq!(
+            Tool::from(tap).as_bokeh_value()["attributes"]["renderers"],
+            json!([{"id": points, "type": "GlyphRenderer"}])
+        );
+
+        let mut select = PolySelectTool::new();
+        select.add_renderer(&points);
+        assert_eq!(
+            Tool::from(select).as_bokeh_value()["attributes"]["renderers"],
+            json!([{"id": points, "type": "GlyphRenderer"}])
+        );
+    }
+
+    #[test]
+    fn test_help_tool() {
+        let mut tool = HelpTool::with_id(Id::from("1001"));
+        tool.help_tooltip = Some("About this dashboard".to_string());
+        tool.redirect = Some("https://example.com/docs".to_string());
+        assert_eq!(
+            Tool::from(tool).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "help_tooltip": "About this dashboard",
+                    "redirect": "https://example.com/docs",
+                },
+                "id": "1001",
+                "type": "HelpTool",
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_action() {
+        let callback = crate::CustomJS::with_id(Id::from("1002"), "source.selected.indices = []");
+        let mut tool = CustomAction::with_id(Id::from("1001"), callback);
+        tool.action_tooltip = Some("Clear selection".to_string());
+        tool.icon = Some("data:image/png;base64,iVBORw0KGgo=".to_string());
+
+        let references = Tool::from(tool).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "action_tooltip": "Clear selection",
+                    "callback": {"id": "1002", "type": "CustomJS"},
+                    "icon": "data:image/png;base64,iVBORw0KGgo=",
+                },
+                "id": "1001",
+                "type": "CustomAction",
+            })
+        );
+        assert_eq!(references[1]["type"], "CustomJS");
+    }
+
+    #[test]
+    fn test_active_tool_must_be_on_toolbar() {
+        let mut toolbar = Toolbar::new();
+        toolbar.add_tool(PanTool::new());
+        toolbar.active_drag = Some(ActiveTool::Auto);
+        toolbar.active_scroll = Some(WheelZoomTool::new().into());
+        assert!(toolbar.validate().is_err());
+
+        let wheel_zoom = WheelZoomTool::new();
+        toolbar.add_tool(wheel_zoom.clone());
+        toolbar.active_scroll = Some(wheel_zoom.into());
+        assert!(toolbar.validate().is_ok());
+    }
+
+    #[test]
+    fn test_merge_tools() {
+        let mut first = Plot::new();
+        first.add_tool(PanTool::with_id(Id::from("1001")));
+        let mut second = Plot::new();
+        second.add_tool(PanTool::with_id(Id::from("1002")));
+        second.add_tool(HoverTool::with_id(Id::from("1003")));
+
+        let mut toolbar_box = merge_tools(vec![&mut first, &mut second]);
+        toolbar_box.toolbar.logo = Some(Logo::Grey);
+        assert_eq!(first.toolbar_location, Some(ToolbarLocation::Hidden));
+        assert_eq!(second.toolbar_location, Some(ToolbarLocation::Hidden));
+
+        let references = toolbar_box.references();
+        assert_eq!(
+            references[1]["attributes"],
+            json!({
+                "logo": "grey",
+                "tools": [
+                    {"id": "1001", "type": "PanTool"},
+                    {"id": "1002", "type": "PanTool"},
+                    {"id": "1003", "type": "HoverTool"},
+                ],
+            })
+        );
+        assert_eq!(references[0]["type"], "ToolbarBox");
+        assert_eq!(references[1]["type"], "ProxyToolbar");
+        assert_eq!(references.len(), 5);
+    }
+}
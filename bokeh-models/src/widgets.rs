@@ -0,0 +1,2310 @@
+//! Widgets, interactive controls laid out alongside plots
+
+use crate::callbacks::JsCallbacks;
+use crate::layouts::{LayoutDom, SizingMode};
+use crate::{Callback, ColumnDataSource, CustomJS, DataTable, Id, Result, ToBokeh};
+use failure::format_err;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Interactive control, which can be placed in a layout or be the root of a document
+#[derive(Clone)]
+pub enum Widget {
+    /// Slider choosing a single number
+    Slider(Slider),
+    /// Slider choosing a range of numbers
+    RangeSlider(RangeSlider),
+    /// Slider choosing a single date
+    DateSlider(DateSlider),
+    /// Slider choosing a range of dates
+    DateRangeSlider(DateRangeSlider),
+    /// Drop-down list choosing one option
+    Select(Select),
+    /// List choosing several options
+    MultiSelect(MultiSelect),
+    /// Button
+    Button(Button),
+    /// Button staying pressed in until clicked again
+    Toggle(Toggle),
+    /// Button opening a menu
+    Dropdown(Dropdown),
+    /// Group of checkboxes
+    CheckboxGroup(CheckboxGroup),
+    /// Group of radio buttons
+    RadioGroup(RadioGroup),
+    /// Row of buttons working as checkboxes
+    CheckboxButtonGroup(CheckboxButtonGroup),
+    /// Row of buttons working as radio buttons
+    RadioButtonGroup(RadioButtonGroup),
+    /// Single line of free text
+    TextInput(TextInput),
+    /// Calendar choosing a date
+    DatePicker(DatePicker),
+    /// Block of HTML
+    Div(Div),
+    /// Paragraph of text
+    Paragraph(Paragraph),
+    /// Block of preformatted text
+    PreText(PreText),
+    /// Table of the rows of a data source
+    DataTable(DataTable),
+}
+
+impl Widget {
+    pub(crate) fn properties_mut(&mut self) -> &mut WidgetProperties {
+        match self {
+            Widget::Slider(w) => &mut w.properties.widget,
+            Widget::RangeSlider(w) => &mut w.properties.widget,
+            Widget::DateSlider(w) => &mut w.properties.widget,
+            Widget::DateRangeSlider(w) => &mut w.properties.widget,
+            Widget::Select(w) => &mut w.properties.widget,
+            Widget::MultiSelect(w) => &mut w.properties.widget,
+            Widget::Button(w) => &mut w.properties.widget,
+            Widget::Toggle(w) => &mut w.properties.widget,
+            Widget::Dropdown(w) => &mut w.properties.widget,
+            Widget::CheckboxGroup(w) => &mut w.properties.widget,
+            Widget::RadioGroup(w) => &mut w.properties.widget,
+            Widget::CheckboxButtonGroup(w) => &mut w.properties.widget,
+            Widget::RadioButtonGroup(w) => &mut w.properties.widget,
+            Widget::TextInput(w) => &mut w.properties.widget,
+            Widget::DatePicker(w) => &mut w.properties.widget,
+            Widget::Div(w) => &mut w.properties.widget,
+            Widget::Paragraph(w) => &mut w.properties.widget,
+            Widget::PreText(w) => &mut w.properties.widget,
+            Widget::DataTable(w) => &mut w.properties,
+        }
+    }
+
+    // Data source shown by the widget, if it shows one
+    pub(crate) fn source_mut(&mut self) -> Option<&mut ColumnDataSource> {
+        match self {
+            Widget::DataTable(w) => Some(&mut w.source),
+            _ => None,
+        }
+    }
+
+    /// Run `callback` in the browser whenever the property `attr` of the widget changes
+    pub fn js_on_change<S>(&mut self, attr: S, callback: CustomJS)
+    where
+        S: AsRef<str>,
+    {
+        self.properties_mut().js_on_change(attr, callback);
+    }
+
+    /// Keep the property `target_attr` of `target` equal to the property `attr` of the widget
+    pub fn js_link<S, T, M>(&mut self, attr: S, target: &M, target_attr: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        M: ToBokeh,
+    {
+        self.properties_mut().js_link(attr, target, target_attr);
+    }
+}
+
+impl From<Widget> for LayoutDom {
+    fn from(w: Widget) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w))
+    }
+}
+
+impl ToBokeh for Widget {
+    fn as_bokeh_value(&self) -> Value {
+        match self {
+            Widget::Slider(w) => w.as_bokeh_value(),
+            Widget::RangeSlider(w) => w.as_bokeh_value(),
+            Widget::DateSlider(w) => w.as_bokeh_value(),
+            Widget::DateRangeSlider(w) => w.as_bokeh_value(),
+            Widget::Select(w) => w.as_bokeh_value(),
+            Widget::MultiSelect(w) => w.as_bokeh_value(),
+            Widget::Button(w) => w.as_bokeh_value(),
+            Widget::Toggle(w) => w.as_bokeh_value(),
+            Widget::Dropdown(w) => w.as_bokeh_value(),
+            Widget::CheckboxGroup(w) => w.as_bokeh_value(),
+            Widget::RadioGroup(w) => w.as_bokeh_value(),
+            Widget::CheckboxButtonGroup(w) => w.as_bokeh_value(),
+            Widget::RadioButtonGroup(w) => w.as_bokeh_value(),
+            Widget::TextInput(w) => w.as_bokeh_value(),
+            Widget::DatePicker(w) => w.as_bokeh_value(),
+            Widget::Div(w) => w.as_bokeh_value(),
+            Widget::Paragraph(w) => w.as_bokeh_value(),
+            Widget::PreText(w) => w.as_bokeh_value(),
+            Widget::DataTable(w) => w.as_bokeh_value(),
+        }
+    }
+
+    fn references(&self) -> Vec<Value> {
+        match self {
+            Widget::Slider(w) => w.references(),
+            Widget::RangeSlider(w) => w.references(),
+            Widget::DateSlider(w) => w.references(),
+            Widget::DateRangeSlider(w) => w.references(),
+            Widget::Select(w) => w.references(),
+            Widget::MultiSelect(w) => w.references(),
+            Widget::Button(w) => w.references(),
+            Widget::Toggle(w) => w.references(),
+            Widget::Dropdown(w) => w.references(),
+            Widget::CheckboxGroup(w) => w.references(),
+            Widget::RadioGroup(w) => w.references(),
+            Widget::CheckboxButtonGroup(w) => w.references(),
+            Widget::RadioButtonGroup(w) => w.references(),
+            Widget::TextInput(w) => w.references(),
+            Widget::DatePicker(w) => w.references(),
+            Widget::Div(w) => w.references(),
+            Widget::Paragraph(w) => w.references(),
+            Widget::PreText(w) => w.references(),
+            Widget::DataTable(w) => w.references(),
+        }
+    }
+}
+
+/// Properties shared by every kind of widget
+#[derive(Clone, Debug, Default)]
+pub struct WidgetProperties {
+    /// Width of the widget in pixels
+    pub width: Option<u32>,
+    /// Height of the widget in pixels
+    pub height: Option<u32>,
+    /// How the widget is sized, fixed if not set
+    pub sizing_mode: Option<SizingMode>,
+    /// Whether the widget is greyed out and ignores input, false if not set
+    pub disabled: Option<bool>,
+    /// CSS classes added to the widget's element, for styling from the page
+    pub css_classes: Vec<String>,
+    js_callbacks: JsCallbacks,
+}
+
+impl WidgetProperties {
+    pub(crate) fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(width) = self.width {
+            attributes["width"] = json!(width);
+        }
+        if let Some(height) = self.height {
+            attributes["height"] = json!(height);
+        }
+        if let Some(sizing_mode) = self.sizing_mode {
+            attributes["sizing_mode"] = json!(sizing_mode.name());
+        }
+        if let Some(disabled) = self.disabled {
+            attributes["disabled"] = json!(disabled);
+        }
+        if !self.css_classes.is_empty() {
+            attributes["css_classes"] = json!(self.css_classes);
+        }
+        self.js_callbacks.add_attributes(attributes);
+    }
+
+    pub(crate) fn references(&self) -> Vec<Value> {
+        self.js_callbacks.references()
+    }
+
+    /// Run `callback` in the browser whenever the property `attr` of the widget changes
+    ///
+    /// E.g. `"value"` for a slider, select or text input, or `"active"` for a group.
+    pub fn js_on_change<S>(&mut self, attr: S, callback: CustomJS)
+    where
+        S: AsRef<str>,
+    {
+        self.js_callbacks.add_change(attr.as_ref(), callback);
+    }
+
+    /// Keep the property `target_attr` of `target` equal to the property `attr` of the widget
+    ///
+    /// The JavaScript doing so is generated, e.g. linking the `"value"` of a slider to the
+    /// `"size"` of a glyph resizes the glyph as the slider moves. `target` must be part of the
+    /// document, such as a glyph or range of one of its plots.
+    pub fn js_link<S, T, M>(&mut self, attr: S, target: &M, target_attr: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        M: ToBokeh,
+    {
+        self.js_callbacks
+            .add_link(attr.as_ref(), target, target_attr.as_ref());
+    }
+}
+
+/// When a slider runs its callback while it is being dragged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallbackPolicy {
+    /// On every movement
+    Continuous,
+    /// At most once every `callback_throttle` milliseconds
+    Throttle,
+    /// Only once the slider is released
+    Mouseup,
+}
+
+impl CallbackPolicy {
+    fn name(self) -> &'static str {
+        match self {
+            CallbackPolicy::Continuous => "continuous",
+            CallbackPolicy::Throttle => "throttle",
+            CallbackPolicy::Mouseup => "mouseup",
+        }
+    }
+}
+
+/// Properties shared by every kind of slider
+#[derive(Clone, Default)]
+pub struct SliderProperties {
+    /// Title shown above the slider
+    pub title: Option<String>,
+    /// Format of the value shown, a Numeral.js format such as `0.0a` for numbers or a strftime
+    /// format for dates
+    pub format: Option<String>,
+    /// Whether the value is shown next to the title, true if not set
+    pub show_value: Option<bool>,
+    /// Whether the value is shown over the handle while it is dragged, true if not set
+    pub tooltips: Option<bool>,
+    /// Colour of the bar between the start and the handle
+    pub bar_color: Option<String>,
+    /// Callback run when the value changes
+    pub callback: Option<Callback>,
+    /// When the callback runs while the slider is dragged, throttled if not set
+    pub callback_policy: Option<CallbackPolicy>,
+    /// Time in milliseconds between throttled callbacks, 200 if not set
+    pub callback_throttle: Option<u32>,
+    /// Size and state of the widget
+    pub widget: WidgetProperties,
+}
+
+impl SliderProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(title) = &self.title {
+            attributes["title"] = json!(title);
+        }
+        if let Some(format) = &self.format {
+            attributes["format"] = json!(format);
+        }
+        if let Some(show_value) = self.show_value {
+            attributes["show_value"] = json!(show_value);
+        }
+        if let Some(tooltips) = self.tooltips {
+            attributes["tooltips"] = json!(tooltips);
+        }
+        if let Some(color) = &self.bar_color {
+            attributes["bar_color"] = json!(color);
+        }
+        if let Some(callback) = &self.callback {
+            attributes["callback"] = callback.as_bokeh_ref();
+        }
+        if let Some(policy) = self.callback_policy {
+            attributes["callback_policy"] = json!(policy.name());
+        }
+        if let Some(throttle) = self.callback_throttle {
+            attributes["callback_throttle"] = json!(throttle);
+        }
+        self.widget.add_attributes(attributes);
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out: Vec<Value> = self.callback.iter().flat_map(|c| c.references()).collect();
+        out.extend(self.widget.references());
+        out
+    }
+}
+
+// Slider
+
+/// Slider choosing a single number between `start` and `end`
+#[derive(Clone)]
+pub struct Slider {
+    id: Id,
+    /// Lowest value of the slider
+    pub start: f64,
+    /// Highest value of the slider
+    pub end: f64,
+    /// Current value of the slider
+    pub value: f64,
+    /// Distance between the values the slider stops at, 1 if not set
+    pub step: Option<f64>,
+    /// Title, format and callback of the slider
+    pub properties: SliderProperties,
+}
+
+impl Slider {
+    /// Create a new slider from `start` to `end` set to `value`
+    pub fn new(start: f64, end: f64, value: f64) -> Slider {
+        Slider::with_id(Id::new(), start, end, value)
+    }
+
+    /// Create a new slider from `start` to `end` set to `value` with a known id
+    pub fn with_id(id: Id, start: f64, end: f64, value: f64) -> Slider {
+        Slider {
+            id,
+            start,
+            end,
+            value,
+            step: None,
+            properties: SliderProperties::default(),
+        }
+    }
+}
+
+impl From<Slider> for Widget {
+    fn from(w: Slider) -> Widget {
+        Widget::Slider(w)
+    }
+}
+
+impl From<Slider> for LayoutDom {
+    fn from(w: Slider) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Slider {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "end": self.end,
+            "start": self.start,
+            "value": self.value,
+        });
+        if let Some(step) = self.step {
+            attributes["step"] = json!(step);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Slider",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// RangeSlider
+
+/// Slider choosing a range of numbers between `start` and `end`, with a handle at each end
+#[derive(Clone)]
+pub struct RangeSlider {
+    id: Id,
+    /// Lowest value of the slider
+    pub start: f64,
+    /// Highest value of the slider
+    pub end: f64,
+    /// Current range of the slider, as `(low, high)`
+    pub value: (f64, f64),
+    /// Distance between the values the handles stop at, 1 if not set
+    pub step: Option<f64>,
+    /// Title, format and callback of the slider
+    pub properties: SliderProperties,
+}
+
+impl RangeSlider {
+    /// Create a new slider from `start` to `end` set to the range `value`
+    pub fn new(start: f64, end: f64, value: (f64, f64)) -> RangeSlider {
+        RangeSlider::with_id(Id::new(), start, end, value)
+    }
+
+    /// Create a new slider from `start` to `end` set to the range `value` with a known id
+    pub fn with_id(id: Id, start: f64, end: f64, value: (f64, f64)) -> RangeSlider {
+        RangeSlider {
+            id,
+            start,
+            end,
+            value,
+            step: None,
+            properties: SliderProperties::default(),
+        }
+    }
+}
+
+impl From<RangeSlider> for Widget {
+    fn from(w: RangeSlider) -> Widget {
+        Widget::RangeSlider(w)
+    }
+}
+
+impl From<RangeSlider> for LayoutDom {
+    fn from(w: RangeSlider) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for RangeSlider {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "end": self.end,
+            "start": self.start,
+            "value": [self.value.0, self.value.1],
+        });
+        if let Some(step) = self.step {
+            attributes["step"] = json!(step);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "RangeSlider",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// Dates
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Date or time shown by a date widget, held as milliseconds since the epoch as on a
+/// `DatetimeAxis`
+///
+/// Dates can be made from milliseconds, parsed from ISO 8601 dates such as `2019-01-31`, or with
+/// the `chrono` feature converted from `NaiveDate` and `DateTime<Utc>`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Date(f64);
+
+impl Date {
+    /// Milliseconds since the epoch
+    pub fn millis(self) -> f64 {
+        self.0
+    }
+
+    /// ISO 8601 form of the day the date falls on
+    pub fn to_iso_date(self) -> String {
+        let (year, month, day) = civil_from_days((self.0 / MILLIS_PER_DAY).floor() as i64);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl From<f64> for Date {
+    fn from(millis: f64) -> Date {
+        Date(millis)
+    }
+}
+
+impl FromStr for Date {
+    type Err = failure::Error;
+
+    /// Parse an ISO 8601 date such as `2019-01-31`
+    fn from_str(s: &str) -> Result<Date> {
+        let invalid = || format_err!("invalid date `{}`, expected YYYY-MM-DD", s);
+        let parts: Vec<&str> = s.split('-').collect();
+        if parts.len() != 3
+            || [4, 2, 2]
+                .iter()
+                .zip(&parts)
+                .any(|(&len, part)| part.len() != len || !part.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return Err(invalid());
+        }
+
+        let year: i64 = parts[0].parse()?;
+        let month: u32 = parts[1].parse()?;
+        let day: u32 = parts[2].parse()?;
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return Err(invalid()),
+        };
+        if day < 1 || day > days_in_month {
+            return Err(invalid());
+        }
+        Ok(Date(
+            days_from_civil(year, month, day) as f64 * MILLIS_PER_DAY,
+        ))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for Date {
+    fn from(date: chrono::NaiveDate) -> Date {
+        Date(
+            date.and_time(chrono::NaiveTime::MIN)
+                .and_utc()
+                .timestamp_millis() as f64,
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Date {
+    fn from(datetime: chrono::DateTime<chrono::Utc>) -> Date {
+        Date(datetime.timestamp_millis() as f64)
+    }
+}
+
+// Conversions between days since the epoch and proleptic Gregorian dates, after
+// http://howardhinnant.github.io/date_algorithms.html
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((i64::from(month) + 9) % 12) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+// DateSlider
+
+/// Slider choosing a single date between `start` and `end`
+#[derive(Clone)]
+pub struct DateSlider {
+    id: Id,
+    /// Earliest date of the slider
+    pub start: Date,
+    /// Latest date of the slider
+    pub end: Date,
+    /// Current date of the slider
+    pub value: Date,
+    /// Number of days between the dates the slider stops at, 1 if not set
+    pub step: Option<u32>,
+    /// Title, format and callback of the slider, with dates shown as `%d %b %Y` if not set
+    pub properties: SliderProperties,
+}
+
+impl DateSlider {
+    /// Create a new slider from `start` to `end` set to `value`
+    pub fn new<D>(start: D, end: D, value: D) -> DateSlider
+    where
+        D: Into<Date>,
+    {
+        DateSlider::with_id(Id::new(), start, end, value)
+    }
+
+    /// Create a new slider from `start` to `end` set to `value` with a known id
+    pub fn with_id<D>(id: Id, start: D, end: D, value: D) -> DateSlider
+    where
+        D: Into<Date>,
+    {
+        DateSlider {
+            id,
+            start: start.into(),
+            end: end.into(),
+            value: value.into(),
+            step: None,
+            properties: SliderProperties::default(),
+        }
+    }
+}
+
+impl From<DateSlider> for Widget {
+    fn from(w: DateSlider) -> Widget {
+        Widget::DateSlider(w)
+    }
+}
+
+impl From<DateSlider> for LayoutDom {
+    fn from(w: DateSlider) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for DateSlider {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "end": self.end.millis(),
+            "start": self.start.millis(),
+            "value": self.value.millis(),
+        });
+        if let Some(step) = self.step {
+            attributes["step"] = json!(step);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "DateSlider",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// DateRangeSlider
+
+/// Slider choosing a range of dates between `start` and `end`
+#[derive(Clone)]
+pub struct DateRangeSlider {
+    id: Id,
+    /// Earliest date of the slider
+    pub start: Date,
+    /// Latest date of the slider
+    pub end: Date,
+    /// Current range of the slider, as `(low, high)`
+    pub value: (Date, Date),
+    /// Number of days between the dates the handles stop at, 1 if not set
+    pub step: Option<u32>,
+    /// Title, format and callback of the slider, with dates shown as `%d %b %Y` if not set
+    pub properties: SliderProperties,
+}
+
+impl DateRangeSlider {
+    /// Create a new slider from `start` to `end` set to the range `value`
+    pub fn new<D>(start: D, end: D, value: (D, D)) -> DateRangeSlider
+    where
+        D: Into<Date>,
+    {
+        DateRangeSlider::with_id(Id::new(), start, end, value)
+    }
+
+    /// Create a new slider from `start` to `end` set to the range `value` with a known id
+    pub fn with_id<D>(id: Id, start: D, end: D, value: (D, D)) -> DateRangeSlider
+    where
+        D: Into<Date>,
+    {
+        DateRangeSlider {
+            id,
+            start: start.into(),
+            end: end.into(),
+            value: (value.0.into(), value.1.into()),
+            step: None,
+            properties: SliderProperties::default(),
+        }
+    }
+}
+
+impl From<DateRangeSlider> for Widget {
+    fn from(w: DateRangeSlider) -> Widget {
+        Widget::DateRangeSlider(w)
+    }
+}
+
+impl From<DateRangeSlider> for LayoutDom {
+    fn from(w: DateRangeSlider) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for DateRangeSlider {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({
+            "end": self.end.millis(),
+            "start": self.start.millis(),
+            "value": [self.value.0.millis(), self.value.1.millis()],
+        });
+        if let Some(step) = self.step {
+            attributes["step"] = json!(step);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "DateRangeSlider",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+/// Properties shared by every kind of input widget
+#[derive(Clone, Default)]
+pub struct InputProperties {
+    /// Title shown above the input
+    pub title: Option<String>,
+    /// Callback run when the value changes
+    pub callback: Option<Callback>,
+    /// Size and state of the widget
+    pub widget: WidgetProperties,
+}
+
+impl InputProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(title) = &self.title {
+            attributes["title"] = json!(title);
+        }
+        if let Some(callback) = &self.callback {
+            attributes["callback"] = callback.as_bokeh_ref();
+        }
+        self.widget.add_attributes(attributes);
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out: Vec<Value> = self.callback.iter().flat_map(|c| c.references()).collect();
+        out.extend(self.widget.references());
+        out
+    }
+}
+
+/// Choice of a `Select` or `MultiSelect`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectOption {
+    /// Value shown as it is
+    Value(String),
+    /// Value, and the label shown for it
+    Labelled(String, String),
+}
+
+impl SelectOption {
+    fn value(&self) -> Value {
+        match self {
+            SelectOption::Value(value) => json!(value),
+            SelectOption::Labelled(value, label) => json!([value, label]),
+        }
+    }
+}
+
+impl From<&str> for SelectOption {
+    fn from(value: &str) -> SelectOption {
+        SelectOption::Value(value.to_string())
+    }
+}
+
+impl From<String> for SelectOption {
+    fn from(value: String) -> SelectOption {
+        SelectOption::Value(value)
+    }
+}
+
+impl From<(&str, &str)> for SelectOption {
+    fn from((value, label): (&str, &str)) -> SelectOption {
+        SelectOption::Labelled(value.to_string(), label.to_string())
+    }
+}
+
+// Select
+
+/// Drop-down list choosing one of its options
+#[derive(Clone)]
+pub struct Select {
+    id: Id,
+    /// Choices in the list
+    pub options: Vec<SelectOption>,
+    /// Value of the chosen option
+    pub value: String,
+    /// Title and callback of the input
+    pub properties: InputProperties,
+}
+
+impl Select {
+    /// Create a new list of `options` with the option `value` chosen
+    pub fn new<I, O, S>(options: I, value: S) -> Select
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<SelectOption>,
+        S: Into<String>,
+    {
+        Select::with_id(Id::new(), options, value)
+    }
+
+    /// Create a new list of `options` with the option `value` chosen and a known id
+    pub fn with_id<I, O, S>(id: Id, options: I, value: S) -> Select
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<SelectOption>,
+        S: Into<String>,
+    {
+        Select {
+            id,
+            options: options.into_iter().map(Into::into).collect(),
+            value: value.into(),
+            properties: InputProperties::default(),
+        }
+    }
+}
+
+impl From<Select> for Widget {
+    fn from(w: Select) -> Widget {
+        Widget::Select(w)
+    }
+}
+
+impl From<Select> for LayoutDom {
+    fn from(w: Select) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Select {
+    fn as_bokeh_value(&self) -> Value {
+        let options: Vec<Value> = self.options.iter().map(|o| o.value()).collect();
+        let mut attributes = json!({
+            "options": options,
+            "value": self.value,
+        });
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Select",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// MultiSelect
+
+/// List choosing any number of its options
+#[derive(Clone)]
+pub struct MultiSelect {
+    id: Id,
+    /// Choices in the list
+    pub options: Vec<SelectOption>,
+    /// Values of the chosen options
+    pub value: Vec<String>,
+    /// Number of options visible at once, 4 if not set
+    pub size: Option<u32>,
+    /// Title and callback of the input
+    pub properties: InputProperties,
+}
+
+impl MultiSelect {
+    /// Create a new list of `options` with none of them chosen
+    pub fn new<I, O>(options: I) -> MultiSelect
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<SelectOption>,
+    {
+        MultiSelect::with_id(Id::new(), options)
+    }
+
+    /// Create a new list of `options` with none of them chosen and a known id
+    pub fn with_id<I, O>(id: Id, options: I) -> MultiSelect
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<SelectOption>,
+    {
+        MultiSelect {
+            id,
+            options: options.into_iter().map(Into::into).collect(),
+            value: Vec::new(),
+            size: None,
+            properties: InputProperties::default(),
+        }
+    }
+}
+
+impl From<MultiSelect> for Widget {
+    fn from(w: MultiSelect) -> Widget {
+        Widget::MultiSelect(w)
+    }
+}
+
+impl From<MultiSelect> for LayoutDom {
+    fn from(w: MultiSelect) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for MultiSelect {
+    fn as_bokeh_value(&self) -> Value {
+        let options: Vec<Value> = self.options.iter().map(|o| o.value()).collect();
+        let mut attributes = json!({
+            "options": options,
+            "value": self.value,
+        });
+        if let Some(size) = self.size {
+            attributes["size"] = json!(size);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "MultiSelect",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+/// Colour scheme of a button
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonType {
+    /// Plain button
+    Default,
+    /// Main action, in blue
+    Primary,
+    /// Positive action, in green
+    Success,
+    /// Action needing care, in orange
+    Warning,
+    /// Destructive action, in red
+    Danger,
+    /// Button styled as a link
+    Link,
+}
+
+impl ButtonType {
+    fn name(self) -> &'static str {
+        match self {
+            ButtonType::Default => "default",
+            ButtonType::Primary => "primary",
+            ButtonType::Success => "success",
+            ButtonType::Warning => "warning",
+            ButtonType::Danger => "danger",
+            ButtonType::Link => "link",
+        }
+    }
+}
+
+/// Properties shared by every kind of button
+#[derive(Clone, Default)]
+pub struct ButtonProperties {
+    /// Colour scheme of the button, default if not set
+    pub button_type: Option<ButtonType>,
+    /// Callback run when the button is clicked
+    pub callback: Option<Callback>,
+    /// Size and state of the widget
+    pub widget: WidgetProperties,
+}
+
+impl ButtonProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(button_type) = self.button_type {
+            attributes["button_type"] = json!(button_type.name());
+        }
+        if let Some(callback) = &self.callback {
+            attributes["callback"] = callback.as_bokeh_ref();
+        }
+        self.widget.add_attributes(attributes);
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out: Vec<Value> = self.callback.iter().flat_map(|c| c.references()).collect();
+        out.extend(self.widget.references());
+        out
+    }
+}
+
+// Button
+
+/// Button running its callback when clicked
+#[derive(Clone)]
+pub struct Button {
+    id: Id,
+    /// Text on the button
+    pub label: String,
+    /// Number of times the button has been clicked
+    pub clicks: Option<u32>,
+    /// Colour scheme and callback of the button
+    pub properties: ButtonProperties,
+}
+
+impl Button {
+    /// Create a new button showing `label`
+    pub fn new<S>(label: S) -> Button
+    where
+        S: Into<String>,
+    {
+        Button::with_id(Id::new(), label)
+    }
+
+    /// Create a new button showing `label` with a known id
+    pub fn with_id<S>(id: Id, label: S) -> Button
+    where
+        S: Into<String>,
+    {
+        Button {
+            id,
+            label: label.into(),
+            clicks: None,
+            properties: ButtonProperties::default(),
+        }
+    }
+}
+
+impl From<Button> for Widget {
+    fn from(w: Button) -> Widget {
+        Widget::Button(w)
+    }
+}
+
+impl From<Button> for LayoutDom {
+    fn from(w: Button) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Button {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "label": self.label });
+        if let Some(clicks) = self.clicks {
+            attributes["clicks"] = json!(clicks);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Button",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// Toggle
+
+/// Button which stays pressed in until it is clicked again, running its callback each time
+#[derive(Clone)]
+pub struct Toggle {
+    id: Id,
+    /// Text on the button
+    pub label: String,
+    /// Whether the button is pressed in, false if not set
+    pub active: Option<bool>,
+    /// Colour scheme and callback of the button
+    pub properties: ButtonProperties,
+}
+
+impl Toggle {
+    /// Create a new toggle button showing `label`
+    pub fn new<S>(label: S) -> Toggle
+    where
+        S: Into<String>,
+    {
+        Toggle::with_id(Id::new(), label)
+    }
+
+    /// Create a new toggle button showing `label` with a known id
+    pub fn with_id<S>(id: Id, label: S) -> Toggle
+    where
+        S: Into<String>,
+    {
+        Toggle {
+            id,
+            label: label.into(),
+            active: None,
+            properties: ButtonProperties::default(),
+        }
+    }
+}
+
+impl From<Toggle> for Widget {
+    fn from(w: Toggle) -> Widget {
+        Widget::Toggle(w)
+    }
+}
+
+impl From<Toggle> for LayoutDom {
+    fn from(w: Toggle) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Toggle {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "label": self.label });
+        if let Some(active) = self.active {
+            attributes["active"] = json!(active);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Toggle",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+/// Properties shared by groups of checkboxes and radio buttons
+#[derive(Clone, Default)]
+pub struct GroupProperties {
+    /// Whether the choices are laid out side by side rather than one above the other, false if
+    /// not set
+    pub inline: Option<bool>,
+    /// Callback run when the choice changes
+    pub callback: Option<Callback>,
+    /// Size and state of the widget
+    pub widget: WidgetProperties,
+}
+
+impl GroupProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if let Some(inline) = self.inline {
+            attributes["inline"] = json!(inline);
+        }
+        if let Some(callback) = &self.callback {
+            attributes["callback"] = callback.as_bokeh_ref();
+        }
+        self.widget.add_attributes(attributes);
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out: Vec<Value> = self.callback.iter().flat_map(|c| c.references()).collect();
+        out.extend(self.widget.references());
+        out
+    }
+}
+
+// Dropdown
+
+/// Entry of the menu of a `Dropdown`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    /// Item showing a label, which sets the value of the dropdown to its value when clicked
+    Item(String, String),
+    /// Line between groups of items
+    Separator,
+}
+
+impl MenuItem {
+    fn value(&self) -> Value {
+        match self {
+            MenuItem::Item(label, value) => json!([label, value]),
+            MenuItem::Separator => Value::Null,
+        }
+    }
+}
+
+impl From<(&str, &str)> for MenuItem {
+    fn from((label, value): (&str, &str)) -> MenuItem {
+        MenuItem::Item(label.to_string(), value.to_string())
+    }
+}
+
+/// Button opening a menu of items
+///
+/// Clicking an item sets `value` to the value of the item and runs the callback, so a callback
+/// added for changes to `value` acts on the item chosen. Clicking the button itself does the
+/// same with `default_value`, if it is set, rather than opening the menu.
+#[derive(Clone)]
+pub struct Dropdown {
+    id: Id,
+    /// Text on the button
+    pub label: String,
+    /// Items of the menu, from top to bottom
+    pub menu: Vec<MenuItem>,
+    /// Value of the item last clicked
+    pub value: Option<String>,
+    /// Value set when the button itself is clicked, making it a split button
+    pub default_value: Option<String>,
+    /// Colour scheme and callback of the button
+    pub properties: ButtonProperties,
+}
+
+impl Dropdown {
+    /// Create a new button showing `label` which opens a menu of `menu`
+    pub fn new<S, I, M>(label: S, menu: I) -> Dropdown
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = M>,
+        M: Into<MenuItem>,
+    {
+        Dropdown::with_id(Id::new(), label, menu)
+    }
+
+    /// Create a new button showing `label` which opens a menu of `menu` with a known id
+    pub fn with_id<S, I, M>(id: Id, label: S, menu: I) -> Dropdown
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = M>,
+        M: Into<MenuItem>,
+    {
+        Dropdown {
+            id,
+            label: label.into(),
+            menu: menu.into_iter().map(Into::into).collect(),
+            value: None,
+            default_value: None,
+            properties: ButtonProperties::default(),
+        }
+    }
+}
+
+impl From<Dropdown> for Widget {
+    fn from(w: Dropdown) -> Widget {
+        Widget::Dropdown(w)
+    }
+}
+
+impl From<Dropdown> for LayoutDom {
+    fn from(w: Dropdown) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Dropdown {
+    fn as_bokeh_value(&self) -> Value {
+        let menu: Vec<Value> = self.menu.iter().map(MenuItem::value).collect();
+        let mut attributes = json!({
+            "label": self.label,
+            "menu": menu,
+        });
+        if let Some(value) = &self.value {
+            attributes["value"] = json!(value);
+        }
+        if let Some(default_value) = &self.default_value {
+            attributes["default_value"] = json!(default_value);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Dropdown",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// CheckboxGroup
+
+/// Group of checkboxes, any number of which can be ticked
+#[derive(Clone)]
+pub struct CheckboxGroup {
+    id: Id,
+    /// Labels of the choices
+    pub labels: Vec<String>,
+    /// Indices of the ticked choices
+    pub active: Vec<usize>,
+    /// Layout and callback of the group
+    pub properties: GroupProperties,
+}
+
+impl CheckboxGroup {
+    /// Create a new group of choices with `labels`, none of them ticked
+    pub fn new<I, S>(labels: I) -> CheckboxGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        CheckboxGroup::with_id(Id::new(), labels)
+    }
+
+    /// Create a new group of choices with `labels`, none of them ticked, with a known id
+    pub fn with_id<I, S>(id: Id, labels: I) -> CheckboxGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        CheckboxGroup {
+            id,
+            labels: labels.into_iter().map(Into::into).collect(),
+            active: Vec::new(),
+            properties: GroupProperties::default(),
+        }
+    }
+}
+
+impl From<CheckboxGroup> for Widget {
+    fn from(w: CheckboxGroup) -> Widget {
+        Widget::CheckboxGroup(w)
+    }
+}
+
+impl From<CheckboxGroup> for LayoutDom {
+    fn from(w: CheckboxGroup) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for CheckboxGroup {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "labels": self.labels });
+        if !self.active.is_empty() {
+            attributes["active"] = json!(self.active);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "CheckboxGroup",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// RadioGroup
+
+/// Group of radio buttons, one of which can be chosen
+#[derive(Clone)]
+pub struct RadioGroup {
+    id: Id,
+    /// Labels of the choices
+    pub labels: Vec<String>,
+    /// Index of the chosen choice, if any
+    pub active: Option<usize>,
+    /// Layout and callback of the group
+    pub properties: GroupProperties,
+}
+
+impl RadioGroup {
+    /// Create a new group of choices with `labels`, none of them chosen
+    pub fn new<I, S>(labels: I) -> RadioGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        RadioGroup::with_id(Id::new(), labels)
+    }
+
+    /// Create a new group of choices with `labels`, none of them chosen, with a known id
+    pub fn with_id<I, S>(id: Id, labels: I) -> RadioGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        RadioGroup {
+            id,
+            labels: labels.into_iter().map(Into::into).collect(),
+            active: None,
+            properties: GroupProperties::default(),
+        }
+    }
+}
+
+impl From<RadioGroup> for Widget {
+    fn from(w: RadioGroup) -> Widget {
+        Widget::RadioGroup(w)
+    }
+}
+
+impl From<RadioGroup> for LayoutDom {
+    fn from(w: RadioGroup) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for RadioGroup {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "labels": self.labels });
+        if let Some(active) = self.active {
+            attributes["active"] = json!(active);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "RadioGroup",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// CheckboxButtonGroup
+
+/// Row of buttons, any number of which can be pressed in
+#[derive(Clone)]
+pub struct CheckboxButtonGroup {
+    id: Id,
+    /// Labels of the choices
+    pub labels: Vec<String>,
+    /// Indices of the pressed buttons
+    pub active: Vec<usize>,
+    /// Colour scheme and callback of the buttons
+    pub properties: ButtonProperties,
+}
+
+impl CheckboxButtonGroup {
+    /// Create a new group of choices with `labels`, none of them pressed
+    pub fn new<I, S>(labels: I) -> CheckboxButtonGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        CheckboxButtonGroup::with_id(Id::new(), labels)
+    }
+
+    /// Create a new group of choices with `labels`, none of them pressed, with a known id
+    pub fn with_id<I, S>(id: Id, labels: I) -> CheckboxButtonGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        CheckboxButtonGroup {
+            id,
+            labels: labels.into_iter().map(Into::into).collect(),
+            active: Vec::new(),
+            properties: ButtonProperties::default(),
+        }
+    }
+}
+
+impl From<CheckboxButtonGroup> for Widget {
+    fn from(w: CheckboxButtonGroup) -> Widget {
+        Widget::CheckboxButtonGroup(w)
+    }
+}
+
+impl From<CheckboxButtonGroup> for LayoutDom {
+    fn from(w: CheckboxButtonGroup) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for CheckboxButtonGroup {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "labels": self.labels });
+        if !self.active.is_empty() {
+            attributes["active"] = json!(self.active);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "CheckboxButtonGroup",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// RadioButtonGroup
+
+/// Row of buttons, one of which can be pressed in
+#[derive(Clone)]
+pub struct RadioButtonGroup {
+    id: Id,
+    /// Labels of the choices
+    pub labels: Vec<String>,
+    /// Index of the pressed button, if any
+    pub active: Option<usize>,
+    /// Colour scheme and callback of the buttons
+    pub properties: ButtonProperties,
+}
+
+impl RadioButtonGroup {
+    /// Create a new group of choices with `labels`, none of them pressed
+    pub fn new<I, S>(labels: I) -> RadioButtonGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        RadioButtonGroup::with_id(Id::new(), labels)
+    }
+
+    /// Create a new group of choices with `labels`, none of them pressed, with a known id
+    pub fn with_id<I, S>(id: Id, labels: I) -> RadioButtonGroup
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        RadioButtonGroup {
+            id,
+            labels: labels.into_iter().map(Into::into).collect(),
+            active: None,
+            properties: ButtonProperties::default(),
+        }
+    }
+}
+
+impl From<RadioButtonGroup> for Widget {
+    fn from(w: RadioButtonGroup) -> Widget {
+        Widget::RadioButtonGroup(w)
+    }
+}
+
+impl From<RadioButtonGroup> for LayoutDom {
+    fn from(w: RadioButtonGroup) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for RadioButtonGroup {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "labels": self.labels });
+        if let Some(active) = self.active {
+            attributes["active"] = json!(active);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "RadioButtonGroup",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// TextInput
+
+/// Single line of free text
+#[derive(Clone, Default)]
+pub struct TextInput {
+    id: Id,
+    /// Current text
+    pub value: String,
+    /// Hint shown while the input is empty
+    pub placeholder: Option<String>,
+    /// Title and callback of the input
+    pub properties: InputProperties,
+}
+
+impl TextInput {
+    /// Create a new empty text input
+    pub fn new() -> TextInput {
+        TextInput::default()
+    }
+
+    /// Create a new empty text input with a known id
+    pub fn with_id(id: Id) -> TextInput {
+        TextInput {
+            id,
+            ..TextInput::default()
+        }
+    }
+}
+
+impl From<TextInput> for Widget {
+    fn from(w: TextInput) -> Widget {
+        Widget::TextInput(w)
+    }
+}
+
+impl From<TextInput> for LayoutDom {
+    fn from(w: TextInput) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for TextInput {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "value": self.value });
+        if let Some(placeholder) = &self.placeholder {
+            attributes["placeholder"] = json!(placeholder);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "TextInput",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+// DatePicker
+
+/// Calendar choosing a single date
+///
+/// Dates are sent to BokehJS as ISO 8601 dates, e.g. `2019-01-31`, dropping any time of day.
+#[derive(Clone)]
+pub struct DatePicker {
+    id: Id,
+    /// Chosen date
+    pub value: Date,
+    /// Earliest date which can be chosen
+    pub min_date: Option<Date>,
+    /// Latest date which can be chosen
+    pub max_date: Option<Date>,
+    /// Title and callback of the input
+    pub properties: InputProperties,
+}
+
+impl DatePicker {
+    /// Create a new calendar with the date `value` chosen
+    pub fn new<D>(value: D) -> DatePicker
+    where
+        D: Into<Date>,
+    {
+        DatePicker::with_id(Id::new(), value)
+    }
+
+    /// Create a new calendar with the date `value` chosen and a known id
+    pub fn with_id<D>(id: Id, value: D) -> DatePicker
+    where
+        D: Into<Date>,
+    {
+        DatePicker {
+            id,
+            value: value.into(),
+            min_date: None,
+            max_date: None,
+            properties: InputProperties::default(),
+        }
+    }
+}
+
+impl From<DatePicker> for Widget {
+    fn from(w: DatePicker) -> Widget {
+        Widget::DatePicker(w)
+    }
+}
+
+impl From<DatePicker> for LayoutDom {
+    fn from(w: DatePicker) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for DatePicker {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "value": self.value.to_iso_date() });
+        if let Some(min_date) = self.min_date {
+            attributes["min_date"] = json!(min_date.to_iso_date());
+        }
+        if let Some(max_date) = self.max_date {
+            attributes["max_date"] = json!(max_date.to_iso_date());
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "DatePicker",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.references());
+        out
+    }
+}
+
+/// Properties shared by every kind of markup
+#[derive(Clone, Debug, Default)]
+pub struct MarkupProperties {
+    /// CSS styles of the text, by property name, e.g. `font-size` to `150%`
+    pub style: BTreeMap<String, String>,
+    /// Size and state of the widget
+    pub widget: WidgetProperties,
+}
+
+impl MarkupProperties {
+    fn add_attributes(&self, attributes: &mut Value) {
+        if !self.style.is_empty() {
+            attributes["style"] = json!(self.style);
+        }
+        self.widget.add_attributes(attributes);
+    }
+}
+
+// Div
+
+/// Block of HTML
+#[derive(Clone)]
+pub struct Div {
+    id: Id,
+    /// Text shown
+    pub text: String,
+    /// Whether the text is shown as it is rather than as HTML, false if not set
+    pub render_as_text: Option<bool>,
+    /// Style and size of the text
+    pub properties: MarkupProperties,
+}
+
+impl Div {
+    /// Create a new block of `text`
+    pub fn new<S>(text: S) -> Div
+    where
+        S: Into<String>,
+    {
+        Div::with_id(Id::new(), text)
+    }
+
+    /// Create a new block of `text` with a known id
+    pub fn with_id<S>(id: Id, text: S) -> Div
+    where
+        S: Into<String>,
+    {
+        Div {
+            id,
+            text: text.into(),
+            render_as_text: None,
+            properties: MarkupProperties::default(),
+        }
+    }
+}
+
+impl From<Div> for Widget {
+    fn from(w: Div) -> Widget {
+        Widget::Div(w)
+    }
+}
+
+impl From<Div> for LayoutDom {
+    fn from(w: Div) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Div {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "text": self.text });
+        if let Some(render_as_text) = self.render_as_text {
+            attributes["render_as_text"] = json!(render_as_text);
+        }
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Div",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.widget.references());
+        out
+    }
+}
+
+// Paragraph
+
+/// Paragraph of plain text
+#[derive(Clone)]
+pub struct Paragraph {
+    id: Id,
+    /// Text shown
+    pub text: String,
+    /// Style and size of the text
+    pub properties: MarkupProperties,
+}
+
+impl Paragraph {
+    /// Create a new block of `text`
+    pub fn new<S>(text: S) -> Paragraph
+    where
+        S: Into<String>,
+    {
+        Paragraph::with_id(Id::new(), text)
+    }
+
+    /// Create a new block of `text` with a known id
+    pub fn with_id<S>(id: Id, text: S) -> Paragraph
+    where
+        S: Into<String>,
+    {
+        Paragraph {
+            id,
+            text: text.into(),
+            properties: MarkupProperties::default(),
+        }
+    }
+}
+
+impl From<Paragraph> for Widget {
+    fn from(w: Paragraph) -> Widget {
+        Widget::Paragraph(w)
+    }
+}
+
+impl From<Paragraph> for LayoutDom {
+    fn from(w: Paragraph) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for Paragraph {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "text": self.text });
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "Paragraph",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.widget.references());
+        out
+    }
+}
+
+// PreText
+
+/// Block of preformatted text, in a fixed width font with its whitespace kept
+#[derive(Clone)]
+pub struct PreText {
+    id: Id,
+    /// Text shown
+    pub text: String,
+    /// Style and size of the text
+    pub properties: MarkupProperties,
+}
+
+impl PreText {
+    /// Create a new block of `text`
+    pub fn new<S>(text: S) -> PreText
+    where
+        S: Into<String>,
+    {
+        PreText::with_id(Id::new(), text)
+    }
+
+    /// Create a new block of `text` with a known id
+    pub fn with_id<S>(id: Id, text: S) -> PreText
+    where
+        S: Into<String>,
+    {
+        PreText {
+            id,
+            text: text.into(),
+            properties: MarkupProperties::default(),
+        }
+    }
+}
+
+impl From<PreText> for Widget {
+    fn from(w: PreText) -> Widget {
+        Widget::PreText(w)
+    }
+}
+
+impl From<PreText> for LayoutDom {
+    fn from(w: PreText) -> LayoutDom {
+        LayoutDom::Widget(Box::new(w.into()))
+    }
+}
+
+impl ToBokeh for PreText {
+    fn as_bokeh_value(&self) -> Value {
+        let mut attributes = json!({ "text": self.text });
+        self.properties.add_attributes(&mut attributes);
+
+        json!({
+            "attributes": attributes,
+            "id": self.id,
+            "type": "PreText",
+        })
+    }
+
+    fn references(&self) -> Vec<Value> {
+        let mut out = vec![self.as_bokeh_value()];
+        out.extend(self.properties.widget.references());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::column;
+    use crate::{CustomJS, Document};
+
+    #[test]
+    fn test_slider() {
+        let mut slider = Slider::with_id(Id::from("1001"), 0.0, 10.0, 2.5);
+        slider.step = Some(0.5);
+        slider.properties.title = Some("Amplitude".to_string());
+        slider.properties.callback = Some(CustomJS::with_id(Id::from("1002"), "").into());
+        slider.properties.callback_policy = Some(CallbackPolicy::Mouseup);
+        slider.properties.widget.width = Some(300);
+
+        let references = Widget::from(slider).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "callback": {"id": "1002", "type": "CustomJS"},
+                    "callback_policy": "mouseup",
+                    "end": 10.0,
+                    "start": 0.0,
+                    "step": 0.5,
+                    "title": "Amplitude",
+                    "value": 2.5,
+                    "width": 300,
+                },
+                "id": "1001",
+                "type": "Slider",
+            })
+        );
+        assert_eq!(references[1]["type"], "CustomJS");
+    }
+
+    #[test]
+    fn test_range_slider() {
+        let mut slider = RangeSlider::with_id(Id::from("1001"), 0.0, 100.0, (20.0, 40.0));
+        slider.step = Some(5.0);
+        slider.properties.format = Some("0".to_string());
+        assert_eq!(
+            Widget::from(slider).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "end": 100.0,
+                    "format": "0",
+                    "start": 0.0,
+                    "step": 5.0,
+                    "value": [20.0, 40.0],
+                },
+                "id": "1001",
+                "type": "RangeSlider",
+            })
+        );
+    }
+
+    #[test]
+    fn test_date_sliders() {
+        // 2019-01-01 and 2019-12-31
+        let (start, end) = (1_546_300_800_000.0, 1_577_750_400_000.0);
+        let mut slider = DateSlider::with_id(Id::from("1001"), start, end, start);
+        slider.step = Some(7);
+        slider.properties.format = Some("%b %Y".to_string());
+        assert_eq!(
+            Widget::from(slider).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "end": end,
+                    "format": "%b %Y",
+                    "start": start,
+                    "step": 7,
+                    "value": start,
+                },
+                "id": "1001",
+                "type": "DateSlider",
+            })
+        );
+
+        let slider = DateRangeSlider::with_id(Id::from("1002"), start, end, (start, end));
+        let value = Widget::from(slider).as_bokeh_value();
+        assert_eq!(value["type"], "DateRangeSlider");
+        assert_eq!(value["attributes"]["value"], json!([start, end]));
+
+        let slider = DateSlider::new(
+            "2019-01-01".parse::<Date>().unwrap(),
+            "2019-12-31".parse().unwrap(),
+            "2019-01-01".parse().unwrap(),
+        );
+        assert_eq!(slider.start.millis(), start);
+        assert_eq!(slider.end.millis(), end);
+    }
+
+    #[test]
+    fn test_dates() {
+        let date: Date = "2019-01-31".parse().unwrap();
+        assert_eq!(date.millis(), 1_548_892_800_000.0);
+        assert_eq!(date.to_iso_date(), "2019-01-31");
+        assert_eq!(Date::from(0.0).to_iso_date(), "1970-01-01");
+        assert_eq!(Date::from(-1.0).to_iso_date(), "1969-12-31");
+        assert_eq!(
+            "2020-02-29".parse::<Date>().unwrap().to_iso_date(),
+            "2020-02-29"
+        );
+
+        for invalid in &[
+            "2019-02-29",
+            "2019-13-01",
+            "2019-1-31",
+            "31/01/2019",
+            "2019-01-31T00",
+        ] {
+            assert!(invalid.parse::<Date>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_dates() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        let date = NaiveDate::from_ymd_opt(2019, 1, 31).unwrap();
+        assert_eq!(Date::from(date), "2019-01-31".parse().unwrap());
+        let datetime = Utc.with_ymd_and_hms(2019, 1, 31, 12, 0, 0).unwrap();
+        assert_eq!(Date::from(datetime).millis(), 1_548_936_000_000.0);
+    }
+
+    #[test]
+    fn test_select() {
+        let mut select = Select::with_id(Id::from("1001"), vec!["red", "green"], "green");
+        select.options.push(("#0000ff", "blue").into());
+        select.properties.title = Some("Colour".to_string());
+        assert_eq!(
+            Widget::from(select).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "options": ["red", "green", ["#0000ff", "blue"]],
+                    "title": "Colour",
+                    "value": "green",
+                },
+                "id": "1001",
+                "type": "Select",
+            })
+        );
+    }
+
+    #[test]
+    fn test_multi_select() {
+        let mut select = MultiSelect::with_id(Id::from("1001"), vec![("a", "A"), ("b", "B")]);
+        select.value = vec!["b".to_string()];
+        select.size = Some(2);
+        assert_eq!(
+            Widget::from(select).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "options": [["a", "A"], ["b", "B"]],
+                    "size": 2,
+                    "value": ["b"],
+                },
+                "id": "1001",
+                "type": "MultiSelect",
+            })
+        );
+    }
+
+    #[test]
+    fn test_button() {
+        let mut button = Button::with_id(Id::from("1001"), "Reset");
+        button.properties.button_type = Some(ButtonType::Danger);
+        button.properties.callback = Some(CustomJS::with_id(Id::from("1002"), "").into());
+
+        let references = Widget::from(button).references();
+        assert_eq!(
+            references[0],
+            json!({
+                "attributes": {
+                    "button_type": "danger",
+                    "callback": {"id": "1002", "type": "CustomJS"},
+                    "label": "Reset",
+                },
+                "id": "1001",
+                "type": "Button",
+            })
+        );
+        assert_eq!(references[1]["type"], "CustomJS");
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut toggle = Toggle::with_id(Id::from("1001"), "Live");
+        toggle.active = Some(true);
+        assert_eq!(
+            Widget::from(toggle).as_bokeh_value(),
+            json!({
+                "attributes": {"active": true, "label": "Live"},
+                "id": "1001",
+                "type": "Toggle",
+            })
+        );
+    }
+
+    #[test]
+    fn test_dropdown() {
+        let menu = vec![
+            ("Export CSV", "csv").into(),
+            ("Export JSON", "json").into(),
+            MenuItem::Separator,
+            ("Clear", "clear").into(),
+        ];
+        let mut dropdown = Dropdown::with_id(Id::from("1001"), "Actions", menu);
+        dropdown.default_value = Some("csv".to_string());
+        dropdown.properties.button_type = Some(ButtonType::Primary);
+        assert_eq!(
+            Widget::from(dropdown).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "button_type": "primary",
+                    "default_value": "csv",
+                    "label": "Actions",
+                    "menu": [
+                        ["Export CSV", "csv"],
+                        ["Export JSON", "json"],
+                        null,
+                        ["Clear", "clear"],
+                    ],
+                },
+                "id": "1001",
+                "type": "Dropdown",
+            })
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut checkboxes = CheckboxGroup::with_id(Id::from("1001"), vec!["x", "y", "z"]);
+        checkboxes.active = vec![0, 2];
+        checkboxes.properties.inline = Some(true);
+        assert_eq!(
+            Widget::from(checkboxes).as_bokeh_value(),
+            json!({
+                "attributes": {"active": [0, 2], "inline": true, "labels": ["x", "y", "z"]},
+                "id": "1001",
+                "type": "CheckboxGroup",
+            })
+        );
+
+        let mut radio = RadioGroup::with_id(Id::from("1002"), vec!["x", "y"]);
+        radio.active = Some(1);
+        assert_eq!(
+            Widget::from(radio).as_bokeh_value()["attributes"],
+            json!({"active": 1, "labels": ["x", "y"]})
+        );
+    }
+
+    #[test]
+    fn test_button_groups() {
+        let mut buttons = CheckboxButtonGroup::with_id(Id::from("1001"), vec!["a", "b"]);
+        buttons.active = vec![1];
+        buttons.properties.button_type = Some(ButtonType::Primary);
+        assert_eq!(
+            Widget::from(buttons).as_bokeh_value(),
+            json!({
+                "attributes": {"active": [1], "button_type": "primary", "labels": ["a", "b"]},
+                "id": "1001",
+                "type": "CheckboxButtonGroup",
+            })
+        );
+
+        let buttons = RadioButtonGroup::with_id(Id::from("1002"), vec!["a", "b"]);
+        let value = Widget::from(buttons).as_bokeh_value();
+        assert_eq!(value["type"], "RadioButtonGroup");
+        assert_eq!(value["attributes"], json!({"labels": ["a", "b"]}));
+    }
+
+    #[test]
+    fn test_text_input() {
+        let mut input = TextInput::with_id(Id::from("1001"));
+        input.placeholder = Some("Search".to_string());
+        input.properties.title = Some("Filter".to_string());
+        assert_eq!(
+            Widget::from(input).as_bokeh_value(),
+            json!({
+                "attributes": {"placeholder": "Search", "title": "Filter", "value": ""},
+                "id": "1001",
+                "type": "TextInput",
+            })
+        );
+    }
+
+    #[test]
+    fn test_date_picker() {
+        let value: Date = "2019-03-01".parse().unwrap();
+        let mut picker = DatePicker::with_id(Id::from("1001"), value);
+        picker.min_date = Some("2019-01-01".parse().unwrap());
+        // Times of day are dropped
+        picker.max_date = Some(Date::from(1_577_793_599_000.0));
+        assert_eq!(
+            Widget::from(picker).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "max_date": "2019-12-31",
+                    "min_date": "2019-01-01",
+                    "value": "2019-03-01",
+                },
+                "id": "1001",
+                "type": "DatePicker",
+            })
+        );
+    }
+
+    #[test]
+    fn test_markups() {
+        let mut div = Div::with_id(Id::from("1001"), "<b>Totals</b>");
+        div.properties
+            .style
+            .insert("font-size".to_string(), "150%".to_string());
+        div.properties.widget.width = Some(200);
+        assert_eq!(
+            Widget::from(div).as_bokeh_value(),
+            json!({
+                "attributes": {
+                    "style": {"font-size": "150%"},
+                    "text": "<b>Totals</b>",
+                    "width": 200,
+                },
+                "id": "1001",
+                "type": "Div",
+            })
+        );
+
+        let paragraph = Paragraph::with_id(Id::from("1002"), "Notes");
+        assert_eq!(
+            Widget::from(paragraph).as_bokeh_value(),
+            json!({"attributes": {"text": "Notes"}, "id": "1002", "type": "Paragraph"})
+        );
+        let pre = PreText::with_id(Id::from("1003"), "a  b");
+        assert_eq!(Widget::from(pre).as_bokeh_value()["type"], "PreText");
+    }
+
+    #[test]
+    fn test_widget_document_root() {
+        let mut doc = Document::new();
+        doc.add_root(Slider::with_id(Id::from("1001"), 0.0, 1.0, 0.0));
+        let doc = doc.validate().unwrap();
+        assert_eq!(doc.root_ids(), vec!["1001"]);
+
+        let mut layout = LayoutDom::from(column(vec![Slider::new(0.0, 1.0, 0.0)]));
+        layout.set_sizing_mode(SizingMode::ScaleWidth);
+        assert_eq!(
+            layout.references()[1]["attributes"]["sizing_mode"],
+            "scale_width"
+        );
+    }
+}
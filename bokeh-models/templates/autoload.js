@@ -0,0 +1,50 @@
+(function() {
+    var element = document.getElementById({{ element_id|safe }});
+    if (element == null) {
+        console.error("Bokeh: autoload.js configured with elementid " + {{ element_id|safe }} + " but no matching script tag was found.");
+        return;
+    }
+
+    function embed() {
+        var root = document.createElement("div");
+        root.className = "bk-root";
+        root.id = {{ element_id|safe }} + "-root";
+        element.parentNode.insertBefore(root, element.nextSibling);
+
+        var render_items = [{"elementid": root.id, "sessionid": {{ session_id|safe }}, "use_for_title": false}];
+        Bokeh.embed.embed_items(null, render_items, {{ app_path|safe }}, {{ absolute_url|safe }});
+    }
+
+    if (window.Bokeh !== undefined) {
+        embed();
+        return;
+    }
+
+    var release = "https://cdn.pydata.org/bokeh/release/";
+    var bundles = ["bokeh", "bokeh-widgets", "bokeh-tables"];
+    bundles.forEach(function(bundle) {
+        var link = document.createElement("link");
+        link.rel = "stylesheet";
+        link.type = "text/css";
+        link.href = release + bundle + "-{{ crate::BOKEH_VERSION }}.min.css";
+        document.head.appendChild(link);
+    });
+
+    // Each bundle registers its models with the core one, so they are loaded in order
+    function load(index) {
+        if (index == bundles.length) {
+            embed();
+            return;
+        }
+        var script = document.createElement("script");
+        script.src = release + bundles[index] + "-{{ crate::BOKEH_VERSION }}.min.js";
+        script.onload = function() {
+            load(index + 1);
+        };
+        script.onerror = function() {
+            console.error("Bokeh: failed to load BokehJS from " + script.src);
+        };
+        document.head.appendChild(script);
+    }
+    load(0);
+})();
@@ -5,6 +5,7 @@ use askama::Template;
 type Guid = String;
 
 // Rendering the output HTML
+#[allow(dead_code)]
 #[derive(Template)]
 #[template(path = "index.html")]
 struct PageTemplate {
@@ -30,7 +31,7 @@ mod tests {
             plot_guid: "398f0a3d-51fc-4aff-9df6-e569ebbc486e".to_string(),
             plot_data: plot_data.to_string(),
         };
-        let text = format!("{}", page.render().unwrap());
+        let text = page.render().unwrap();
         // TODO: better check
         assert!(text.contains("root.Bokeh.embed.embed_items"));
     }